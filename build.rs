@@ -1,7 +1,19 @@
+use std::process::Command;
+
 fn main() {
     glib_build_tools::compile_resources(
         &["resources"],
         "resources/resources.gresource.xml",
         "lon.gresource",
     );
+
+    // Compile the GSettings schema into OUT_DIR too, so an uninstalled
+    // `cargo run` can fall back to it (see `settings::settings`)
+    println!("cargo:rerun-if-changed=data/dev.myyc.lon.gschema.xml");
+    let out_dir = std::env::var("OUT_DIR").expect("OUT_DIR is set by cargo");
+    let status = Command::new("glib-compile-schemas")
+        .args(["--strict", "--targetdir", &out_dir, "data"])
+        .status()
+        .expect("Failed to run glib-compile-schemas");
+    assert!(status.success(), "glib-compile-schemas failed");
 }
@@ -55,6 +55,7 @@ mod imp {
 
             app.add_action_entries([quit_action, about_action]);
             app.set_accels_for_action("app.quit", &["<primary>q"]);
+            app.set_accels_for_action("win.show-preferences", &["<primary>comma"]);
         }
     }
 
@@ -0,0 +1,193 @@
+use super::conversion::{cmyk_to_rgb, hsv_to_rgb, lab_d50_to_rgb};
+use super::model::{Cmyk, Hsv, Lab, PantoneColor, Rgb};
+use super::palette::Palette;
+use super::swatch_file::{utf16_be, ByteReader, SwatchFileError};
+
+const RGB: u16 = 0;
+const HSB: u16 = 1;
+const CMYK: u16 = 2;
+const LAB: u16 = 7;
+const GRAYSCALE: u16 = 8;
+
+/// Read a Photoshop swatches file. Version 2 files carry names in a second
+/// section after the version 1 colours; without it colours are named by their
+/// hex value. Swatches in colour spaces lon cannot convert, such as Pantone
+/// book references, are skipped.
+pub fn read(bytes: &[u8], name: &str) -> Result<Palette, SwatchFileError> {
+    let mut reader = ByteReader::new(bytes);
+    let mut version = reader.u16()?;
+    if version != 1 && version != 2 {
+        return Err(SwatchFileError::WrongFormat);
+    }
+    let mut colors = read_section(&mut reader, version)?;
+    if version == 1 && !reader.is_at_end() {
+        version = reader.u16()?;
+        if version == 2 {
+            colors = read_section(&mut reader, version)?;
+        }
+    }
+
+    let mut palette = Palette::new(name);
+    for color in colors {
+        palette.push(color, "");
+    }
+    Ok(palette)
+}
+
+fn read_section(
+    reader: &mut ByteReader,
+    version: u16,
+) -> Result<Vec<PantoneColor>, SwatchFileError> {
+    let count = reader.u16()?;
+    let mut colors = Vec::new();
+    for _ in 0..count {
+        let space = reader.u16()?;
+        let values = [reader.u16()?, reader.u16()?, reader.u16()?, reader.u16()?];
+        let name = if version == 2 {
+            let len = reader.u32()? as usize;
+            reader.utf16(len)?
+        } else {
+            String::new()
+        };
+        let Some(rgb) = to_rgb(space, values) else {
+            continue;
+        };
+        let mut color = PantoneColor::custom(&name, rgb);
+        if color.name.is_empty() {
+            color.name = color.hex.clone();
+        }
+        colors.push(color);
+    }
+    Ok(colors)
+}
+
+/// A swatch's four 16-bit values in `space` as sRGB.
+fn to_rgb(space: u16, [w, x, y, z]: [u16; 4]) -> Option<Rgb> {
+    let fraction = |value: u16| value as f32 / u16::MAX as f32;
+    match space {
+        RGB => Some(Rgb {
+            r: (w as f32 / 257.0).round() as u8,
+            g: (x as f32 / 257.0).round() as u8,
+            b: (y as f32 / 257.0).round() as u8,
+        }),
+        HSB => Some(hsv_to_rgb(&Hsv {
+            h: fraction(w) * 360.0,
+            s: fraction(x) * 100.0,
+            v: fraction(y) * 100.0,
+        })),
+        // Ink coverage is stored inverted: 0 is full ink
+        CMYK => Some(cmyk_to_rgb(&Cmyk {
+            c: (1.0 - fraction(w)) * 100.0,
+            m: (1.0 - fraction(x)) * 100.0,
+            y: (1.0 - fraction(y)) * 100.0,
+            k: (1.0 - fraction(z)) * 100.0,
+        })),
+        // D50, with lightness in hundredths and a and b signed
+        LAB => Some(lab_d50_to_rgb(&Lab {
+            l: w as f32 / 100.0,
+            a: x as i16 as f32 / 100.0,
+            b: y as i16 as f32 / 100.0,
+        })),
+        // Black ink in hundredths of a percent
+        GRAYSCALE => Some(cmyk_to_rgb(&Cmyk {
+            c: 0.0,
+            m: 0.0,
+            y: 0.0,
+            k: w.min(10000) as f32 / 100.0,
+        })),
+        _ => None,
+    }
+}
+
+/// Write `palettes` as one list of RGB swatches, since the format has no
+/// groups: a version 1 section for older readers, then version 2 with names.
+pub fn write(palettes: &[Palette]) -> Vec<u8> {
+    let colors: Vec<&PantoneColor> = palettes
+        .iter()
+        .flat_map(|palette| &palette.entries)
+        .map(|entry| &entry.color)
+        .collect();
+
+    let mut bytes = Vec::new();
+    for version in [1u16, 2] {
+        bytes.extend_from_slice(&version.to_be_bytes());
+        bytes.extend_from_slice(&(colors.len() as u16).to_be_bytes());
+        for color in &colors {
+            bytes.extend_from_slice(&RGB.to_be_bytes());
+            for channel in [color.rgb.r, color.rgb.g, color.rgb.b, 0] {
+                bytes.extend_from_slice(&(channel as u16 * 257).to_be_bytes());
+            }
+            if version == 2 {
+                let (len, name) = utf16_be(&color.full_name());
+                bytes.extend_from_slice(&(len as u32).to_be_bytes());
+                bytes.extend_from_slice(&name);
+            }
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::conversion::hex_to_rgb;
+
+    fn entry(space: u16, values: [u16; 4]) -> Vec<u8> {
+        let mut bytes = space.to_be_bytes().to_vec();
+        for value in values {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        bytes
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut palette = Palette::new("Brand");
+        palette.push(
+            PantoneColor::custom("Harbour", hex_to_rgb("#1d4e89").unwrap()),
+            "",
+        );
+        palette.push(
+            PantoneColor::custom("Blåbær", hex_to_rgb("#4f86f7").unwrap()),
+            "",
+        );
+
+        let parsed = read(&write(&[palette.clone()]), "brand").unwrap();
+        assert_eq!(parsed.name, "brand");
+        assert_eq!(parsed.entries.len(), 2);
+        for (parsed, original) in parsed.entries.iter().zip(&palette.entries) {
+            assert_eq!(parsed.color.name, original.color.name);
+            assert_eq!(parsed.color.hex, original.color.hex);
+        }
+    }
+
+    #[test]
+    fn test_read_colour_spaces() {
+        // Version 1 only, so colours are named by value
+        let mut bytes = vec![0, 1, 0, 5];
+        bytes.extend(entry(RGB, [0xffff, 0, 0, 0]));
+        bytes.extend(entry(CMYK, [0xffff, 0x8000, 0, 0xffff]));
+        bytes.extend(entry(LAB, [5429, 8080, 6989, 0]));
+        bytes.extend(entry(GRAYSCALE, [10000, 0, 0, 0]));
+        // A Pantone book reference, which is skipped
+        bytes.extend(entry(3, [286, 0, 0, 0]));
+
+        let palette = read(&bytes, "spaces").unwrap();
+        let hexes: Vec<&str> = palette
+            .entries
+            .iter()
+            .map(|entry| entry.color.hex.as_str())
+            .collect();
+        assert_eq!(hexes, ["#ff0000", "#ff8000", "#ff0000", "#000000"]);
+        assert_eq!(palette.entries[0].color.name, "#ff0000");
+
+        assert_eq!(
+            read(&bytes[..bytes.len() - 3], "x").unwrap_err(),
+            SwatchFileError::Truncated
+        );
+        assert_eq!(
+            read(b"ASEF", "x").unwrap_err(),
+            SwatchFileError::WrongFormat
+        );
+    }
+}
@@ -0,0 +1,241 @@
+use super::model::{ColorLibrary, PantoneColor};
+use super::versioned::Versioned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Identifies an annotated colour by library and code. TCX colours carry no
+/// code, and custom colours' codes need not be unique, so both go by name.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AnnotationKey {
+    pub library: String,
+    pub code: String,
+}
+
+impl AnnotationKey {
+    pub fn for_color(color: &PantoneColor) -> Self {
+        let code = match color.library {
+            ColorLibrary::Custom => None,
+            _ => color.code(),
+        };
+        Self {
+            library: color.library.id().to_string(),
+            code: code.unwrap_or(&color.name).to_string(),
+        }
+    }
+}
+
+/// The user's own notes and tags on a colour.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    #[serde(default)]
+    pub notes: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Annotation {
+    pub fn is_empty(&self) -> bool {
+        self.notes.is_empty() && self.tags.is_empty()
+    }
+
+    /// Add `tag` unless it is blank or already there, ignoring case. Returns
+    /// whether it was added.
+    pub fn add_tag(&mut self, tag: &str) -> bool {
+        let tag = tag.trim();
+        if tag.is_empty() || self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            return false;
+        }
+        self.tags.push(tag.to_string());
+        true
+    }
+
+    pub fn remove_tag(&mut self, tag: &str) {
+        self.tags.retain(|t| t != tag);
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct AnnotatedColor {
+    #[serde(flatten)]
+    key: AnnotationKey,
+    #[serde(flatten)]
+    annotation: Annotation,
+}
+
+/// Every annotation the user has made, as stored on disk.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Annotations {
+    colors: Vec<AnnotatedColor>,
+}
+
+impl Versioned for Annotations {
+    const KIND: &'static str = "annotations";
+    const VERSION: u32 = 1;
+}
+
+impl Annotations {
+    pub fn get(&self, key: &AnnotationKey) -> Option<&Annotation> {
+        self.colors
+            .iter()
+            .find(|entry| entry.key == *key)
+            .map(|entry| &entry.annotation)
+    }
+
+    /// Change the annotation on `key` with `f`, forgetting it once it is empty.
+    pub fn update(&mut self, key: &AnnotationKey, f: impl FnOnce(&mut Annotation)) {
+        let index = match self.colors.iter().position(|entry| entry.key == *key) {
+            Some(index) => index,
+            None => {
+                self.colors.push(AnnotatedColor {
+                    key: key.clone(),
+                    annotation: Annotation::default(),
+                });
+                self.colors.len() - 1
+            }
+        };
+        f(&mut self.colors[index].annotation);
+        if self.colors[index].annotation.is_empty() {
+            self.colors.remove(index);
+        }
+    }
+
+    /// Move the annotation on `from` to `to`, e.g. after a custom colour was renamed.
+    pub fn rename(&mut self, from: &AnnotationKey, to: &AnnotationKey) {
+        if from == to || self.get(to).is_some() {
+            return;
+        }
+        if let Some(entry) = self.colors.iter_mut().find(|entry| entry.key == *from) {
+            entry.key = to.clone();
+        }
+    }
+
+    /// The tags on every tagged colour.
+    pub fn tags_by_key(&self) -> HashMap<AnnotationKey, Vec<String>> {
+        self.colors
+            .iter()
+            .filter(|entry| !entry.annotation.tags.is_empty())
+            .map(|entry| (entry.key.clone(), entry.annotation.tags.clone()))
+            .collect()
+    }
+}
+
+/// A search box query. `tag:approved` or `tag:"client x"` keeps colours with
+/// a tag starting with that text; the rest matches as in
+/// `PantoneColor::matches_query`, or against a tag.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TagQuery {
+    pub text: String,
+    pub tags: Vec<String>,
+}
+
+impl TagQuery {
+    pub fn parse(query: &str) -> Self {
+        let mut text = Vec::new();
+        let mut tags = Vec::new();
+        let mut rest = query.trim();
+        while !rest.is_empty() {
+            if let Some(after) = rest.strip_prefix("tag:") {
+                let (tag, remainder) = match after.strip_prefix('"') {
+                    Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+                    None => after.split_once(' ').unwrap_or((after, "")),
+                };
+                if !tag.trim().is_empty() {
+                    tags.push(tag.trim().to_lowercase());
+                }
+                rest = remainder.trim_start();
+            } else {
+                let (word, remainder) = rest.split_once(' ').unwrap_or((rest, ""));
+                text.push(word);
+                rest = remainder.trim_start();
+            }
+        }
+        Self {
+            text: text.join(" "),
+            tags,
+        }
+    }
+
+    /// Whether `color`, tagged with `tags`, fits the query.
+    pub fn matches(&self, color: &PantoneColor, tags: &[String]) -> bool {
+        let lowercase: Vec<String> = tags.iter().map(|tag| tag.to_lowercase()).collect();
+        let tagged = self
+            .tags
+            .iter()
+            .all(|wanted| lowercase.iter().any(|tag| tag.starts_with(wanted)));
+        let text = self.text.to_lowercase();
+        tagged
+            && (color.matches_query(&text)
+                || (!text.is_empty() && lowercase.iter().any(|tag| tag.contains(&text))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::conversion::hex_to_rgb;
+
+    #[test]
+    fn test_update_and_round_trip() {
+        let color = PantoneColor::test_in(ColorLibrary::SolidCoated, "PANTONE 100-C", "#f6eb61");
+        let key = AnnotationKey::for_color(&color);
+        assert_eq!(
+            key,
+            AnnotationKey {
+                library: "solid-coated".to_string(),
+                code: "100-C".to_string(),
+            }
+        );
+
+        let mut annotations = Annotations::default();
+        annotations.update(&key, |a| {
+            assert!(a.add_tag("approved for print"));
+            assert!(!a.add_tag(" Approved for print "));
+            assert!(a.add_tag("client x"));
+        });
+        annotations.update(&key, |a| {
+            a.notes = "Matches the 2024 swatch book".to_string()
+        });
+        assert_eq!(annotations.get(&key).unwrap().tags.len(), 2);
+
+        let parsed = Annotations::from_json(&annotations.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.get(&key), annotations.get(&key));
+
+        // Custom colours go by their unique name, even when they share a code
+        let mut sand = PantoneColor::custom("Sand", hex_to_rgb("#e3d3b0").unwrap());
+        sand.custom_code = Some("BR-1".to_string());
+        let mut dune = sand.clone();
+        dune.name = "Dune".to_string();
+        let mut coded = PantoneColor::custom("Harbour", hex_to_rgb("#1d4e89").unwrap());
+        coded.custom_code = Some("Sand".to_string());
+        let custom_key = |name: &str| AnnotationKey {
+            library: "custom".to_string(),
+            code: name.to_string(),
+        };
+        assert_eq!(AnnotationKey::for_color(&sand), custom_key("Sand"));
+        assert_ne!(
+            AnnotationKey::for_color(&sand),
+            AnnotationKey::for_color(&dune)
+        );
+        assert_eq!(AnnotationKey::for_color(&coded), custom_key("Harbour"));
+
+        // Emptied annotations are forgotten
+        annotations.update(&key, |a| *a = Annotation::default());
+        assert!(annotations.get(&key).is_none());
+        assert!(annotations.tags_by_key().is_empty());
+    }
+
+    #[test]
+    fn test_tag_query() {
+        let query = TagQuery::parse(r#"blue tag:"client x" tag:approved"#);
+        assert_eq!(query.text, "blue");
+        assert_eq!(query.tags, ["client x", "approved"]);
+
+        let color = PantoneColor::custom("Harbour Blue", hex_to_rgb("#1d4e89").unwrap());
+        let tags = ["Approved for print".to_string(), "Client X".to_string()];
+        assert!(query.matches(&color, &tags));
+        assert!(!query.matches(&color, &tags[..1]));
+        assert!(TagQuery::parse("client").matches(&color, &tags));
+        assert!(!TagQuery::parse("uncoated").matches(&color, &tags));
+        assert!(TagQuery::parse("").matches(&color, &[]));
+    }
+}
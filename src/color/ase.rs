@@ -0,0 +1,437 @@
+use super::conversion::{cmyk_to_rgb, lab_d50_to_rgb};
+use super::model::{Cmyk, ColorLibrary, Lab, PantoneColor, Rgb};
+use super::palette::Palette;
+use super::swatch_file::{utf16_be, ByteReader, SwatchFileError};
+
+const SIGNATURE: &[u8; 4] = b"ASEF";
+const GROUP_START: u16 = 0xc001;
+const GROUP_END: u16 = 0xc002;
+const COLOR_ENTRY: u16 = 0x0001;
+
+/// A swatch's value in the colour model it was saved in. Channels are
+/// 0.0–1.0, except Lab, whose lightness is 0.0–1.0 and a/b are as in CIELAB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AseValue {
+    Rgb([f32; 3]),
+    Cmyk([f32; 4]),
+    Lab([f32; 3]),
+    /// Lightness: 0.0 is black.
+    Gray(f32),
+}
+
+impl AseValue {
+    fn model(&self) -> &'static [u8; 4] {
+        match self {
+            Self::Rgb(_) => b"RGB ",
+            Self::Cmyk(_) => b"CMYK",
+            Self::Lab(_) => b"LAB ",
+            Self::Gray(_) => b"Gray",
+        }
+    }
+
+    fn channels(&self) -> &[f32] {
+        match self {
+            Self::Rgb(values) | Self::Lab(values) => values,
+            Self::Cmyk(values) => values,
+            Self::Gray(value) => std::slice::from_ref(value),
+        }
+    }
+
+    pub fn to_rgb(self) -> Rgb {
+        let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+        match self {
+            Self::Rgb([r, g, b]) => Rgb {
+                r: channel(r),
+                g: channel(g),
+                b: channel(b),
+            },
+            Self::Cmyk([c, m, y, k]) => cmyk_to_rgb(&Cmyk {
+                c: c * 100.0,
+                m: m * 100.0,
+                y: y * 100.0,
+                k: k * 100.0,
+            }),
+            Self::Lab([l, a, b]) => lab_d50_to_rgb(&Lab { l: l * 100.0, a, b }),
+            Self::Gray(gray) => Rgb {
+                r: channel(gray),
+                g: channel(gray),
+                b: channel(gray),
+            },
+        }
+    }
+}
+
+/// How an app treats the swatch when it is edited or printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AseColorType {
+    /// A process colour whose edits update every use.
+    Global,
+    /// A named ink, such as a Pantone colour.
+    Spot,
+    #[default]
+    Process,
+}
+
+impl AseColorType {
+    fn code(&self) -> u16 {
+        match self {
+            Self::Global => 0,
+            Self::Spot => 1,
+            Self::Process => 2,
+        }
+    }
+
+    fn from_code(code: u16) -> Self {
+        match code {
+            0 => Self::Global,
+            1 => Self::Spot,
+            _ => Self::Process,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AseSwatch {
+    pub name: String,
+    pub value: AseValue,
+    pub color_type: AseColorType,
+}
+
+impl AseSwatch {
+    /// `color` as an RGB swatch under its full name; Pantone colours are spot
+    /// colours.
+    pub fn from_color(color: &PantoneColor) -> Self {
+        let channel = |c: u8| c as f32 / 255.0;
+        Self {
+            name: color.full_name(),
+            value: AseValue::Rgb([
+                channel(color.rgb.r),
+                channel(color.rgb.g),
+                channel(color.rgb.b),
+            ]),
+            color_type: match color.library {
+                ColorLibrary::Custom => AseColorType::Process,
+                _ => AseColorType::Spot,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AseGroup {
+    pub name: String,
+    pub swatches: Vec<AseSwatch>,
+}
+
+/// The contents of an `.ase` file: swatches outside any group, then the groups.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AseDocument {
+    pub swatches: Vec<AseSwatch>,
+    pub groups: Vec<AseGroup>,
+}
+
+impl AseDocument {
+    pub fn read(bytes: &[u8]) -> Result<Self, SwatchFileError> {
+        let mut reader = ByteReader::new(bytes);
+        if reader.take(4)? != SIGNATURE {
+            return Err(SwatchFileError::WrongFormat);
+        }
+        let major = reader.u16()?;
+        let _minor = reader.u16()?;
+        if major != 1 {
+            return Err(SwatchFileError::Invalid(format!(
+                "Unsupported swatch exchange version {}",
+                major
+            )));
+        }
+
+        let mut document = Self::default();
+        let mut group: Option<AseGroup> = None;
+        let block_count = reader.u32()?;
+        for _ in 0..block_count {
+            let block_type = reader.u16()?;
+            let length = reader.u32()? as usize;
+            let mut block = ByteReader::new(reader.take(length)?);
+            match block_type {
+                GROUP_START => {
+                    // Groups do not nest; a new one closes any left open
+                    document.groups.extend(group.take());
+                    group = Some(AseGroup {
+                        name: read_name(&mut block)?,
+                        swatches: Vec::new(),
+                    });
+                }
+                GROUP_END => document.groups.extend(group.take()),
+                COLOR_ENTRY => {
+                    let swatch = read_swatch(&mut block)?;
+                    match group.as_mut() {
+                        Some(group) => group.swatches.push(swatch),
+                        None => document.swatches.push(swatch),
+                    }
+                }
+                // Skip blocks from newer versions
+                _ => {}
+            }
+        }
+        document.groups.extend(group);
+        Ok(document)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(SIGNATURE);
+        bytes.extend_from_slice(&1u16.to_be_bytes());
+        bytes.extend_from_slice(&0u16.to_be_bytes());
+        let block_count = self.swatches.len()
+            + self
+                .groups
+                .iter()
+                .map(|group| group.swatches.len() + 2)
+                .sum::<usize>();
+        bytes.extend_from_slice(&(block_count as u32).to_be_bytes());
+
+        for swatch in &self.swatches {
+            write_block(&mut bytes, COLOR_ENTRY, &swatch_body(swatch));
+        }
+        for group in &self.groups {
+            write_block(&mut bytes, GROUP_START, &name_body(&group.name));
+            for swatch in &group.swatches {
+                write_block(&mut bytes, COLOR_ENTRY, &swatch_body(swatch));
+            }
+            write_block(&mut bytes, GROUP_END, &[]);
+        }
+        bytes
+    }
+
+    /// Each palette as a group of RGB swatches.
+    pub fn from_palettes(palettes: &[Palette]) -> Self {
+        Self {
+            swatches: Vec::new(),
+            groups: palettes
+                .iter()
+                .map(|palette| AseGroup {
+                    name: palette.name.clone(),
+                    swatches: palette
+                        .entries
+                        .iter()
+                        .map(|entry| AseSwatch::from_color(&entry.color))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Each group as a palette of custom colours, with the ungrouped swatches
+    /// first in a palette called `name`. Empty groups are dropped.
+    pub fn to_palettes(&self, name: &str) -> Vec<Palette> {
+        let ungrouped = AseGroup {
+            name: name.to_string(),
+            swatches: self.swatches.clone(),
+        };
+        [&ungrouped]
+            .into_iter()
+            .chain(&self.groups)
+            .filter(|group| !group.swatches.is_empty())
+            .map(|group| {
+                let mut palette = Palette::new(&group.name);
+                for swatch in &group.swatches {
+                    palette.push(
+                        PantoneColor::custom(&swatch.name, swatch.value.to_rgb()),
+                        "",
+                    );
+                }
+                palette
+            })
+            .collect()
+    }
+}
+
+fn write_block(bytes: &mut Vec<u8>, block_type: u16, body: &[u8]) {
+    bytes.extend_from_slice(&block_type.to_be_bytes());
+    bytes.extend_from_slice(&(body.len() as u32).to_be_bytes());
+    bytes.extend_from_slice(body);
+}
+
+/// A name preceded by its length in UTF-16 code units.
+fn name_body(name: &str) -> Vec<u8> {
+    let (len, text) = utf16_be(name);
+    let mut body = (len as u16).to_be_bytes().to_vec();
+    body.extend_from_slice(&text);
+    body
+}
+
+fn swatch_body(swatch: &AseSwatch) -> Vec<u8> {
+    let mut body = name_body(&swatch.name);
+    body.extend_from_slice(swatch.value.model());
+    for channel in swatch.value.channels() {
+        body.extend_from_slice(&channel.to_be_bytes());
+    }
+    body.extend_from_slice(&swatch.color_type.code().to_be_bytes());
+    body
+}
+
+/// A name as a UTF-16 length followed by the text, nul-terminated.
+fn read_name(reader: &mut ByteReader) -> Result<String, SwatchFileError> {
+    let len = reader.u16()? as usize;
+    reader.utf16(len)
+}
+
+fn read_swatch(reader: &mut ByteReader) -> Result<AseSwatch, SwatchFileError> {
+    let name = read_name(reader)?;
+    let value = match reader.take(4)? {
+        b"RGB " => AseValue::Rgb([reader.f32()?, reader.f32()?, reader.f32()?]),
+        b"CMYK" => AseValue::Cmyk([reader.f32()?, reader.f32()?, reader.f32()?, reader.f32()?]),
+        b"LAB " => AseValue::Lab([reader.f32()?, reader.f32()?, reader.f32()?]),
+        b"Gray" => AseValue::Gray(reader.f32()?),
+        _ => {
+            return Err(SwatchFileError::Invalid(format!(
+                "“{}” uses an unknown colour model",
+                name
+            )))
+        }
+    };
+    let color_type = AseColorType::from_code(reader.u16()?);
+    Ok(AseSwatch {
+        name,
+        value,
+        color_type,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::conversion::hex_to_rgb;
+
+    fn swatch(name: &str, value: AseValue, color_type: AseColorType) -> AseSwatch {
+        AseSwatch {
+            name: name.to_string(),
+            value,
+            color_type,
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let document = AseDocument {
+            swatches: vec![swatch("Paper", AseValue::Gray(0.95), AseColorType::Process)],
+            groups: vec![
+                AseGroup {
+                    name: "Brand – Primär".to_string(),
+                    swatches: vec![
+                        swatch(
+                            "PANTONE 286 C",
+                            AseValue::Rgb([0.0, 0.2, 0.627]),
+                            AseColorType::Spot,
+                        ),
+                        swatch(
+                            "Ink",
+                            AseValue::Cmyk([0.6, 0.4, 0.4, 1.0]),
+                            AseColorType::Global,
+                        ),
+                        swatch(
+                            "Lab Red",
+                            AseValue::Lab([0.5429, 80.8, 69.89]),
+                            AseColorType::Process,
+                        ),
+                    ],
+                },
+                AseGroup {
+                    name: "Empty".to_string(),
+                    swatches: Vec::new(),
+                },
+            ],
+        };
+        let bytes = document.to_bytes();
+        assert_eq!(&bytes[..4], b"ASEF");
+        assert_eq!(AseDocument::read(&bytes).unwrap(), document);
+    }
+
+    #[test]
+    fn test_read_known_bytes() {
+        // One ungrouped RGB spot swatch named "A": pure red
+        let mut bytes = b"ASEF\x00\x01\x00\x00\x00\x00\x00\x01".to_vec();
+        bytes.extend_from_slice(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x18]);
+        bytes.extend_from_slice(&[0x00, 0x02, 0x00, b'A', 0x00, 0x00]);
+        bytes.extend_from_slice(b"RGB ");
+        for channel in [1.0f32, 0.0, 0.0] {
+            bytes.extend_from_slice(&channel.to_be_bytes());
+        }
+        bytes.extend_from_slice(&[0x00, 0x01]);
+
+        let document = AseDocument::read(&bytes).unwrap();
+        assert_eq!(document.swatches.len(), 1);
+        assert_eq!(document.swatches[0].name, "A");
+        assert_eq!(document.swatches[0].color_type, AseColorType::Spot);
+        assert_eq!(
+            document.swatches[0].value.to_rgb(),
+            Rgb { r: 255, g: 0, b: 0 }
+        );
+
+        assert_eq!(
+            AseDocument::read(&bytes[..bytes.len() - 1]),
+            Err(SwatchFileError::Truncated)
+        );
+        assert_eq!(
+            AseDocument::read(b"GIMP Palette"),
+            Err(SwatchFileError::WrongFormat)
+        );
+    }
+
+    #[test]
+    fn test_read_lab_swatch() {
+        // An Illustrator Lab process swatch for sRGB blue, which it stores
+        // relative to D50 with lightness as a fraction
+        let mut bytes = b"ASEF\x00\x01\x00\x00\x00\x00\x00\x01".to_vec();
+        bytes.extend_from_slice(&[0x00, 0x01, 0x00, 0x00, 0x00, 0x1e]);
+        bytes.extend_from_slice(&[0x00, 0x05, 0x00, b'B', 0x00, b'l', 0x00, b'u']);
+        bytes.extend_from_slice(&[0x00, b'e', 0x00, 0x00]);
+        bytes.extend_from_slice(b"LAB ");
+        for value in [0.2957f32, 68.3, -112.03] {
+            bytes.extend_from_slice(&value.to_be_bytes());
+        }
+        bytes.extend_from_slice(&[0x00, 0x02]);
+
+        let document = AseDocument::read(&bytes).unwrap();
+        assert_eq!(document.swatches[0].name, "Blue");
+        assert_eq!(document.swatches[0].color_type, AseColorType::Process);
+        assert_eq!(
+            document.swatches[0].value.to_rgb(),
+            Rgb { r: 0, g: 0, b: 255 }
+        );
+    }
+
+    #[test]
+    fn test_palettes() {
+        let mut palette = Palette::new("Brand");
+        palette.push(
+            PantoneColor::custom("Harbour", hex_to_rgb("#1d4e89").unwrap()),
+            "",
+        );
+        let document = AseDocument::from_palettes(&[palette]);
+        assert_eq!(
+            document.groups[0].swatches[0].color_type,
+            AseColorType::Process
+        );
+
+        let document = AseDocument::read(&document.to_bytes()).unwrap();
+        let palettes = document.to_palettes("Imported");
+        assert_eq!(palettes.len(), 1);
+        assert_eq!(palettes[0].name, "Brand");
+        assert_eq!(palettes[0].entries[0].color.name, "Harbour");
+        assert_eq!(palettes[0].entries[0].color.hex, "#1d4e89");
+
+        // Values in other models come in as their sRGB equivalent
+        let cmyk = AseValue::Cmyk([0.0, 0.5, 1.0, 0.0]).to_rgb();
+        assert_eq!(
+            cmyk,
+            Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            }
+        );
+        let lab = AseValue::Lab([0.5429, 80.8, 69.89]).to_rgb();
+        assert_eq!(lab, Rgb { r: 255, g: 0, b: 0 });
+    }
+}
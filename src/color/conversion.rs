@@ -1,8 +1,8 @@
-use super::model::{ColorFamily, Hsl, Rgb};
+use super::model::{Cmyk, ColorFamily, Hsl, Hsv, Hwb, Lab, Lch, Oklab, Oklch, Rgb};
 
 pub fn hex_to_rgb(hex: &str) -> Option<Rgb> {
     let hex = hex.trim_start_matches('#');
-    if hex.len() != 6 {
+    if hex.len() != 6 || !hex.is_ascii() {
         return None;
     }
 
@@ -49,6 +49,287 @@ pub fn rgb_to_hsl(rgb: &Rgb) -> Hsl {
     }
 }
 
+pub fn rgb_to_hsv(rgb: &Rgb) -> Hsv {
+    let max = rgb.r.max(rgb.g).max(rgb.b) as f32 / 255.0;
+    let min = rgb.r.min(rgb.g).min(rgb.b) as f32 / 255.0;
+    let s = if max == 0.0 { 0.0 } else { (max - min) / max };
+    Hsv {
+        h: rgb_to_hsl(rgb).h,
+        s: s * 100.0,
+        v: max * 100.0,
+    }
+}
+
+pub fn rgb_to_hwb(rgb: &Rgb) -> Hwb {
+    let max = rgb.r.max(rgb.g).max(rgb.b) as f32 / 255.0;
+    let min = rgb.r.min(rgb.g).min(rgb.b) as f32 / 255.0;
+    Hwb {
+        h: rgb_to_hsl(rgb).h,
+        w: min * 100.0,
+        b: (1.0 - max) * 100.0,
+    }
+}
+
+pub fn rgb_to_cmyk(rgb: &Rgb) -> Cmyk {
+    let (r, g, b) = (
+        rgb.r as f32 / 255.0,
+        rgb.g as f32 / 255.0,
+        rgb.b as f32 / 255.0,
+    );
+    let k = 1.0 - r.max(g).max(b);
+    if k >= 1.0 {
+        return Cmyk {
+            c: 0.0,
+            m: 0.0,
+            y: 0.0,
+            k: 100.0,
+        };
+    }
+    let ink = |channel: f32| (1.0 - channel - k) / (1.0 - k) * 100.0;
+    Cmyk {
+        c: ink(r),
+        m: ink(g),
+        y: ink(b),
+        k: k * 100.0,
+    }
+}
+
+/// A 0.0–1.0 channel to 0–255, clipping out-of-range values.
+fn to_channel(c: f32) -> u8 {
+    (c.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+/// Hue in degrees with saturation and value in 0.0–1.0, as sRGB.
+fn hsv_channels(h: f32, s: f32, v: f32) -> Rgb {
+    let f = |n: f32| {
+        let k = (n + h.rem_euclid(360.0) / 60.0) % 6.0;
+        v - v * s * k.min(4.0 - k).clamp(0.0, 1.0)
+    };
+    Rgb {
+        r: to_channel(f(5.0)),
+        g: to_channel(f(3.0)),
+        b: to_channel(f(1.0)),
+    }
+}
+
+pub fn hsv_to_rgb(hsv: &Hsv) -> Rgb {
+    hsv_channels(hsv.h, hsv.s / 100.0, hsv.v / 100.0)
+}
+
+pub fn hsl_to_rgb(hsl: &Hsl) -> Rgb {
+    let l = hsl.l / 100.0;
+    let v = l + hsl.s / 100.0 * l.min(1.0 - l);
+    let s = if v == 0.0 { 0.0 } else { 2.0 * (1.0 - l / v) };
+    hsv_channels(hsl.h, s, v)
+}
+
+pub fn hwb_to_rgb(hwb: &Hwb) -> Rgb {
+    let (mut w, mut b) = (hwb.w / 100.0, hwb.b / 100.0);
+    // Whiteness and blackness past 100% between them make a grey
+    if w + b > 1.0 {
+        let sum = w + b;
+        w /= sum;
+        b /= sum;
+    }
+    let v = 1.0 - b;
+    let s = if v == 0.0 { 0.0 } else { 1.0 - w / v };
+    hsv_channels(hwb.h, s, v)
+}
+
+pub fn cmyk_to_rgb(cmyk: &Cmyk) -> Rgb {
+    let k = 1.0 - cmyk.k / 100.0;
+    let channel = |ink: f32| to_channel((1.0 - ink / 100.0) * k);
+    Rgb {
+        r: channel(cmyk.c),
+        g: channel(cmyk.m),
+        b: channel(cmyk.y),
+    }
+}
+
+/// Polar form of an opponent (a, b) plane: chroma and hue in degrees.
+fn polar(a: f32, b: f32) -> (f32, f32) {
+    let h = b.atan2(a).to_degrees();
+    (a.hypot(b), if h < 0.0 { h + 360.0 } else { h })
+}
+
+pub fn rgb_to_lch(rgb: &Rgb) -> Lch {
+    let lab = rgb_to_lab(rgb);
+    let (c, h) = polar(lab.a, lab.b);
+    Lch { l: lab.l, c, h }
+}
+
+pub fn rgb_to_oklch(rgb: &Rgb) -> Oklch {
+    let lab = rgb_to_oklab(rgb);
+    let (c, h) = polar(lab.a, lab.b);
+    Oklch { l: lab.l, c, h }
+}
+
+pub fn rgb_to_hex(rgb: &Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb.r, rgb.g, rgb.b)
+}
+
+/// sRGB channel (0–255) to linear light (0.0–1.0).
+fn srgb_to_linear(c: u8) -> f32 {
+    let c = c as f32 / 255.0;
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// sRGB to CIE XYZ (D65, Y in 0.0–1.0).
+pub fn rgb_to_xyz(rgb: &Rgb) -> (f32, f32, f32) {
+    let r = srgb_to_linear(rgb.r);
+    let g = srgb_to_linear(rgb.g);
+    let b = srgb_to_linear(rgb.b);
+
+    (
+        0.412_456_4 * r + 0.357_576_1 * g + 0.180_437_5 * b,
+        0.212_672_9 * r + 0.715_152_2 * g + 0.072_175 * b,
+        0.019_333_9 * r + 0.119_192 * g + 0.950_304_1 * b,
+    )
+}
+
+pub fn rgb_to_lab(rgb: &Rgb) -> Lab {
+    // D65 reference white
+    const XN: f32 = 0.950_47;
+    const YN: f32 = 1.0;
+    const ZN: f32 = 1.088_83;
+
+    fn f(t: f32) -> f32 {
+        const DELTA: f32 = 6.0 / 29.0;
+        if t > DELTA * DELTA * DELTA {
+            t.cbrt()
+        } else {
+            t / (3.0 * DELTA * DELTA) + 4.0 / 29.0
+        }
+    }
+
+    let (x, y, z) = rgb_to_xyz(rgb);
+    let fx = f(x / XN);
+    let fy = f(y / YN);
+    let fz = f(z / ZN);
+
+    Lab {
+        l: 116.0 * fy - 16.0,
+        a: 500.0 * (fx - fy),
+        b: 200.0 * (fy - fz),
+    }
+}
+
+/// Linear light (0.0–1.0) to an sRGB channel (0–255).
+fn linear_to_srgb(c: f32) -> u8 {
+    let c = c.clamp(0.0, 1.0);
+    let c = if c <= 0.003_130_8 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    };
+    (c * 255.0).round() as u8
+}
+
+pub fn rgb_to_oklab(rgb: &Rgb) -> Oklab {
+    let r = srgb_to_linear(rgb.r);
+    let g = srgb_to_linear(rgb.g);
+    let b = srgb_to_linear(rgb.b);
+
+    let l = (0.412_221_47 * r + 0.536_332_55 * g + 0.051_445_995 * b).cbrt();
+    let m = (0.211_903_5 * r + 0.680_699_5 * g + 0.107_396_96 * b).cbrt();
+    let s = (0.088_302_46 * r + 0.281_718_85 * g + 0.629_978_7 * b).cbrt();
+
+    Oklab {
+        l: 0.210_454_26 * l + 0.793_617_8 * m - 0.004_072_047 * s,
+        a: 1.977_998_5 * l - 2.428_592_2 * m + 0.450_593_7 * s,
+        b: 0.025_904_037 * l + 0.782_771_77 * m - 0.808_675_77 * s,
+    }
+}
+
+/// OKLab back to sRGB, clipping out-of-gamut values.
+pub fn oklab_to_rgb(lab: &Oklab) -> Rgb {
+    let l = (lab.l + 0.396_337_78 * lab.a + 0.215_803_76 * lab.b).powi(3);
+    let m = (lab.l - 0.105_561_346 * lab.a - 0.063_854_17 * lab.b).powi(3);
+    let s = (lab.l - 0.089_484_18 * lab.a - 1.291_485_5 * lab.b).powi(3);
+
+    Rgb {
+        r: linear_to_srgb(4.076_741_7 * l - 3.307_711_6 * m + 0.230_969_94 * s),
+        g: linear_to_srgb(-1.268_438 * l + 2.609_757_4 * m - 0.341_319_38 * s),
+        b: linear_to_srgb(-0.004_196_086_3 * l - 0.703_418_6 * m + 1.707_614_7 * s),
+    }
+}
+
+/// CIE XYZ (D65, Y in 0.0–1.0) back to sRGB, clipping out-of-gamut values.
+pub fn xyz_to_rgb(x: f32, y: f32, z: f32) -> Rgb {
+    Rgb {
+        r: linear_to_srgb(3.240_454 * x - 1.537_139 * y - 0.498_531 * z),
+        g: linear_to_srgb(-0.969_266 * x + 1.876_011 * y + 0.041_556 * z),
+        b: linear_to_srgb(0.055_643 * x - 0.204_026 * y + 1.057_225 * z),
+    }
+}
+
+/// CIELAB to CIE XYZ relative to the reference white `(xn, zn)`, with Y
+/// in 0.0–1.0.
+fn lab_to_xyz(lab: &Lab, (xn, zn): (f32, f32)) -> (f32, f32, f32) {
+    fn f_inv(t: f32) -> f32 {
+        const DELTA: f32 = 6.0 / 29.0;
+        if t > DELTA {
+            t * t * t
+        } else {
+            3.0 * DELTA * DELTA * (t - 4.0 / 29.0)
+        }
+    }
+
+    let fy = (lab.l + 16.0) / 116.0;
+    let fx = fy + lab.a / 500.0;
+    let fz = fy - lab.b / 200.0;
+    (xn * f_inv(fx), f_inv(fy), zn * f_inv(fz))
+}
+
+/// CIELAB (D65) back to sRGB, clipping out-of-gamut values.
+pub fn lab_to_rgb(lab: &Lab) -> Rgb {
+    let (x, y, z) = lab_to_xyz(lab, (0.950_47, 1.088_83));
+    xyz_to_rgb(x, y, z)
+}
+
+/// CIELAB relative to D50, as Photoshop and Illustrator store it, to sRGB.
+/// The white point is adapted to D65 with the Bradford transform.
+pub fn lab_d50_to_rgb(lab: &Lab) -> Rgb {
+    let (x, y, z) = lab_to_xyz(lab, (0.964_22, 0.825_21));
+    xyz_to_rgb(
+        0.955_576_6 * x - 0.023_039_3 * y + 0.063_163_6 * z,
+        -0.028_289_5 * x + 1.009_941_6 * y + 0.021_007_7 * z,
+        0.012_298_2 * x - 0.020_483 * y + 1.329_909_8 * z,
+    )
+}
+
+/// Cartesian form of a chroma and hue in degrees.
+fn cartesian(c: f32, h: f32) -> (f32, f32) {
+    let h = h.to_radians();
+    (c * h.cos(), c * h.sin())
+}
+
+pub fn lch_to_rgb(lch: &Lch) -> Rgb {
+    let (a, b) = cartesian(lch.c, lch.h);
+    lab_to_rgb(&Lab { l: lch.l, a, b })
+}
+
+pub fn oklch_to_rgb(oklch: &Oklch) -> Rgb {
+    let (a, b) = cartesian(oklch.c, oklch.h);
+    oklab_to_rgb(&Oklab { l: oklch.l, a, b })
+}
+
+/// WCAG relative luminance in the range 0.0 (black) to 1.0 (white).
+pub fn relative_luminance(rgb: &Rgb) -> f32 {
+    0.2126 * srgb_to_linear(rgb.r) + 0.7152 * srgb_to_linear(rgb.g) + 0.0722 * srgb_to_linear(rgb.b)
+}
+
+/// Whether black text reads better than white text on top of `rgb`.
+pub fn prefers_dark_text(rgb: &Rgb) -> bool {
+    let l = relative_luminance(rgb);
+    // Contrast ratios against black and white: (l + 0.05) / 0.05 vs 1.05 / (l + 0.05)
+    (l + 0.05) * (l + 0.05) > 1.05 * 0.05
+}
+
 pub fn classify_family(hsl: &Hsl) -> ColorFamily {
     // Handle neutrals (low saturation or extreme lightness)
     if hsl.s < 10.0 || hsl.l < 5.0 || hsl.l > 95.0 {
@@ -83,6 +364,164 @@ mod tests {
         assert_eq!(hex_to_rgb("#ff0000"), Some(Rgb { r: 255, g: 0, b: 0 }));
         assert_eq!(hex_to_rgb("00ff00"), Some(Rgb { r: 0, g: 255, b: 0 }));
         assert_eq!(hex_to_rgb("#fff"), None);
+        assert_eq!(hex_to_rgb("#€€"), None);
+    }
+
+    #[test]
+    fn test_rgb_to_lab() {
+        let white = rgb_to_lab(&Rgb {
+            r: 255,
+            g: 255,
+            b: 255,
+        });
+        assert!((white.l - 100.0).abs() < 0.01);
+        assert!(white.a.abs() < 0.01 && white.b.abs() < 0.01);
+
+        // sRGB red is roughly lab(53.24, 80.09, 67.20)
+        let red = rgb_to_lab(&Rgb { r: 255, g: 0, b: 0 });
+        assert!((red.l - 53.24).abs() < 0.05);
+        assert!((red.a - 80.09).abs() < 0.05);
+        assert!((red.b - 67.20).abs() < 0.05);
+    }
+
+    #[test]
+    fn test_oklab_round_trip() {
+        let white = rgb_to_oklab(&Rgb {
+            r: 255,
+            g: 255,
+            b: 255,
+        });
+        assert!((white.l - 1.0).abs() < 1e-3);
+        assert!(white.a.abs() < 1e-3 && white.b.abs() < 1e-3);
+
+        for rgb in [
+            Rgb { r: 255, g: 0, b: 0 },
+            Rgb {
+                r: 18,
+                g: 52,
+                b: 86,
+            },
+            Rgb {
+                r: 240,
+                g: 238,
+                b: 233,
+            },
+        ] {
+            assert_eq!(oklab_to_rgb(&rgb_to_oklab(&rgb)), rgb);
+        }
+    }
+
+    #[test]
+    fn test_cylindrical_and_device_spaces() {
+        let orange = Rgb {
+            r: 255,
+            g: 128,
+            b: 0,
+        };
+        let hsv = rgb_to_hsv(&orange);
+        assert!((hsv.h - 30.1).abs() < 0.1 && hsv.s == 100.0 && hsv.v == 100.0);
+        let hwb = rgb_to_hwb(&orange);
+        assert!(hwb.w == 0.0 && hwb.b == 0.0);
+        assert_eq!(rgb_to_cmyk(&orange).to_string(), "cmyk(0%, 50%, 100%, 0%)");
+        assert_eq!(
+            rgb_to_cmyk(&Rgb { r: 0, g: 0, b: 0 }).to_string(),
+            "cmyk(0%, 0%, 0%, 100%)"
+        );
+
+        // sRGB red is roughly lch(53.24, 104.55, 40.0) and oklch(62.8% 0.258 29.2)
+        let red = Rgb { r: 255, g: 0, b: 0 };
+        let lch = rgb_to_lch(&red);
+        assert!((lch.c - 104.55).abs() < 0.1 && (lch.h - 40.0).abs() < 0.1);
+        let oklch = rgb_to_oklch(&red);
+        assert!((oklch.l - 0.628).abs() < 1e-3);
+        assert!((oklch.c - 0.258).abs() < 1e-3 && (oklch.h - 29.23).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_inverse_conversions() {
+        for rgb in [
+            Rgb { r: 255, g: 0, b: 0 },
+            Rgb {
+                r: 255,
+                g: 128,
+                b: 0,
+            },
+            Rgb {
+                r: 18,
+                g: 52,
+                b: 86,
+            },
+            Rgb {
+                r: 240,
+                g: 238,
+                b: 233,
+            },
+            Rgb { r: 0, g: 0, b: 0 },
+        ] {
+            assert_eq!(hsl_to_rgb(&rgb_to_hsl(&rgb)), rgb);
+            assert_eq!(hsv_to_rgb(&rgb_to_hsv(&rgb)), rgb);
+            assert_eq!(hwb_to_rgb(&rgb_to_hwb(&rgb)), rgb);
+            assert_eq!(cmyk_to_rgb(&rgb_to_cmyk(&rgb)), rgb);
+            assert_eq!(lab_to_rgb(&rgb_to_lab(&rgb)), rgb);
+            assert_eq!(lch_to_rgb(&rgb_to_lch(&rgb)), rgb);
+            assert_eq!(oklch_to_rgb(&rgb_to_oklch(&rgb)), rgb);
+        }
+
+        // sRGB red and the white point in D50 Lab
+        let red = Lab {
+            l: 54.29,
+            a: 80.80,
+            b: 69.89,
+        };
+        assert_eq!(lab_d50_to_rgb(&red), Rgb { r: 255, g: 0, b: 0 });
+        let white = Lab {
+            l: 100.0,
+            a: 0.0,
+            b: 0.0,
+        };
+        assert_eq!(
+            lab_d50_to_rgb(&white),
+            Rgb {
+                r: 255,
+                g: 255,
+                b: 255
+            }
+        );
+
+        // Whiteness and blackness beyond 100% normalise to a grey
+        let grey = hwb_to_rgb(&Hwb {
+            h: 0.0,
+            w: 100.0,
+            b: 100.0,
+        });
+        assert_eq!(
+            grey,
+            Rgb {
+                r: 128,
+                g: 128,
+                b: 128
+            }
+        );
+    }
+
+    #[test]
+    fn test_prefers_dark_text() {
+        assert!(prefers_dark_text(&Rgb {
+            r: 255,
+            g: 255,
+            b: 255
+        }));
+        assert!(prefers_dark_text(&Rgb {
+            r: 251,
+            g: 192,
+            b: 45
+        }));
+        assert!(!prefers_dark_text(&Rgb { r: 0, g: 0, b: 0 }));
+        assert!(!prefers_dark_text(&Rgb {
+            r: 25,
+            g: 118,
+            b: 210
+        }));
     }
 
     #[test]
@@ -0,0 +1,154 @@
+use super::conversion::hex_to_rgb;
+use super::model::PantoneColor;
+use super::versioned::Versioned;
+use serde::{Deserialize, Serialize};
+
+/// A colour the user added to the Custom library, such as a brand colour.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CustomColor {
+    pub name: String,
+    pub hex: String,
+    #[serde(default)]
+    pub code: String,
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl CustomColor {
+    /// The entry as a library colour, or `None` if its hex value is unreadable.
+    pub fn to_color(&self) -> Option<PantoneColor> {
+        let mut color = PantoneColor::custom(&self.name, hex_to_rgb(&self.hex)?);
+        color.custom_code = Some(self.code.clone()).filter(|code| !code.is_empty());
+        Some(color)
+    }
+}
+
+/// The Custom library, as stored on disk. Names are unique, as they key the
+/// colours in favourites.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomLibrary {
+    colors: Vec<CustomColor>,
+}
+
+impl Versioned for CustomLibrary {
+    const KIND: &'static str = "custom colours";
+    const VERSION: u32 = 1;
+}
+
+impl CustomLibrary {
+    pub fn colors(&self) -> &[CustomColor] {
+        &self.colors
+    }
+
+    /// Every entry as a library colour, in the order they were added.
+    pub fn to_colors(&self) -> Vec<PantoneColor> {
+        self.colors
+            .iter()
+            .filter_map(CustomColor::to_color)
+            .collect()
+    }
+
+    pub fn find(&self, name: &str) -> Option<&CustomColor> {
+        self.colors.iter().find(|color| color.name == name)
+    }
+
+    /// Add `color`, unless its name is already taken. Returns whether it was added.
+    pub fn add(&mut self, color: CustomColor) -> bool {
+        if self.find(&color.name).is_some() {
+            return false;
+        }
+        self.colors.push(color);
+        true
+    }
+
+    /// Add `color`, as `name 2`, `name 3`… if its name is taken, e.g. when
+    /// importing a swatch file. Returns the name it was added under.
+    pub fn add_renamed(&mut self, mut color: CustomColor) -> String {
+        let taken = |library: &Self, candidate: &str| library.find(candidate).is_some();
+        if taken(self, &color.name) {
+            color.name = (2..)
+                .map(|n| format!("{} {}", color.name, n))
+                .find(|candidate| !taken(self, candidate))
+                .unwrap_or_default();
+        }
+        let name = color.name.clone();
+        self.colors.push(color);
+        name
+    }
+
+    /// Overwrite the colour called `name` with `color`, which may rename it to
+    /// a name not already taken. Returns whether it was replaced.
+    pub fn replace(&mut self, name: &str, color: CustomColor) -> bool {
+        if color.name != name && self.find(&color.name).is_some() {
+            return false;
+        }
+        match self.colors.iter_mut().find(|c| c.name == name) {
+            Some(existing) => {
+                *existing = color;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<CustomColor> {
+        let index = self.colors.iter().position(|color| color.name == name)?;
+        Some(self.colors.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::model::ColorLibrary;
+
+    fn brand(name: &str, hex: &str) -> CustomColor {
+        CustomColor {
+            name: name.to_string(),
+            hex: hex.to_string(),
+            code: String::new(),
+            notes: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_names_stay_unique() {
+        let mut library = CustomLibrary::default();
+        assert!(library.add(brand("Harbour", "#1d4e89")));
+        assert!(library.add(brand("Sand", "#e3d3b0")));
+        assert!(!library.add(brand("Harbour", "#000000")));
+
+        assert!(!library.replace("Sand", brand("Harbour", "#e3d3b0")));
+        assert!(library.replace("Sand", brand("Dune", "#e0cca0")));
+        assert!(!library.replace("Sand", brand("Sand", "#e0cca0")));
+        assert_eq!(library.find("Dune").unwrap().hex, "#e0cca0");
+
+        assert_eq!(library.add_renamed(brand("Dune", "#000000")), "Dune 2");
+        assert_eq!(library.add_renamed(brand("Dune", "#000000")), "Dune 3");
+        library.remove("Dune 2");
+        library.remove("Dune 3");
+
+        assert!(library.remove("Harbour").is_some());
+        assert!(library.remove("Harbour").is_none());
+        assert_eq!(library.colors().len(), 1);
+    }
+
+    #[test]
+    fn test_to_color_and_round_trip() {
+        let mut library = CustomLibrary::default();
+        library.add(CustomColor {
+            code: "HB-01".to_string(),
+            notes: "Primary".to_string(),
+            ..brand("Harbour", "#1d4e89")
+        });
+        library.add(brand("Broken", "not a colour"));
+
+        let colors = library.to_colors();
+        assert_eq!(colors.len(), 1);
+        assert_eq!(colors[0].library, ColorLibrary::Custom);
+        assert_eq!(colors[0].code(), Some("HB-01"));
+
+        let parsed = CustomLibrary::from_json(&library.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.colors(), library.colors());
+    }
+}
@@ -0,0 +1,333 @@
+use super::conversion::rgb_to_lab;
+use super::model::{Lab, PantoneColor, Rgb};
+
+/// Colour difference formula.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DeltaE {
+    /// Euclidean distance in CIELAB.
+    Cie76,
+    /// CIE94 with graphic-arts weighting.
+    Cie94,
+    /// CMC l:c with the 2:1 acceptability weighting used for textiles.
+    Cmc,
+    #[default]
+    Ciede2000,
+}
+
+impl DeltaE {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Cie76 => "cie76",
+            Self::Cie94 => "cie94",
+            Self::Cmc => "cmc",
+            Self::Ciede2000 => "ciede2000",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|formula| formula.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Cie76 => "ΔE*76",
+            Self::Cie94 => "ΔE*94",
+            Self::Cmc => "ΔE CMC",
+            Self::Ciede2000 => "ΔE00",
+        }
+    }
+
+    pub fn all() -> &'static [DeltaE] {
+        &[Self::Cie76, Self::Cie94, Self::Cmc, Self::Ciede2000]
+    }
+
+    /// The difference of `b` from the reference `a`. CIE94 and CMC are not
+    /// symmetric, so the order matters for them.
+    pub fn compute(&self, a: &Lab, b: &Lab) -> f32 {
+        match self {
+            Self::Cie76 => cie76(a, b),
+            Self::Cie94 => cie94(a, b),
+            Self::Cmc => cmc(a, b, 2.0, 1.0),
+            Self::Ciede2000 => ciede2000(a, b),
+        }
+    }
+}
+
+/// Lightness, chroma and hue differences of a sample from a reference in CIELAB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LabDifference {
+    /// ΔL*: positive when the sample is lighter.
+    pub dl: f32,
+    /// ΔC*ab: positive when the sample is more chromatic.
+    pub dc: f32,
+    /// ΔH*ab: signed by the direction of the hue rotation (positive is anticlockwise).
+    pub dh: f32,
+}
+
+impl LabDifference {
+    pub fn between(reference: &Lab, sample: &Lab) -> Self {
+        let (l1, a1, b1) = (reference.l as f64, reference.a as f64, reference.b as f64);
+        let (l2, a2, b2) = (sample.l as f64, sample.a as f64, sample.b as f64);
+        let c1 = a1.hypot(b1);
+        let c2 = a2.hypot(b2);
+        let dc = c2 - c1;
+        let dh2 = ((a2 - a1).powi(2) + (b2 - b1).powi(2) - dc * dc).max(0.0);
+        // Cross product sign tells which way the hue turned
+        let sign = if a1 * b2 - b1 * a2 < 0.0 { -1.0 } else { 1.0 };
+        Self {
+            dl: (l2 - l1) as f32,
+            dc: dc as f32,
+            dh: (sign * dh2.sqrt()) as f32,
+        }
+    }
+}
+
+fn cie76(a: &Lab, b: &Lab) -> f32 {
+    ((a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)).sqrt()
+}
+
+/// CIE94 with graphic-arts constants (kL = 1, K1 = 0.045, K2 = 0.015).
+fn cie94(reference: &Lab, sample: &Lab) -> f32 {
+    let d = LabDifference::between(reference, sample);
+    let c1 = (reference.a as f64).hypot(reference.b as f64);
+    let sc = 1.0 + 0.045 * c1;
+    let sh = 1.0 + 0.015 * c1;
+    let (dl, dc, dh) = (d.dl as f64, d.dc as f64 / sc, d.dh as f64 / sh);
+    (dl * dl + dc * dc + dh * dh).sqrt() as f32
+}
+
+/// CMC l:c, weighted by the reference colour.
+fn cmc(reference: &Lab, sample: &Lab, l: f64, c: f64) -> f32 {
+    let d = LabDifference::between(reference, sample);
+    let l1 = reference.l as f64;
+    let c1 = (reference.a as f64).hypot(reference.b as f64);
+    let h1 = {
+        let h = (reference.b as f64).atan2(reference.a as f64).to_degrees();
+        if h < 0.0 {
+            h + 360.0
+        } else {
+            h
+        }
+    };
+
+    let sl = if l1 < 16.0 {
+        0.511
+    } else {
+        0.040975 * l1 / (1.0 + 0.01765 * l1)
+    };
+    let sc = 0.0638 * c1 / (1.0 + 0.0131 * c1) + 0.638;
+    let t = if (164.0..=345.0).contains(&h1) {
+        0.56 + (0.2 * (h1 + 168.0).to_radians().cos()).abs()
+    } else {
+        0.36 + (0.4 * (h1 + 35.0).to_radians().cos()).abs()
+    };
+    let c1_4 = c1.powi(4);
+    let f = (c1_4 / (c1_4 + 1900.0)).sqrt();
+    let sh = sc * (f * t + 1.0 - f);
+
+    let dl = d.dl as f64 / (l * sl);
+    let dc = d.dc as f64 / (c * sc);
+    let dh = d.dh as f64 / sh;
+    (dl * dl + dc * dc + dh * dh).sqrt() as f32
+}
+
+/// CIEDE2000 with unit weighting factors (kL = kC = kH = 1).
+fn ciede2000(lab1: &Lab, lab2: &Lab) -> f32 {
+    let (l1, a1, b1) = (lab1.l as f64, lab1.a as f64, lab1.b as f64);
+    let (l2, a2, b2) = (lab2.l as f64, lab2.a as f64, lab2.b as f64);
+
+    let c1 = (a1 * a1 + b1 * b1).sqrt();
+    let c2 = (a2 * a2 + b2 * b2).sqrt();
+    let c_bar = (c1 + c2) / 2.0;
+    let c_bar7 = c_bar.powi(7);
+    let g = 0.5 * (1.0 - (c_bar7 / (c_bar7 + 25f64.powi(7))).sqrt());
+
+    let a1p = (1.0 + g) * a1;
+    let a2p = (1.0 + g) * a2;
+    let c1p = (a1p * a1p + b1 * b1).sqrt();
+    let c2p = (a2p * a2p + b2 * b2).sqrt();
+
+    let hue = |b: f64, a: f64| {
+        if a == 0.0 && b == 0.0 {
+            0.0
+        } else {
+            let h = b.atan2(a).to_degrees();
+            if h < 0.0 {
+                h + 360.0
+            } else {
+                h
+            }
+        }
+    };
+    let h1p = hue(b1, a1p);
+    let h2p = hue(b2, a2p);
+
+    let dl = l2 - l1;
+    let dc = c2p - c1p;
+    let dh = if c1p * c2p == 0.0 {
+        0.0
+    } else if (h2p - h1p).abs() <= 180.0 {
+        h2p - h1p
+    } else if h2p - h1p > 180.0 {
+        h2p - h1p - 360.0
+    } else {
+        h2p - h1p + 360.0
+    };
+    let dh_big = 2.0 * (c1p * c2p).sqrt() * (dh.to_radians() / 2.0).sin();
+
+    let l_bar = (l1 + l2) / 2.0;
+    let cp_bar = (c1p + c2p) / 2.0;
+    let hp_bar = if c1p * c2p == 0.0 {
+        h1p + h2p
+    } else if (h1p - h2p).abs() <= 180.0 {
+        (h1p + h2p) / 2.0
+    } else if h1p + h2p < 360.0 {
+        (h1p + h2p + 360.0) / 2.0
+    } else {
+        (h1p + h2p - 360.0) / 2.0
+    };
+
+    let t = 1.0 - 0.17 * (hp_bar - 30.0).to_radians().cos()
+        + 0.24 * (2.0 * hp_bar).to_radians().cos()
+        + 0.32 * (3.0 * hp_bar + 6.0).to_radians().cos()
+        - 0.20 * (4.0 * hp_bar - 63.0).to_radians().cos();
+    let d_theta = 30.0 * (-((hp_bar - 275.0) / 25.0).powi(2)).exp();
+    let cp_bar7 = cp_bar.powi(7);
+    let rc = 2.0 * (cp_bar7 / (cp_bar7 + 25f64.powi(7))).sqrt();
+    let sl = 1.0 + (0.015 * (l_bar - 50.0).powi(2)) / (20.0 + (l_bar - 50.0).powi(2)).sqrt();
+    let sc = 1.0 + 0.045 * cp_bar;
+    let sh = 1.0 + 0.015 * cp_bar * t;
+    let rt = -(2.0 * d_theta).to_radians().sin() * rc;
+
+    let dl = dl / sl;
+    let dc = dc / sc;
+    let dh = dh_big / sh;
+
+    (dl * dl + dc * dc + dh * dh + rt * dc * dh).sqrt() as f32
+}
+
+/// A library colour and how far it is from the colour being matched.
+#[derive(Debug, Clone)]
+pub struct ColorMatch {
+    pub color: PantoneColor,
+    pub delta_e: f32,
+}
+
+/// The `limit` candidates closest to `target`, nearest first.
+pub fn nearest_matches<'a>(
+    candidates: impl IntoIterator<Item = &'a PantoneColor>,
+    target: &Rgb,
+    formula: DeltaE,
+    limit: usize,
+) -> Vec<ColorMatch> {
+    let target = rgb_to_lab(target);
+    let mut matches: Vec<ColorMatch> = candidates
+        .into_iter()
+        .map(|color| ColorMatch {
+            delta_e: formula.compute(&target, &rgb_to_lab(&color.rgb)),
+            color: color.clone(),
+        })
+        .collect();
+    matches.sort_by(|a, b| a.delta_e.total_cmp(&b.delta_e));
+    matches.truncate(limit);
+    matches
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::model::ColorLibrary;
+
+    fn lab(l: f32, a: f32, b: f32) -> Lab {
+        Lab { l, a, b }
+    }
+
+    #[test]
+    fn test_ciede2000_reference_pairs() {
+        // Pairs from Sharma, Wu & Dalal, "The CIEDE2000 Color-Difference Formula"
+        let pairs = [
+            (
+                lab(50.0, 2.6772, -79.7751),
+                lab(50.0, 0.0, -82.7485),
+                2.0425,
+            ),
+            (
+                lab(50.0, -1.3802, -84.2814),
+                lab(50.0, 0.0, -82.7485),
+                1.0000,
+            ),
+            (lab(50.0, 2.5, 0.0), lab(73.0, 25.0, -18.0), 27.1492),
+            (lab(50.0, 2.5, 0.0), lab(50.0, 0.0, -2.5), 4.3065),
+            (
+                lab(2.0776, 0.0795, -1.135),
+                lab(0.9033, -0.0636, -0.5514),
+                0.9082,
+            ),
+        ];
+        for (a, b, expected) in pairs {
+            let delta = DeltaE::Ciede2000.compute(&a, &b);
+            assert!((delta - expected).abs() < 1e-3, "{delta} != {expected}");
+        }
+    }
+
+    #[test]
+    fn test_cie76() {
+        let delta = DeltaE::Cie76.compute(&lab(50.0, 0.0, 0.0), &lab(53.0, 4.0, 0.0));
+        assert!((delta - 5.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_cie94_and_cmc() {
+        // A pure chroma step away from grey: every formula weights only ΔC
+        let grey = lab(50.0, 0.0, 0.0);
+        let tinted = lab(50.0, 3.0, 4.0);
+        assert!((DeltaE::Cie94.compute(&grey, &tinted) - 5.0).abs() < 1e-4);
+        // SC = 0.638 for an achromatic reference
+        assert!((DeltaE::Cmc.compute(&grey, &tinted) - 5.0 / 0.638).abs() < 1e-3);
+
+        // Dark lightness steps use the fixed SL = 0.511 with l = 2
+        let dark = DeltaE::Cmc.compute(&lab(10.0, 0.0, 0.0), &lab(12.0, 0.0, 0.0));
+        assert!((dark - 2.0 / (2.0 * 0.511)).abs() < 1e-4);
+
+        for formula in DeltaE::all() {
+            assert_eq!(formula.compute(&tinted, &tinted), 0.0);
+            assert_eq!(DeltaE::from_id(formula.id()), Some(*formula));
+        }
+    }
+
+    #[test]
+    fn test_lab_difference() {
+        let d = LabDifference::between(&lab(50.0, 0.0, 0.0), &lab(55.0, 3.0, 4.0));
+        assert!((d.dl - 5.0).abs() < 1e-5);
+        assert!((d.dc - 5.0).abs() < 1e-5);
+        assert!(d.dh.abs() < 1e-3);
+
+        // Same chroma, hue turned from +a towards +b
+        let d = LabDifference::between(&lab(50.0, 10.0, 0.0), &lab(50.0, 0.0, 10.0));
+        assert!(d.dc.abs() < 1e-5);
+        assert!((d.dh - 200f32.sqrt()).abs() < 1e-4);
+        let d = LabDifference::between(&lab(50.0, 0.0, 10.0), &lab(50.0, 10.0, 0.0));
+        assert!((d.dh + 200f32.sqrt()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_nearest_matches() {
+        let colors: Vec<PantoneColor> = [
+            ("red", "#ff0000"),
+            ("blue", "#0000ff"),
+            ("dark-red", "#cc0000"),
+        ]
+        .iter()
+        .map(|(name, hex)| PantoneColor::test_in(ColorLibrary::SolidCoated, name, hex))
+        .collect();
+
+        let matches = nearest_matches(&colors, &Rgb { r: 250, g: 5, b: 5 }, DeltaE::Ciede2000, 2);
+        assert_eq!(matches.len(), 2);
+        assert_eq!(matches[0].color.name, "red");
+        assert_eq!(matches[1].color.name, "dark-red");
+        assert!(matches[0].delta_e < matches[1].delta_e);
+    }
+}
@@ -0,0 +1,11 @@
+// Brand 2025 — exported from lon
+import androidx.compose.ui.graphics.Color
+
+/** PANTONE 100 C */
+val Pantone100C = Color(0xFFF6EB61)
+
+/** PANTONE Cloud Dancer TCX */
+val PantoneCloudDancerTcx = Color(0xFFF0EEE9)
+
+/** Harbour Blue HB-01 */
+val HarbourBlueHb01 = Color(0xFF1D4E89)
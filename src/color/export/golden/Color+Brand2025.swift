@@ -0,0 +1,11 @@
+// Brand 2025 — exported from lon
+import SwiftUI
+
+extension Color {
+    /// PANTONE 100 C
+    static let pantone100C = Color(red: 0.965, green: 0.922, blue: 0.380)
+    /// PANTONE Cloud Dancer TCX
+    static let pantoneCloudDancerTcx = Color(red: 0.941, green: 0.933, blue: 0.914)
+    /// Harbour Blue HB-01
+    static let harbourBlueHb01 = Color(red: 0.114, green: 0.306, blue: 0.537)
+}
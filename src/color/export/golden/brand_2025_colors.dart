@@ -0,0 +1,15 @@
+// Brand 2025 — exported from lon
+import 'package:flutter/widgets.dart';
+
+class Brand2025Colors {
+  Brand2025Colors._();
+
+  /// PANTONE 100 C
+  static const pantone100C = Color(0xFFF6EB61);
+
+  /// PANTONE Cloud Dancer TCX
+  static const pantoneCloudDancerTcx = Color(0xFFF0EEE9);
+
+  /// Harbour Blue HB-01
+  static const harbourBlueHb01 = Color(0xFF1D4E89);
+}
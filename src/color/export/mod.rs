@@ -0,0 +1,206 @@
+mod platform;
+mod web;
+
+use super::palette::Palette;
+
+/// Source code a palette can be exported as, for developers to paste into a project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CodeFormat {
+    /// `--pantone-100-c: #f6eb61;` under `:root`
+    #[default]
+    Css,
+    /// `$pantone-100-c: #f6eb61;`
+    Scss,
+    /// `@pantone-100-c: #f6eb61;`
+    Less,
+    /// A module exporting the object for Tailwind's `theme.colors`
+    Tailwind,
+    /// W3C Design Tokens Community Group JSON
+    DesignTokens,
+    /// `<color name="pantone_100_c">#F6EB61</color>` in `res/values/colors.xml`
+    AndroidXml,
+    /// `static let pantone100C = Color(red: 0.965, green: 0.922, blue: 0.380)`
+    SwiftUi,
+    /// An Xcode asset catalog folder with a `.colorset` per colour
+    AssetCatalog,
+    /// `static const pantone100C = Color(0xFFF6EB61);` in a constants class
+    Flutter,
+    /// `val Pantone100C = Color(0xFFF6EB61)` for Jetpack Compose
+    Compose,
+}
+
+impl CodeFormat {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Css => "css",
+            Self::Scss => "scss",
+            Self::Less => "less",
+            Self::Tailwind => "tailwind",
+            Self::DesignTokens => "design-tokens",
+            Self::AndroidXml => "android-xml",
+            Self::SwiftUi => "swiftui",
+            Self::AssetCatalog => "asset-catalog",
+            Self::Flutter => "flutter",
+            Self::Compose => "compose",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|f| f.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Css => "CSS Custom Properties",
+            Self::Scss => "SCSS Variables",
+            Self::Less => "Less Variables",
+            Self::Tailwind => "Tailwind Colours",
+            Self::DesignTokens => "Design Tokens (DTCG)",
+            Self::AndroidXml => "Android Colour Resources",
+            Self::SwiftUi => "SwiftUI Colour Extension",
+            Self::AssetCatalog => "Xcode Asset Catalog",
+            Self::Flutter => "Flutter Colour Constants",
+            Self::Compose => "Jetpack Compose Colours",
+        }
+    }
+
+    pub fn all() -> &'static [CodeFormat] {
+        &[
+            Self::Css,
+            Self::Scss,
+            Self::Less,
+            Self::Tailwind,
+            Self::DesignTokens,
+            Self::AndroidXml,
+            Self::SwiftUi,
+            Self::AssetCatalog,
+            Self::Flutter,
+            Self::Compose,
+        ]
+    }
+
+    /// Whether the format is a folder of files rather than one file.
+    pub fn is_folder(&self) -> bool {
+        matches!(self, Self::AssetCatalog)
+    }
+
+    /// A file name for `palette_name` in this format, following the target
+    /// platform's conventions, e.g. `brand.tokens.json` or `BrandColors.kt`.
+    pub fn file_name(&self, palette_name: &str) -> String {
+        let stem = match slugify(palette_name) {
+            stem if stem.is_empty() => "colors".to_string(),
+            stem => stem,
+        };
+        match self {
+            Self::Css => format!("{}.css", stem),
+            Self::Scss => format!("{}.scss", stem),
+            Self::Less => format!("{}.less", stem),
+            Self::Tailwind => format!("{}.colors.js", stem),
+            Self::DesignTokens => format!("{}.tokens.json", stem),
+            Self::AndroidXml => "colors.xml".to_string(),
+            Self::SwiftUi => format!("Color+{}.swift", platform::type_name(palette_name)),
+            Self::AssetCatalog => format!("{}.xcassets", platform::type_name(palette_name)),
+            Self::Flutter => format!("{}_colors.dart", stem.replace('-', "_")),
+            Self::Compose => format!("{}Colors.kt", platform::type_name(palette_name)),
+        }
+    }
+
+    /// Every file the export is made of, as paths relative to where it is
+    /// saved and their contents.
+    pub fn files(&self, palette: &Palette) -> Vec<(String, String)> {
+        match self {
+            Self::AssetCatalog => platform::asset_catalog(palette),
+            _ => vec![(self.file_name(&palette.name), self.render(palette))],
+        }
+    }
+
+    /// The export as text; folder formats list each file in turn.
+    pub fn render(&self, palette: &Palette) -> String {
+        match self {
+            Self::Css => web::css(palette),
+            Self::Scss => web::variables(palette, '$'),
+            Self::Less => web::variables(palette, '@'),
+            Self::Tailwind => web::tailwind(palette),
+            Self::DesignTokens => web::design_tokens(palette),
+            Self::AndroidXml => platform::android_xml(palette),
+            Self::SwiftUi => platform::swiftui(palette),
+            Self::Flutter => platform::flutter(palette),
+            Self::Compose => platform::compose(palette),
+            // Each file under a comment naming it, for preview
+            Self::AssetCatalog => platform::asset_catalog(palette)
+                .into_iter()
+                .map(|(path, contents)| format!("// {}\n{}", path, contents))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
+/// The line each file starts with, naming the palette.
+fn heading(palette: &Palette) -> String {
+    format!("{} — exported from lon", palette.name.replace("*/", "* /"))
+}
+
+/// `text` in lowercase ASCII letters and digits, with any run of other
+/// characters turned into a single `-`: `PANTONE 100 C` becomes `pantone-100-c`.
+pub fn slugify(text: &str) -> String {
+    text.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_ascii_lowercase)
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// A unique key for each colour in `palette`, slugified from its full name.
+/// Keys never start with a digit; formats whose keywords a key could spell,
+/// such as `default`, escape those themselves.
+fn color_keys(palette: &Palette) -> Vec<String> {
+    let mut keys: Vec<String> = Vec::new();
+    for entry in &palette.entries {
+        let mut key = slugify(&entry.color.full_name());
+        if !key.starts_with(|c: char| c.is_ascii_alphabetic()) {
+            key = format!("color-{}", key).trim_end_matches('-').to_string();
+        }
+        if keys.contains(&key) {
+            key = (2..)
+                .map(|n| format!("{}-{}", key, n))
+                .find(|candidate| !keys.contains(candidate))
+                .unwrap_or_default();
+        }
+        keys.push(key);
+    }
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::conversion::hex_to_rgb;
+    use crate::color::model::{ColorLibrary, PantoneColor};
+
+    #[test]
+    fn test_keys() {
+        assert_eq!(slugify("PANTONE 100 C"), "pantone-100-c");
+        assert_eq!(slugify("  Blåbær / Sea–Blue! "), "bl-b-r-sea-blue");
+
+        let coated = PantoneColor::test_in(ColorLibrary::SolidCoated, "PANTONE 100-C", "#f6eb61");
+        let mut palette = Palette::new("Brand");
+        palette.push(coated, "");
+        for name in ["Sand", "Sand", "2024 Red", "★"] {
+            palette.push(
+                PantoneColor::custom(name, hex_to_rgb("#e3d3b0").unwrap()),
+                "",
+            );
+        }
+        assert_eq!(
+            color_keys(&palette),
+            ["pantone-100-c", "sand", "sand-2", "color-2024-red", "color"]
+        );
+
+        assert_eq!(
+            CodeFormat::DesignTokens.file_name("Brand 2025"),
+            "brand-2025.tokens.json"
+        );
+        assert_eq!(CodeFormat::Css.file_name("★"), "colors.css");
+    }
+}
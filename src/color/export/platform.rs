@@ -0,0 +1,414 @@
+use super::{color_keys, heading, slugify};
+use crate::color::model::Rgb;
+use crate::color::palette::Palette;
+use serde_json::{json, Value};
+
+/// The words of a slugified key, e.g. `pantone-100-c`.
+fn words(key: &str) -> impl Iterator<Item = &str> {
+    key.split('-').filter(|word| !word.is_empty())
+}
+
+fn capitalise(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// `pantone-100-c` as `Pantone100C`.
+fn pascal_case(key: &str) -> String {
+    words(key).map(capitalise).collect()
+}
+
+/// `pantone-100-c` as `pantone100C`.
+fn camel_case(key: &str) -> String {
+    let pascal = pascal_case(key);
+    let mut chars = pascal.chars();
+    chars
+        .next()
+        .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+        .unwrap_or_default()
+}
+
+/// Words Swift, Dart and Java reserve, which a single-word key could spell.
+const SWIFT_KEYWORDS: &[&str] = &[
+    "any",
+    "as",
+    "associatedtype",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "continue",
+    "default",
+    "defer",
+    "deinit",
+    "do",
+    "else",
+    "enum",
+    "extension",
+    "fallthrough",
+    "false",
+    "fileprivate",
+    "for",
+    "func",
+    "guard",
+    "if",
+    "import",
+    "in",
+    "init",
+    "inout",
+    "internal",
+    "is",
+    "let",
+    "nil",
+    "open",
+    "operator",
+    "precedencegroup",
+    "private",
+    "protocol",
+    "public",
+    "repeat",
+    "rethrows",
+    "return",
+    "self",
+    "static",
+    "struct",
+    "subscript",
+    "super",
+    "switch",
+    "throw",
+    "throws",
+    "true",
+    "try",
+    "typealias",
+    "var",
+    "where",
+    "while",
+];
+const DART_KEYWORDS: &[&str] = &[
+    "assert", "break", "case", "catch", "class", "const", "continue", "default", "do", "else",
+    "enum", "extends", "false", "final", "finally", "for", "if", "in", "is", "new", "null",
+    "rethrow", "return", "super", "switch", "this", "throw", "true", "try", "var", "void", "while",
+    "with",
+];
+const JAVA_KEYWORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// `key` as a Swift constant, in backticks if it is a keyword.
+fn swift_name(key: &str) -> String {
+    let name = camel_case(key);
+    if SWIFT_KEYWORDS.contains(&name.as_str()) {
+        format!("`{}`", name)
+    } else {
+        name
+    }
+}
+
+/// `key` as a Dart constant, with a trailing `_` if it is a keyword.
+fn dart_name(key: &str) -> String {
+    let name = camel_case(key);
+    if DART_KEYWORDS.contains(&name.as_str()) {
+        name + "_"
+    } else {
+        name
+    }
+}
+
+/// `key` as an Android resource name. Those become Java fields of `R.color`,
+/// so keywords get a trailing `_`.
+fn android_name(key: &str) -> String {
+    let name = key.replace('-', "_");
+    if JAVA_KEYWORDS.contains(&name.as_str()) {
+        name + "_"
+    } else {
+        name
+    }
+}
+
+/// A class or file name for the palette, never starting with a digit.
+pub(super) fn type_name(palette_name: &str) -> String {
+    let name = pascal_case(&slugify(palette_name));
+    if name.starts_with(|c: char| c.is_ascii_alphabetic()) {
+        name
+    } else {
+        format!("Palette{}", name)
+    }
+}
+
+/// `0xFFRRGGBB`, as Flutter and Compose take colours.
+fn argb(rgb: &Rgb) -> String {
+    format!("0xFF{:02X}{:02X}{:02X}", rgb.r, rgb.g, rgb.b)
+}
+
+pub fn android_xml(palette: &Palette) -> String {
+    let mut text = format!(
+        "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<!-- {} -->\n<resources>\n",
+        heading(palette).replace("--", "- -")
+    );
+    for (key, entry) in color_keys(palette).iter().zip(&palette.entries) {
+        let rgb = entry.color.rgb;
+        text.push_str(&format!(
+            "    <color name=\"{}\">#{:02X}{:02X}{:02X}</color>\n",
+            android_name(key),
+            rgb.r,
+            rgb.g,
+            rgb.b
+        ));
+    }
+    text.push_str("</resources>\n");
+    text
+}
+
+pub fn swiftui(palette: &Palette) -> String {
+    let mut text = format!(
+        "// {}\nimport SwiftUI\n\nextension Color {{\n",
+        heading(palette)
+    );
+    for (key, entry) in color_keys(palette).iter().zip(&palette.entries) {
+        let rgb = entry.color.rgb;
+        let channel = |c: u8| c as f32 / 255.0;
+        text.push_str(&format!(
+            "    /// {}\n    static let {} = Color(red: {:.3}, green: {:.3}, blue: {:.3})\n",
+            entry.color.full_name(),
+            swift_name(key),
+            channel(rgb.r),
+            channel(rgb.g),
+            channel(rgb.b)
+        ));
+    }
+    text.push_str("}\n");
+    text
+}
+
+/// The `info` Xcode expects in every `Contents.json` of an asset catalog.
+fn catalog_info() -> Value {
+    json!({ "author": "xcode", "version": 1 })
+}
+
+fn contents_json(contents: Value) -> String {
+    let mut text = serde_json::to_string_pretty(&contents).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+/// An asset catalog with a colour set per colour, as paths relative to the
+/// folder it is saved in and their contents.
+pub fn asset_catalog(palette: &Palette) -> Vec<(String, String)> {
+    let catalog = format!("{}.xcassets", type_name(&palette.name));
+    let mut files = vec![(
+        format!("{}/Contents.json", catalog),
+        contents_json(json!({ "info": catalog_info() })),
+    )];
+    for (key, entry) in color_keys(palette).iter().zip(&palette.entries) {
+        let rgb = entry.color.rgb;
+        let channel = |c: u8| format!("0x{:02X}", c);
+        let contents = json!({
+            "colors": [{
+                "color": {
+                    "color-space": "srgb",
+                    "components": {
+                        "alpha": "1.000",
+                        "blue": channel(rgb.b),
+                        "green": channel(rgb.g),
+                        "red": channel(rgb.r),
+                    },
+                },
+                "idiom": "universal",
+            }],
+            "info": catalog_info(),
+        });
+        files.push((
+            format!("{}/{}.colorset/Contents.json", catalog, camel_case(key)),
+            contents_json(contents),
+        ));
+    }
+    files
+}
+
+pub fn flutter(palette: &Palette) -> String {
+    let class = format!("{}Colors", type_name(&palette.name));
+    let mut text = format!(
+        "// {}\nimport 'package:flutter/widgets.dart';\n\nclass {} {{\n  {}._();\n",
+        heading(palette),
+        class,
+        class
+    );
+    for (key, entry) in color_keys(palette).iter().zip(&palette.entries) {
+        text.push_str(&format!(
+            "\n  /// {}\n  static const {} = Color({});\n",
+            entry.color.full_name(),
+            dart_name(key),
+            argb(&entry.color.rgb)
+        ));
+    }
+    text.push_str("}\n");
+    text
+}
+
+pub fn compose(palette: &Palette) -> String {
+    let mut text = format!(
+        "// {}\nimport androidx.compose.ui.graphics.Color\n",
+        heading(palette)
+    );
+    for (key, entry) in color_keys(palette).iter().zip(&palette.entries) {
+        text.push_str(&format!(
+            "\n/** {} */\nval {} = Color({})\n",
+            entry.color.full_name().replace("*/", "* /"),
+            pascal_case(key),
+            argb(&entry.color.rgb)
+        ));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::conversion::hex_to_rgb;
+    use crate::color::model::{ColorLibrary, PantoneColor};
+
+    /// The palette every golden file was generated from.
+    fn palette() -> Palette {
+        let coated = PantoneColor::test_in(ColorLibrary::SolidCoated, "PANTONE 100-C", "#f6eb61");
+        let tcx = PantoneColor::test_in(ColorLibrary::FashionHomeTcx, "cloud-dancer", "#f0eee9");
+        let mut brand = PantoneColor::custom("Harbour Blue", hex_to_rgb("#1d4e89").unwrap());
+        brand.custom_code = Some("HB-01".to_string());
+
+        let mut palette = Palette::new("Brand 2025");
+        palette.push(coated, "");
+        palette.push(tcx, "");
+        palette.push(brand, "");
+        palette
+    }
+
+    #[test]
+    fn test_identifiers() {
+        assert_eq!(pascal_case("pantone-100-c"), "Pantone100C");
+        assert_eq!(camel_case("pantone-100-c"), "pantone100C");
+        assert_eq!(type_name("2025 brand"), "Palette2025Brand");
+        assert_eq!(argb(&hex_to_rgb("#1d4e89").unwrap()), "0xFF1D4E89");
+
+        // Keywords are escaped in each language's own way
+        assert_eq!(swift_name("default"), "`default`");
+        assert_eq!(swift_name("new"), "new");
+        assert_eq!(dart_name("new"), "new_");
+        assert_eq!(dart_name("in-stock"), "inStock");
+        assert_eq!(android_name("class"), "class_");
+        assert_eq!(android_name("pantone-100-c"), "pantone_100_c");
+
+        let mut palette = Palette::new("Keywords");
+        palette.push(
+            PantoneColor::custom("Default", hex_to_rgb("#1d4e89").unwrap()),
+            "",
+        );
+        assert!(swiftui(&palette).contains("static let `default` = "));
+        assert!(flutter(&palette).contains("static const default_ = "));
+        assert!(android_xml(&palette).contains("<color name=\"default_\">"));
+        assert!(compose(&palette).contains("val Default = "));
+    }
+
+    #[test]
+    fn test_android_xml() {
+        assert_eq!(android_xml(&palette()), include_str!("golden/colors.xml"));
+    }
+
+    #[test]
+    fn test_swiftui() {
+        assert_eq!(
+            swiftui(&palette()),
+            include_str!("golden/Color+Brand2025.swift")
+        );
+    }
+
+    #[test]
+    fn test_asset_catalog() {
+        let files = asset_catalog(&palette());
+        let paths: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            [
+                "Brand2025.xcassets/Contents.json",
+                "Brand2025.xcassets/pantone100C.colorset/Contents.json",
+                "Brand2025.xcassets/pantoneCloudDancerTcx.colorset/Contents.json",
+                "Brand2025.xcassets/harbourBlueHb01.colorset/Contents.json",
+            ]
+        );
+        assert_eq!(files[0].1, include_str!("golden/xcassets/Contents.json"));
+        assert_eq!(
+            files[1].1,
+            include_str!("golden/xcassets/pantone100C.colorset/Contents.json")
+        );
+    }
+
+    #[test]
+    fn test_flutter() {
+        assert_eq!(
+            flutter(&palette()),
+            include_str!("golden/brand_2025_colors.dart")
+        );
+    }
+
+    #[test]
+    fn test_compose() {
+        assert_eq!(
+            compose(&palette()),
+            include_str!("golden/Brand2025Colors.kt")
+        );
+    }
+}
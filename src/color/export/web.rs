@@ -0,0 +1,134 @@
+use super::{color_keys, heading};
+use crate::color::palette::Palette;
+use serde_json::{json, Map};
+
+pub fn css(palette: &Palette) -> String {
+    let mut text = format!("/* {} */\n:root {{\n", heading(palette));
+    for (key, entry) in color_keys(palette).iter().zip(&palette.entries) {
+        text.push_str(&format!("  --{}: {};\n", key, entry.color.hex));
+    }
+    text.push_str("}\n");
+    text
+}
+
+/// Sass or Less variables, which differ only in their `sigil`.
+pub fn variables(palette: &Palette, sigil: char) -> String {
+    let mut text = format!("// {}\n", heading(palette));
+    for (key, entry) in color_keys(palette).iter().zip(&palette.entries) {
+        text.push_str(&format!("{}{}: {};\n", sigil, key, entry.color.hex));
+    }
+    text
+}
+
+pub fn tailwind(palette: &Palette) -> String {
+    let mut text = format!(
+        "/** {}. Use as, or spread into, theme.colors. */\nmodule.exports = {{\n",
+        heading(palette)
+    );
+    for (key, entry) in color_keys(palette).iter().zip(&palette.entries) {
+        text.push_str(&format!("  '{}': '{}',\n", key, entry.color.hex));
+    }
+    text.push_str("};\n");
+    text
+}
+
+/// One group named after the palette, holding a colour token per entry with
+/// its full name as the description and lon's own details under `$extensions`.
+pub fn design_tokens(palette: &Palette) -> String {
+    let group = match super::slugify(&palette.name) {
+        group if group.is_empty() => "colors".to_string(),
+        group => group,
+    };
+    let mut tokens = Map::new();
+    tokens.insert("$type".to_string(), json!("color"));
+    tokens.insert("$description".to_string(), json!(palette.name));
+    for (key, entry) in color_keys(palette).iter().zip(&palette.entries) {
+        let color = &entry.color;
+        let component = |channel: u8| (channel as f64 / 255.0 * 10000.0).round() / 10000.0;
+        let mut extension = Map::new();
+        extension.insert("library".to_string(), json!(color.library.id()));
+        extension.insert("name".to_string(), json!(color.name));
+        if let Some(code) = color.code() {
+            extension.insert("code".to_string(), json!(code));
+        }
+        if !entry.notes.is_empty() {
+            extension.insert("notes".to_string(), json!(entry.notes));
+        }
+        tokens.insert(
+            key.clone(),
+            json!({
+                "$value": {
+                    "colorSpace": "srgb",
+                    "components": [
+                        component(color.rgb.r),
+                        component(color.rgb.g),
+                        component(color.rgb.b),
+                    ],
+                    "hex": color.hex,
+                },
+                "$description": color.full_name(),
+                "$extensions": { "dev.myyc.lon": extension },
+            }),
+        );
+    }
+
+    let mut text = serde_json::to_string_pretty(&json!({ group: tokens })).unwrap_or_default();
+    text.push('\n');
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::conversion::hex_to_rgb;
+    use crate::color::model::{ColorLibrary, PantoneColor};
+
+    fn palette() -> Palette {
+        let coated = PantoneColor::test_in(ColorLibrary::SolidCoated, "PANTONE 100-C", "#f6eb61");
+        let mut palette = Palette::new("Brand");
+        palette.push(coated, "Logo yellow");
+        palette.push(
+            PantoneColor::custom("Harbour \"Blue\"", hex_to_rgb("#1d4e89").unwrap()),
+            "",
+        );
+        palette
+    }
+
+    #[test]
+    fn test_stylesheets() {
+        assert_eq!(
+            css(&palette()),
+            "/* Brand — exported from lon */\n:root {\n  --pantone-100-c: #f6eb61;\n  \
+             --harbour-blue: #1d4e89;\n}\n"
+        );
+        assert_eq!(
+            variables(&palette(), '$'),
+            "// Brand — exported from lon\n$pantone-100-c: #f6eb61;\n$harbour-blue: #1d4e89;\n"
+        );
+        assert!(tailwind(&palette()).contains("  'pantone-100-c': '#f6eb61',\n"));
+    }
+
+    #[test]
+    fn test_design_tokens() {
+        let text = design_tokens(&palette());
+        // Tools read the group's type before its tokens
+        assert!(text.starts_with("{\n  \"brand\": {\n    \"$type\": \"color\",\n"));
+        let tokens: serde_json::Value = serde_json::from_str(&text).unwrap();
+        let group = &tokens["brand"];
+        assert_eq!(group["$type"], "color");
+        assert_eq!(group["$description"], "Brand");
+
+        let token = &group["pantone-100-c"];
+        assert_eq!(token["$value"]["hex"], "#f6eb61");
+        assert_eq!(token["$value"]["colorSpace"], "srgb");
+        assert_eq!(token["$value"]["components"][0], 0.9647);
+        assert_eq!(token["$description"], "PANTONE 100 C");
+        let lon = &token["$extensions"]["dev.myyc.lon"];
+        assert_eq!(lon["library"], "solid-coated");
+        assert_eq!(lon["code"], "100-C");
+        assert_eq!(lon["notes"], "Logo yellow");
+
+        assert_eq!(group["harbour-blue"]["$description"], "Harbour \"Blue\"");
+        assert!(design_tokens(&Palette::new("★")).contains("\"colors\""));
+    }
+}
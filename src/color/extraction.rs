@@ -0,0 +1,183 @@
+use super::conversion::{oklab_to_rgb, rgb_to_oklab};
+use super::model::{Oklab, Rgb};
+
+/// Upper bound on pixels fed to the clustering; larger inputs are subsampled.
+const MAX_SAMPLES: usize = 20_000;
+const MAX_ITERATIONS: usize = 24;
+
+/// One cluster of an image: its mean colour and the fraction of pixels in it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DominantColor {
+    pub rgb: Rgb,
+    pub share: f32,
+}
+
+fn distance2(a: &Oklab, b: &Oklab) -> f32 {
+    (a.l - b.l).powi(2) + (a.a - b.a).powi(2) + (a.b - b.b).powi(2)
+}
+
+/// Cluster `pixels` into at most `count` dominant colours with k-means in OKLab.
+///
+/// Seeding is deterministic (farthest-point), so the same image always yields
+/// the same palette. Results are ordered by share, largest first.
+pub fn extract_dominant_colors(pixels: &[Rgb], count: usize) -> Vec<DominantColor> {
+    if pixels.is_empty() || count == 0 {
+        return Vec::new();
+    }
+
+    let step = pixels.len().div_ceil(MAX_SAMPLES);
+    let samples: Vec<Oklab> = pixels.iter().step_by(step).map(rgb_to_oklab).collect();
+
+    // Farthest-point seeding: start from the mean, then keep adding the sample
+    // farthest from every centroid chosen so far.
+    let mut centroids = vec![mean(&samples)];
+    let mut nearest: Vec<f32> = samples
+        .iter()
+        .map(|s| distance2(s, &centroids[0]))
+        .collect();
+    while centroids.len() < count {
+        let Some((index, &farthest)) = nearest.iter().enumerate().max_by(|a, b| a.1.total_cmp(b.1))
+        else {
+            break;
+        };
+        if farthest <= f32::EPSILON {
+            // Fewer distinct colours than requested
+            break;
+        }
+        let centroid = samples[index];
+        for (d, s) in nearest.iter_mut().zip(&samples) {
+            *d = d.min(distance2(s, &centroid));
+        }
+        centroids.push(centroid);
+    }
+
+    let mut assignment = vec![0usize; samples.len()];
+    for _ in 0..MAX_ITERATIONS {
+        let mut changed = false;
+        for (slot, sample) in assignment.iter_mut().zip(&samples) {
+            let closest = closest_centroid(sample, &centroids);
+            if *slot != closest {
+                *slot = closest;
+                changed = true;
+            }
+        }
+
+        let mut sums = vec![(0.0f32, 0.0f32, 0.0f32, 0usize); centroids.len()];
+        for (cluster, sample) in assignment.iter().zip(&samples) {
+            let sum = &mut sums[*cluster];
+            sum.0 += sample.l;
+            sum.1 += sample.a;
+            sum.2 += sample.b;
+            sum.3 += 1;
+        }
+        for (centroid, (l, a, b, n)) in centroids.iter_mut().zip(sums) {
+            if n > 0 {
+                let n = n as f32;
+                *centroid = Oklab {
+                    l: l / n,
+                    a: a / n,
+                    b: b / n,
+                };
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    let mut counts = vec![0usize; centroids.len()];
+    for cluster in &assignment {
+        counts[*cluster] += 1;
+    }
+
+    let total = samples.len() as f32;
+    let mut colors: Vec<DominantColor> = centroids
+        .iter()
+        .zip(counts)
+        .filter(|(_, n)| *n > 0)
+        .map(|(centroid, n)| DominantColor {
+            rgb: oklab_to_rgb(centroid),
+            share: n as f32 / total,
+        })
+        .collect();
+    colors.sort_by(|a, b| b.share.total_cmp(&a.share));
+    colors
+}
+
+fn closest_centroid(sample: &Oklab, centroids: &[Oklab]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .min_by(|a, b| distance2(sample, a.1).total_cmp(&distance2(sample, b.1)))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn mean(samples: &[Oklab]) -> Oklab {
+    let n = samples.len().max(1) as f32;
+    let (l, a, b) = samples
+        .iter()
+        .fold((0.0, 0.0, 0.0), |(l, a, b), s| (l + s.l, a + s.a, b + s.b));
+    Oklab {
+        l: l / n,
+        a: a / n,
+        b: b / n,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RED: Rgb = Rgb {
+        r: 220,
+        g: 30,
+        b: 40,
+    };
+    const BLUE: Rgb = Rgb {
+        r: 20,
+        g: 60,
+        b: 200,
+    };
+    const CREAM: Rgb = Rgb {
+        r: 240,
+        g: 230,
+        b: 200,
+    };
+
+    #[test]
+    fn test_extracts_clusters_with_shares() {
+        let mut pixels = vec![RED; 600];
+        pixels.extend(vec![BLUE; 300]);
+        pixels.extend(vec![CREAM; 100]);
+
+        let colors = extract_dominant_colors(&pixels, 3);
+        assert_eq!(colors.len(), 3);
+        assert_eq!(colors[0].rgb, RED);
+        assert_eq!(colors[1].rgb, BLUE);
+        assert_eq!(colors[2].rgb, CREAM);
+        assert!((colors[0].share - 0.6).abs() < 1e-6);
+        assert!((colors[1].share - 0.3).abs() < 1e-6);
+        assert!((colors[2].share - 0.1).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_fewer_distinct_colors_than_requested() {
+        let pixels = vec![BLUE; 50];
+        let colors = extract_dominant_colors(&pixels, 5);
+        assert_eq!(
+            colors,
+            vec![DominantColor {
+                rgb: BLUE,
+                share: 1.0
+            }]
+        );
+    }
+
+    #[test]
+    fn test_empty_input() {
+        assert!(extract_dominant_colors(&[], 4).is_empty());
+        assert!(extract_dominant_colors(&[RED], 0).is_empty());
+    }
+}
@@ -0,0 +1,69 @@
+use super::model::ColorKey;
+use super::versioned::Versioned;
+use serde::{Deserialize, Serialize};
+
+/// Starred colours, in the order they were starred.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Favourites {
+    colors: Vec<ColorKey>,
+}
+
+impl Versioned for Favourites {
+    const KIND: &'static str = "favourites";
+    const VERSION: u32 = 1;
+}
+
+impl Favourites {
+    pub fn contains(&self, key: &ColorKey) -> bool {
+        self.colors.contains(key)
+    }
+
+    /// Star `key`, or unstar it if it already was. Returns whether it is now starred.
+    pub fn toggle(&mut self, key: &ColorKey) -> bool {
+        if let Some(index) = self.colors.iter().position(|k| k == key) {
+            self.colors.remove(index);
+            false
+        } else {
+            self.colors.push(key.clone());
+            true
+        }
+    }
+
+    /// Point a starred `from` at `to` instead, e.g. after the colour was renamed.
+    pub fn rename(&mut self, from: &ColorKey, to: &ColorKey) {
+        if let Some(key) = self.colors.iter_mut().find(|k| *k == from) {
+            *key = to.clone();
+        }
+    }
+
+    pub fn keys(&self) -> &[ColorKey] {
+        &self.colors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_toggle_and_round_trip() {
+        let coated = ColorKey::new("solid-coated", "PANTONE 100-C");
+        let tcx = ColorKey::new("tcx", "cloud-dancer");
+
+        let mut favourites = Favourites::default();
+        assert!(favourites.toggle(&coated));
+        assert!(favourites.toggle(&tcx));
+        assert!(!favourites.toggle(&coated));
+        assert!(favourites.toggle(&coated));
+        assert_eq!(favourites.keys(), &[tcx.clone(), coated.clone()]);
+
+        let parsed = Favourites::from_json(&favourites.to_json().unwrap()).unwrap();
+        assert!(parsed.contains(&tcx));
+        assert!(parsed.contains(&coated));
+        assert_eq!(parsed.keys(), favourites.keys());
+
+        let renamed = ColorKey::new("custom", "Harbour");
+        favourites.rename(&tcx, &renamed);
+        assert_eq!(favourites.keys(), &[renamed, coated]);
+    }
+}
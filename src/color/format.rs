@@ -0,0 +1,223 @@
+use super::conversion::rgb_to_hex;
+use super::model::PantoneColor;
+
+/// Placeholders understood by a custom copy template.
+pub const TEMPLATE_PLACEHOLDERS: &[&str] = &[
+    "{name}",
+    "{code}",
+    "{library}",
+    "{hex}",
+    "{HEX}",
+    "{r}",
+    "{g}",
+    "{b}",
+    "{h}",
+    "{s}",
+    "{l}",
+];
+
+/// How a colour is written to the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CopyFormat {
+    /// `#bb2649`
+    #[default]
+    Hex,
+    /// `#BB2649`
+    HexUpper,
+    /// `bb2649`
+    HexBare,
+    /// `BB2649`
+    HexUpperBare,
+    /// `rgb(187, 38, 73)`
+    Rgb,
+    /// `0xBB2649`
+    Hex0x,
+    /// `Color(0xFFBB2649)`
+    Flutter,
+    /// `UIColor(red: 0.733, green: 0.149, blue: 0.286, alpha: 1.0)`
+    UiColor,
+    /// The name as swatch books print it, e.g. `PANTONE 100 C`.
+    Name,
+    /// A user-defined template, see [`TEMPLATE_PLACEHOLDERS`].
+    Template,
+}
+
+impl CopyFormat {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::HexUpper => "hex-upper",
+            Self::HexBare => "hex-bare",
+            Self::HexUpperBare => "hex-upper-bare",
+            Self::Rgb => "rgb",
+            Self::Hex0x => "0x",
+            Self::Flutter => "flutter",
+            Self::UiColor => "uicolor",
+            Self::Name => "name",
+            Self::Template => "template",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|f| f.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Hex => "Hex",
+            Self::HexUpper => "Hex, Uppercase",
+            Self::HexBare => "Hex without #",
+            Self::HexUpperBare => "Hex without #, Uppercase",
+            Self::Rgb => "CSS rgb()",
+            Self::Hex0x => "0xRRGGBB",
+            Self::Flutter => "Flutter Color",
+            Self::UiColor => "UIColor",
+            Self::Name => "Pantone Name",
+            Self::Template => "Custom Template",
+        }
+    }
+
+    pub fn all() -> &'static [CopyFormat] {
+        &[
+            Self::Hex,
+            Self::HexUpper,
+            Self::HexBare,
+            Self::HexUpperBare,
+            Self::Rgb,
+            Self::Hex0x,
+            Self::Flutter,
+            Self::UiColor,
+            Self::Name,
+            Self::Template,
+        ]
+    }
+
+    /// Write `color` in this format. `template` is only used by [`CopyFormat::Template`].
+    pub fn format(&self, color: &PantoneColor, template: &str) -> String {
+        let hex = rgb_to_hex(&color.rgb);
+        let bare = hex.trim_start_matches('#');
+        let rgb = &color.rgb;
+        match self {
+            Self::Hex => hex.clone(),
+            Self::HexUpper => hex.to_uppercase(),
+            Self::HexBare => bare.to_string(),
+            Self::HexUpperBare => bare.to_uppercase(),
+            Self::Rgb => rgb.to_string(),
+            Self::Hex0x => format!("0x{}", bare.to_uppercase()),
+            Self::Flutter => format!("Color(0xFF{})", bare.to_uppercase()),
+            Self::UiColor => format!(
+                "UIColor(red: {:.3}, green: {:.3}, blue: {:.3}, alpha: 1.0)",
+                rgb.r as f32 / 255.0,
+                rgb.g as f32 / 255.0,
+                rgb.b as f32 / 255.0
+            ),
+            Self::Name => color.full_name(),
+            Self::Template => expand_template(template, color),
+        }
+    }
+}
+
+/// Replace the [`TEMPLATE_PLACEHOLDERS`] in `template`. Anything else in braces is kept as is.
+pub fn expand_template(template: &str, color: &PantoneColor) -> String {
+    let hex = rgb_to_hex(&color.rgb);
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        out.push_str(&rest[..start]);
+        let tail = &rest[start..];
+        let Some(end) = tail.find('}') else {
+            rest = tail;
+            break;
+        };
+        let key = &tail[1..end];
+        if key.contains('{') {
+            // A stray brace before the real placeholder
+            out.push('{');
+            rest = &tail[1..];
+            continue;
+        }
+        let value = match key {
+            "name" => Some(color.full_name()),
+            "code" => Some(color.code().unwrap_or_default().to_string()),
+            "library" => Some(color.library.display_name().to_string()),
+            "hex" => Some(hex.clone()),
+            "HEX" => Some(hex.to_uppercase()),
+            "r" => Some(color.rgb.r.to_string()),
+            "g" => Some(color.rgb.g.to_string()),
+            "b" => Some(color.rgb.b.to_string()),
+            "h" => Some(format!("{:.0}", color.hsl.h)),
+            "s" => Some(format!("{:.0}", color.hsl.s)),
+            "l" => Some(format!("{:.0}", color.hsl.l)),
+            _ => None,
+        };
+        match value {
+            Some(value) => out.push_str(&value),
+            None => out.push_str(&tail[..=end]),
+        }
+        rest = &tail[end + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::model::ColorLibrary;
+
+    fn viva_magenta() -> PantoneColor {
+        PantoneColor::test_in(ColorLibrary::FashionHomeTcx, "viva-magenta", "#BB2649")
+    }
+
+    fn coated() -> PantoneColor {
+        PantoneColor::test_in(ColorLibrary::SolidCoated, "PANTONE 100-C", "#F6EB61")
+    }
+
+    #[test]
+    fn test_formats() {
+        let color = viva_magenta();
+        let formatted: Vec<String> = CopyFormat::all()
+            .iter()
+            .map(|f| f.format(&color, "{name}"))
+            .collect();
+        assert_eq!(
+            formatted,
+            [
+                "#bb2649",
+                "#BB2649",
+                "bb2649",
+                "BB2649",
+                "rgb(187, 38, 73)",
+                "0xBB2649",
+                "Color(0xFFBB2649)",
+                "UIColor(red: 0.733, green: 0.149, blue: 0.286, alpha: 1.0)",
+                "PANTONE Viva Magenta TCX",
+                "PANTONE Viva Magenta TCX",
+            ]
+        );
+        assert_eq!(CopyFormat::Name.format(&coated(), ""), "PANTONE 100 C");
+    }
+
+    #[test]
+    fn test_ids_round_trip() {
+        for format in CopyFormat::all() {
+            assert_eq!(CopyFormat::from_id(format.id()), Some(*format));
+        }
+        assert_eq!(CopyFormat::from_id("nope"), None);
+    }
+
+    #[test]
+    fn test_expand_template() {
+        let color = coated();
+        assert_eq!(
+            expand_template("{code}: {r},{g},{b} {HEX} {unknown} {", &color),
+            "100-C: 246,235,97 #F6EB61 {unknown} {"
+        );
+        assert_eq!(expand_template("", &color), "");
+        assert_eq!(expand_template("{{name}}", &color), "{PANTONE 100 C}");
+        assert_eq!(
+            expand_template("{name} ({library})", &viva_magenta()),
+            "PANTONE Viva Magenta TCX (Fashion, Home + Interiors (TCX))"
+        );
+    }
+}
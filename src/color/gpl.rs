@@ -0,0 +1,138 @@
+use super::model::{PantoneColor, Rgb};
+use super::palette::Palette;
+use super::swatch_file::SwatchFileError;
+
+const HEADER: &str = "GIMP Palette";
+
+/// Read a GIMP or Inkscape palette. Its `Name:` header names the palette,
+/// falling back to `name`; unnamed colours are named by their hex value.
+pub fn read(text: &str, name: &str) -> Result<Palette, SwatchFileError> {
+    let mut lines = text.trim_start_matches('\u{feff}').lines();
+    if lines.next().map(str::trim) != Some(HEADER) {
+        return Err(SwatchFileError::WrongFormat);
+    }
+
+    let mut palette = Palette::new(name);
+    for (number, line) in lines.enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("Name:") {
+            if !value.trim().is_empty() {
+                palette.name = value.trim().to_string();
+            }
+            continue;
+        }
+        if line.starts_with("Columns:") {
+            continue;
+        }
+
+        // Three channels, then everything after them is the name
+        let mut rest = line;
+        let mut channels = [0u8; 3];
+        for channel in &mut channels {
+            let (value, after) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+            *channel = value.parse().map_err(|_| {
+                SwatchFileError::Invalid(format!("Line {} is not a colour", number + 2))
+            })?;
+            rest = after.trim_start();
+        }
+        let rgb = Rgb {
+            r: channels[0],
+            g: channels[1],
+            b: channels[2],
+        };
+        let mut color = PantoneColor::custom(rest.trim(), rgb);
+        if color.name.is_empty() {
+            color.name = color.hex.clone();
+        }
+        palette.push(color, "");
+    }
+    Ok(palette)
+}
+
+/// Write `palettes` as one palette named after the first, since the format has
+/// no groups; a comment marks where each palette starts.
+pub fn write(palettes: &[Palette]) -> String {
+    let name = palettes.first().map_or("", |palette| palette.name.as_str());
+    let mut text = format!("{}\nName: {}\nColumns: 0\n#\n", HEADER, single_line(name));
+    for palette in palettes {
+        if palettes.len() > 1 {
+            text.push_str(&format!("# {}\n", single_line(&palette.name)));
+        }
+        for entry in &palette.entries {
+            let rgb = entry.color.rgb;
+            text.push_str(&format!(
+                "{:>3} {:>3} {:>3}\t{}\n",
+                rgb.r,
+                rgb.g,
+                rgb.b,
+                single_line(&entry.color.full_name())
+            ));
+        }
+    }
+    text
+}
+
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::conversion::hex_to_rgb;
+    use crate::color::model::ColorLibrary;
+
+    #[test]
+    fn test_read() {
+        let text = "GIMP Palette\r\nName: Brand\r\nColumns: 4\r\n# Exported\r\n\
+                    29  78 137\tHarbour Blue\r\n255 255 255\r\n";
+        let palette = read(text, "brand").unwrap();
+        assert_eq!(palette.name, "Brand");
+        assert_eq!(palette.entries.len(), 2);
+        assert_eq!(palette.entries[0].color.name, "Harbour Blue");
+        assert_eq!(palette.entries[0].color.hex, "#1d4e89");
+        assert_eq!(palette.entries[1].color.name, "#ffffff");
+
+        assert_eq!(
+            read("Name: x\n", "x").unwrap_err(),
+            SwatchFileError::WrongFormat
+        );
+        assert!(matches!(
+            read("GIMP Palette\n300 0 0 Red\n", "x"),
+            Err(SwatchFileError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let coated = PantoneColor::test_in(ColorLibrary::SolidCoated, "PANTONE 286-C", "#0033a0");
+        let mut palette = Palette::new("Brand");
+        palette.push(coated, "");
+        palette.push(
+            PantoneColor::custom("Sand", hex_to_rgb("#e3d3b0").unwrap()),
+            "",
+        );
+        let mut accents = Palette::new("Accents");
+        accents.push(
+            PantoneColor::custom("Coral", hex_to_rgb("#ff6f61").unwrap()),
+            "",
+        );
+
+        let text = write(&[palette, accents]);
+        assert!(text.starts_with("GIMP Palette\nName: Brand\n"));
+        assert!(text.contains("  0  51 160\tPANTONE 286 C\n"));
+        assert!(text.contains("# Accents\n"));
+
+        let parsed = read(&text, "brand").unwrap();
+        let names: Vec<&str> = parsed
+            .entries
+            .iter()
+            .map(|entry| entry.color.name.as_str())
+            .collect();
+        assert_eq!(names, ["PANTONE 286 C", "Sand", "Coral"]);
+        assert_eq!(parsed.entries[2].color.hex, "#ff6f61");
+    }
+}
@@ -0,0 +1,190 @@
+use super::model::{ColorFamily, PantoneColor, SortOrder};
+use std::cmp::Ordering;
+
+/// How a library page is split into sections.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Grouping {
+    /// One section per `ColorFamily`, in `ColorFamily::all()` order.
+    #[default]
+    Family,
+    /// Sections are bands of the active sort key (hue ranges, initials, ...).
+    SortKey,
+}
+
+impl Grouping {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Family => "family",
+            Self::SortKey => "sort-key",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|g| g.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Family => "Family",
+            Self::SortKey => "Sort Key",
+        }
+    }
+
+    pub fn all() -> &'static [Grouping] {
+        &[Self::Family, Self::SortKey]
+    }
+
+    /// Index of the section `color` falls in. Sections sort by this index.
+    pub fn section(&self, color: &PantoneColor, sort: SortOrder) -> u32 {
+        match self {
+            Self::Family => ColorFamily::all()
+                .iter()
+                .position(|f| *f == color.family)
+                .unwrap_or(0) as u32,
+            Self::SortKey => sort.band(color),
+        }
+    }
+
+    /// Header text for a section, e.g. "Reds" or "Hue 30–60°".
+    pub fn section_title(&self, section: u32, sort: SortOrder) -> String {
+        match self {
+            Self::Family => ColorFamily::all()
+                .get(section as usize)
+                .map(|f| f.plural_name().to_string())
+                .unwrap_or_default(),
+            Self::SortKey => sort.band_title(section),
+        }
+    }
+
+    /// Compact label for the fast-scroll index.
+    pub fn section_label(&self, section: u32, sort: SortOrder) -> String {
+        match self {
+            Self::Family => ColorFamily::all()
+                .get(section as usize)
+                .map(|f| f.display_name().chars().take(2).collect())
+                .unwrap_or_default(),
+            Self::SortKey => sort.band_label(section),
+        }
+    }
+
+    /// Full ordering: by section first, then by the sort key within it.
+    pub fn compare(&self, a: &PantoneColor, b: &PantoneColor, sort: SortOrder) -> Ordering {
+        self.section(a, sort)
+            .cmp(&self.section(b, sort))
+            .then_with(|| sort.compare(a, b))
+    }
+}
+
+impl SortOrder {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Name => "name",
+            Self::Hue => "hue",
+            Self::Saturation => "saturation",
+            Self::Lightness => "lightness",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|s| s.id() == id)
+    }
+
+    pub fn compare(&self, a: &PantoneColor, b: &PantoneColor) -> Ordering {
+        let by_key = match self {
+            Self::Name => Ordering::Equal,
+            Self::Hue => a.hsl.h.total_cmp(&b.hsl.h),
+            Self::Saturation => a.hsl.s.total_cmp(&b.hsl.s),
+            Self::Lightness => a.hsl.l.total_cmp(&b.hsl.l),
+        };
+        by_key.then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    }
+
+    /// Coarse bucket of the sort key: 30° hue bands, 10% saturation and
+    /// lightness bands, or the initial letter for names (26 for anything else).
+    pub fn band(&self, color: &PantoneColor) -> u32 {
+        match self {
+            Self::Name => color
+                .name
+                .chars()
+                .find(|c| c.is_alphanumeric())
+                .map(|c| c.to_ascii_uppercase())
+                .filter(|c| c.is_ascii_uppercase())
+                .map(|c| c as u32 - 'A' as u32)
+                .unwrap_or(26),
+            Self::Hue => ((color.hsl.h / 30.0) as u32).min(11),
+            Self::Saturation => ((color.hsl.s / 10.0) as u32).min(9),
+            Self::Lightness => ((color.hsl.l / 10.0) as u32).min(9),
+        }
+    }
+
+    pub fn band_title(&self, band: u32) -> String {
+        match self {
+            Self::Name => self.band_label(band),
+            Self::Hue => format!("Hue {}–{}°", band * 30, band * 30 + 30),
+            Self::Saturation => format!("Saturation {}–{}%", band * 10, band * 10 + 10),
+            Self::Lightness => format!("Lightness {}–{}%", band * 10, band * 10 + 10),
+        }
+    }
+
+    pub fn band_label(&self, band: u32) -> String {
+        match self {
+            Self::Name => char::from_u32('A' as u32 + band)
+                .filter(|c| c.is_ascii_uppercase())
+                .map(String::from)
+                .unwrap_or_else(|| "#".to_string()),
+            Self::Hue => format!("{}°", band * 30),
+            Self::Saturation | Self::Lightness => format!("{}", band * 10),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::model::ColorLibrary;
+
+    fn color(name: &str, hex: &str) -> PantoneColor {
+        PantoneColor::test_in(ColorLibrary::FashionHomeTcx, name, hex)
+    }
+
+    #[test]
+    fn test_family_sections() {
+        let red = color("red", "#d32f2f");
+        let blue = color("blue", "#1976d2");
+        let grouping = Grouping::Family;
+
+        assert_eq!(grouping.section(&red, SortOrder::Hue), 0);
+        assert_eq!(grouping.section_title(0, SortOrder::Hue), "Reds");
+        assert_eq!(
+            grouping.compare(&red, &blue, SortOrder::Name),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn test_sort_key_bands() {
+        let egret = color("egret", "#f3ece0");
+        let navy = color("navy-peony", "#223a5e");
+
+        assert_eq!(SortOrder::Name.band(&egret), 4);
+        assert_eq!(SortOrder::Name.band_label(4), "E");
+        assert_eq!(SortOrder::Name.band_label(26), "#");
+        assert_eq!(SortOrder::Hue.band(&navy), 7);
+        assert_eq!(SortOrder::Hue.band_title(7), "Hue 210–240°");
+        assert_eq!(SortOrder::Lightness.band(&color("white", "#ffffff")), 9);
+        assert_eq!(
+            Grouping::SortKey.compare(&navy, &egret, SortOrder::Name),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn test_ids_round_trip() {
+        for sort in SortOrder::all() {
+            assert_eq!(SortOrder::from_id(sort.id()), Some(*sort));
+        }
+        for grouping in Grouping::all() {
+            assert_eq!(Grouping::from_id(grouping.id()), Some(*grouping));
+        }
+    }
+}
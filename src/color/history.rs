@@ -0,0 +1,150 @@
+use super::model::PantoneColor;
+use super::versioned::Versioned;
+use serde::{Deserialize, Serialize};
+
+/// Oldest entries are dropped beyond this many.
+pub const MAX_HISTORY: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub color: PantoneColor,
+    /// Unix time, in seconds.
+    pub viewed_at: i64,
+}
+
+/// Colours the user opened, most recent first, each listed once.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct History {
+    entries: Vec<HistoryEntry>,
+}
+
+impl Versioned for History {
+    const KIND: &'static str = "history";
+    const VERSION: u32 = 1;
+}
+
+impl History {
+    /// Move `color` to the front, stamped with `viewed_at`.
+    pub fn record(&mut self, color: &PantoneColor, viewed_at: i64) {
+        let key = color.key();
+        self.entries.retain(|entry| entry.color.key() != key);
+        self.entries.insert(
+            0,
+            HistoryEntry {
+                color: color.clone(),
+                viewed_at,
+            },
+        );
+        self.entries.truncate(MAX_HISTORY);
+    }
+
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
+
+/// Back and forward through the colours opened this session, like a browser.
+#[derive(Debug, Clone, Default)]
+pub struct ViewTrail {
+    back: Vec<PantoneColor>,
+    current: Option<PantoneColor>,
+    forward: Vec<PantoneColor>,
+}
+
+impl ViewTrail {
+    /// Open `color`, dropping anything ahead of the current one.
+    pub fn visit(&mut self, color: &PantoneColor) {
+        if self.current.as_ref().map(|c| c.key()) == Some(color.key()) {
+            return;
+        }
+        if let Some(current) = self.current.replace(color.clone()) {
+            self.back.push(current);
+            if self.back.len() > MAX_HISTORY {
+                self.back.remove(0);
+            }
+        }
+        self.forward.clear();
+    }
+
+    pub fn go_back(&mut self) -> Option<&PantoneColor> {
+        let previous = self.back.pop()?;
+        if let Some(current) = self.current.replace(previous) {
+            self.forward.push(current);
+        }
+        self.current.as_ref()
+    }
+
+    pub fn go_forward(&mut self) -> Option<&PantoneColor> {
+        let next = self.forward.pop()?;
+        if let Some(current) = self.current.replace(next) {
+            self.back.push(current);
+        }
+        self.current.as_ref()
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.back.is_empty()
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        !self.forward.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::conversion::hex_to_rgb;
+
+    fn color(name: &str) -> PantoneColor {
+        PantoneColor::custom(name, hex_to_rgb("#bb2649").unwrap())
+    }
+
+    #[test]
+    fn test_history_is_deduplicated_and_bounded() {
+        let mut history = History::default();
+        history.record(&color("a"), 1);
+        history.record(&color("b"), 2);
+        history.record(&color("a"), 3);
+        let names: Vec<&str> = history
+            .entries()
+            .iter()
+            .map(|e| e.color.name.as_str())
+            .collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(history.entries()[0].viewed_at, 3);
+
+        for i in 0..MAX_HISTORY + 10 {
+            history.record(&color(&i.to_string()), i as i64);
+        }
+        assert_eq!(history.entries().len(), MAX_HISTORY);
+
+        let parsed = History::from_json(&history.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.entries().len(), MAX_HISTORY);
+    }
+
+    #[test]
+    fn test_trail_back_and_forward() {
+        let mut trail = ViewTrail::default();
+        assert!(!trail.can_go_back());
+        trail.visit(&color("a"));
+        trail.visit(&color("b"));
+        trail.visit(&color("b"));
+        trail.visit(&color("c"));
+
+        assert_eq!(trail.go_back().unwrap().name, "b");
+        assert_eq!(trail.go_back().unwrap().name, "a");
+        assert!(trail.go_back().is_none());
+        assert_eq!(trail.go_forward().unwrap().name, "b");
+        assert!(trail.can_go_forward());
+
+        // Opening something new drops the forward entries
+        trail.visit(&color("d"));
+        assert!(!trail.can_go_forward());
+        assert_eq!(trail.go_back().unwrap().name, "b");
+    }
+}
@@ -1,7 +1,9 @@
 use super::conversion::{classify_family, hex_to_rgb, rgb_to_hsl};
-use super::model::{ColorLibrary, PantoneColor};
+use super::difference::{nearest_matches, ColorMatch, DeltaE};
+use super::model::{ColorKey, ColorLibrary, PantoneColor, Rgb};
 use gtk::gio;
 use serde::Deserialize;
+use std::cell::{Ref, RefCell};
 use std::collections::HashMap;
 
 #[derive(Deserialize)]
@@ -41,6 +43,7 @@ fn load_tcx_colors() -> Vec<PantoneColor> {
                 hsl,
                 family,
                 library: ColorLibrary::FashionHomeTcx,
+                custom_code: None,
             })
         })
         .collect()
@@ -70,13 +73,15 @@ fn load_solid_coated_colors() -> Vec<PantoneColor> {
                 hsl,
                 family,
                 library: ColorLibrary::SolidCoated,
+                custom_code: None,
             })
         })
         .collect()
 }
 
 pub struct ColorDatabase {
-    colors: HashMap<ColorLibrary, Vec<PantoneColor>>,
+    /// The Custom library changes as the user edits it; the rest are fixed.
+    colors: RefCell<HashMap<ColorLibrary, Vec<PantoneColor>>>,
 }
 
 impl ColorDatabase {
@@ -84,19 +89,58 @@ impl ColorDatabase {
         let mut colors = HashMap::new();
         colors.insert(ColorLibrary::FashionHomeTcx, load_tcx_colors());
         colors.insert(ColorLibrary::SolidCoated, load_solid_coated_colors());
-        Self { colors }
+        Self {
+            colors: RefCell::new(colors),
+        }
     }
 
-    pub fn get_library(&self, library: ColorLibrary) -> &[PantoneColor] {
-        self.colors
-            .get(&library)
-            .map(|v| v.as_slice())
-            .unwrap_or(&[])
+    pub fn get_library(&self, library: ColorLibrary) -> Ref<'_, [PantoneColor]> {
+        Ref::map(self.colors.borrow(), |colors| {
+            colors.get(&library).map(|v| v.as_slice()).unwrap_or(&[])
+        })
     }
 
     pub fn library_count(&self, library: ColorLibrary) -> usize {
         self.get_library(library).len()
     }
+
+    /// Replace the colours of the Custom library.
+    pub fn set_custom_colors(&self, colors: Vec<PantoneColor>) {
+        self.colors
+            .borrow_mut()
+            .insert(ColorLibrary::Custom, colors);
+    }
+
+    /// The colour called `name` (ignoring case) in any library.
+    pub fn find_by_name(&self, name: &str) -> Option<PantoneColor> {
+        let name = name.trim();
+        let colors = self.colors.borrow();
+        ColorLibrary::all()
+            .iter()
+            .filter_map(|library| colors.get(library))
+            .flatten()
+            .find(|color| color.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// The colour `key` refers to, if its library still has it.
+    pub fn find(&self, key: &ColorKey) -> Option<PantoneColor> {
+        let library = ColorLibrary::from_id(&key.library)?;
+        self.get_library(library)
+            .iter()
+            .find(|color| color.name == key.name)
+            .cloned()
+    }
+
+    /// The closest Pantone colours to `rgb` across every bundled library.
+    pub fn nearest(&self, rgb: &Rgb, formula: DeltaE, limit: usize) -> Vec<ColorMatch> {
+        let colors = self.colors.borrow();
+        let candidates = ColorLibrary::pantone()
+            .iter()
+            .filter_map(|library| colors.get(library))
+            .flatten();
+        nearest_matches(candidates, rgb, formula, limit)
+    }
 }
 
 impl Default for ColorDatabase {
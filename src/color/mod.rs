@@ -1,6 +1,37 @@
+mod aco;
+mod annotations;
+mod ase;
 mod conversion;
+mod custom;
+mod difference;
+mod export;
+mod extraction;
+mod favourites;
+mod format;
+mod gpl;
+mod grouping;
+mod history;
 mod library;
 mod model;
+mod notation;
+mod palette;
+mod soc;
+mod swatch_file;
+mod versioned;
 
+pub use annotations::{Annotation, AnnotationKey, Annotations, TagQuery};
+pub use conversion::{hex_to_rgb, prefers_dark_text, rgb_to_hex, rgb_to_lab};
+pub use custom::{CustomColor, CustomLibrary};
+pub use difference::{ColorMatch, DeltaE, LabDifference};
+pub use export::CodeFormat;
+pub use extraction::{extract_dominant_colors, DominantColor};
+pub use favourites::Favourites;
+pub use format::{CopyFormat, TEMPLATE_PLACEHOLDERS};
+pub use grouping::Grouping;
+pub use history::{History, HistoryEntry, ViewTrail};
 pub use library::ColorDatabase;
-pub use model::{ColorFamily, ColorLibrary, PantoneColor, SortOrder};
+pub use model::{ColorFamily, ColorKey, ColorLibrary, PantoneColor, Rgb, SortOrder};
+pub use notation::Notation;
+pub use palette::{Palette, PaletteCollection};
+pub use swatch_file::SwatchFormat;
+pub use versioned::Versioned;
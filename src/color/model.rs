@@ -1,3 +1,4 @@
+use super::conversion::{classify_family, rgb_to_hex, rgb_to_hsl};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 
@@ -9,6 +10,119 @@ pub struct PantoneColor {
     pub hsl: Hsl,
     pub family: ColorFamily,
     pub library: ColorLibrary,
+    /// The code the user gave a custom colour; Pantone codes come from the name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub custom_code: Option<String>,
+}
+
+impl PantoneColor {
+    /// Case-insensitive match against the name, the code or the hex value (with or
+    /// without `#`).
+    pub fn matches_query(&self, query: &str) -> bool {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return true;
+        }
+        let hex_query = query.trim_start_matches('#');
+        self.name.to_lowercase().contains(&query)
+            || self
+                .code()
+                .is_some_and(|code| code.to_lowercase().contains(&query))
+            || (!hex_query.is_empty() && self.hex.to_lowercase().contains(hex_query))
+    }
+
+    /// The Pantone code, for libraries whose data carries one (e.g. `100-C`).
+    pub fn code(&self) -> Option<&str> {
+        match self.library {
+            ColorLibrary::SolidCoated => {
+                Some(self.name.strip_prefix("PANTONE ").unwrap_or(&self.name))
+            }
+            ColorLibrary::FashionHomeTcx => None,
+            ColorLibrary::Custom => self.custom_code.as_deref(),
+        }
+    }
+
+    /// The name as other apps and swatch books print it, e.g. `PANTONE 100 C`
+    /// or `PANTONE Cloud Dancer TCX`; custom colours keep their own name,
+    /// followed by their code if they have one.
+    pub fn full_name(&self) -> String {
+        match self.library {
+            ColorLibrary::SolidCoated => {
+                let code = self.code().unwrap_or(&self.name);
+                match code.rsplit_once('-') {
+                    Some((number, suffix)) => format!("PANTONE {} {}", number, suffix),
+                    None => format!("PANTONE {}", code),
+                }
+            }
+            ColorLibrary::FashionHomeTcx => {
+                let words: Vec<String> = self
+                    .name
+                    .split('-')
+                    .filter(|word| !word.is_empty())
+                    .map(|word| {
+                        let mut chars = word.chars();
+                        chars
+                            .next()
+                            .map(|first| first.to_uppercase().chain(chars).collect())
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                format!("PANTONE {} TCX", words.join(" "))
+            }
+            ColorLibrary::Custom => match self.code() {
+                Some(code) => format!("{} {}", self.name, code),
+                None => self.name.clone(),
+            },
+        }
+    }
+
+    /// A colour the user entered by value rather than picked from a library.
+    pub fn custom(name: &str, rgb: Rgb) -> Self {
+        let hsl = rgb_to_hsl(&rgb);
+        Self {
+            name: name.to_string(),
+            hex: rgb_to_hex(&rgb),
+            rgb,
+            hsl,
+            family: classify_family(&hsl),
+            library: ColorLibrary::Custom,
+            custom_code: None,
+        }
+    }
+
+    /// A colour in `library` with the value `hex`, for tests.
+    #[cfg(test)]
+    pub fn test_in(library: ColorLibrary, name: &str, hex: &str) -> Self {
+        let rgb = super::conversion::hex_to_rgb(hex).unwrap();
+        Self {
+            library,
+            ..Self::custom(name, rgb)
+        }
+    }
+
+    pub fn key(&self) -> ColorKey {
+        ColorKey {
+            library: self.library.id().to_string(),
+            name: self.name.clone(),
+        }
+    }
+}
+
+/// Identifies a colour across sessions: its library id and its name, which is
+/// unique within the library.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ColorKey {
+    pub library: String,
+    pub name: String,
+}
+
+impl ColorKey {
+    pub fn new(library: &str, name: &str) -> Self {
+        Self {
+            library: library.to_string(),
+            name: name.to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
@@ -37,6 +151,108 @@ impl fmt::Display for Hsl {
     }
 }
 
+/// CIELAB under D65.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Lab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
+impl fmt::Display for Lab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "lab({:.1}% {:.1} {:.1})", self.l, self.a, self.b)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Hsv {
+    pub h: f32,
+    pub s: f32,
+    pub v: f32,
+}
+
+impl fmt::Display for Hsv {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hsv({:.0}, {:.0}%, {:.0}%)", self.h, self.s, self.v)
+    }
+}
+
+/// Hue, whiteness and blackness, as in CSS `hwb()`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Hwb {
+    pub h: f32,
+    pub w: f32,
+    pub b: f32,
+}
+
+impl fmt::Display for Hwb {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "hwb({:.0} {:.0}% {:.0}%)", self.h, self.w, self.b)
+    }
+}
+
+/// Naive device CMYK, without an ICC profile. Channels are percentages.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Cmyk {
+    pub c: f32,
+    pub m: f32,
+    pub y: f32,
+    pub k: f32,
+}
+
+impl fmt::Display for Cmyk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "cmyk({:.0}%, {:.0}%, {:.0}%, {:.0}%)",
+            self.c, self.m, self.y, self.k
+        )
+    }
+}
+
+/// Cylindrical CIELAB: lightness, chroma and hue angle.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Lch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+impl fmt::Display for Lch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "lch({:.1}% {:.1} {:.1})", self.l, self.c, self.h)
+    }
+}
+
+/// Cylindrical OKLab, as in CSS `oklch()`. Lightness is 0.0–1.0.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Oklch {
+    pub l: f32,
+    pub c: f32,
+    pub h: f32,
+}
+
+impl fmt::Display for Oklch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "oklch({:.1}% {:.3} {:.1})",
+            self.l * 100.0,
+            self.c,
+            self.h
+        )
+    }
+}
+
+/// Björn Ottosson's OKLab, a perceptually uniform space for averaging and clustering.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct Oklab {
+    pub l: f32,
+    pub a: f32,
+    pub b: f32,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Default)]
 pub enum ColorFamily {
     #[default]
@@ -68,6 +284,38 @@ impl ColorFamily {
         }
     }
 
+    pub fn plural_name(&self) -> &'static str {
+        match self {
+            Self::Red => "Reds",
+            Self::Orange => "Oranges",
+            Self::Yellow => "Yellows",
+            Self::Green => "Greens",
+            Self::Cyan => "Cyans",
+            Self::Blue => "Blues",
+            Self::Purple => "Purples",
+            Self::Pink => "Pinks",
+            Self::Brown => "Browns",
+            Self::Neutral => "Neutrals",
+        }
+    }
+
+    /// A typical member of the family, used to tint UI affordances such as filter chips.
+    pub fn representative_rgb(&self) -> Rgb {
+        let (r, g, b) = match self {
+            Self::Red => (0xd3, 0x2f, 0x2f),
+            Self::Orange => (0xef, 0x6c, 0x00),
+            Self::Yellow => (0xfb, 0xc0, 0x2d),
+            Self::Green => (0x38, 0x8e, 0x3c),
+            Self::Cyan => (0x00, 0x97, 0xa7),
+            Self::Blue => (0x19, 0x76, 0xd2),
+            Self::Purple => (0x7b, 0x1f, 0xa2),
+            Self::Pink => (0xd8, 0x1b, 0x60),
+            Self::Brown => (0x6d, 0x4c, 0x41),
+            Self::Neutral => (0x9e, 0x9e, 0x9e),
+        };
+        Rgb { r, g, b }
+    }
+
     pub fn all() -> &'static [ColorFamily] {
         &[
             Self::Red,
@@ -89,13 +337,31 @@ pub enum ColorLibrary {
     #[default]
     FashionHomeTcx,
     SolidCoated,
+    /// Colours the user added by value, e.g. brand colours or palette entries.
+    Custom,
 }
 
 impl ColorLibrary {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::FashionHomeTcx => "tcx",
+            Self::SolidCoated => "solid-coated",
+            Self::Custom => "custom",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|library| library.id() == id)
+    }
+
     pub fn display_name(&self) -> &'static str {
         match self {
             Self::FashionHomeTcx => "Fashion, Home + Interiors (TCX)",
             Self::SolidCoated => "Solid Coated",
+            Self::Custom => "Custom",
         }
     }
 
@@ -103,10 +369,17 @@ impl ColorLibrary {
         match self {
             Self::FashionHomeTcx => "TCX",
             Self::SolidCoated => "Solid Coated",
+            Self::Custom => "Custom",
         }
     }
 
+    /// Every library, the bundled Pantone ones first.
     pub fn all() -> &'static [ColorLibrary] {
+        &[Self::FashionHomeTcx, Self::SolidCoated, Self::Custom]
+    }
+
+    /// The Pantone libraries bundled with the app.
+    pub fn pantone() -> &'static [ColorLibrary] {
         &[Self::FashionHomeTcx, Self::SolidCoated]
     }
 }
@@ -134,3 +407,40 @@ impl SortOrder {
         &[Self::Name, Self::Hue, Self::Saturation, Self::Lightness]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_query() {
+        let color = PantoneColor::test_in(ColorLibrary::FashionHomeTcx, "cloud-dancer", "#f0eee9");
+
+        assert!(color.matches_query(""));
+        assert!(color.matches_query("Cloud"));
+        assert!(color.matches_query("#F0EE"));
+        assert!(color.matches_query("eee9"));
+        assert!(!color.matches_query("red"));
+        assert!(!color.matches_query("#"));
+        assert_eq!(color.code(), None);
+        assert_eq!(color.full_name(), "PANTONE Cloud Dancer TCX");
+
+        let coated = PantoneColor {
+            name: "PANTONE 100-C".to_string(),
+            library: ColorLibrary::SolidCoated,
+            ..color
+        };
+        assert_eq!(coated.code(), Some("100-C"));
+        assert_eq!(coated.full_name(), "PANTONE 100 C");
+
+        let brand = PantoneColor {
+            name: "Harbour Blue".to_string(),
+            library: ColorLibrary::Custom,
+            custom_code: Some("HB-01".to_string()),
+            ..coated
+        };
+        assert_eq!(brand.code(), Some("HB-01"));
+        assert!(brand.matches_query("hb-0"));
+        assert_eq!(brand.full_name(), "Harbour Blue HB-01");
+    }
+}
@@ -0,0 +1,225 @@
+use super::conversion::{
+    cmyk_to_rgb, hex_to_rgb, hsl_to_rgb, hsv_to_rgb, hwb_to_rgb, lab_to_rgb, lch_to_rgb,
+    oklch_to_rgb, rgb_to_cmyk, rgb_to_hex, rgb_to_hsl, rgb_to_hsv, rgb_to_hwb, rgb_to_lab,
+    rgb_to_lch, rgb_to_oklch,
+};
+use super::model::{Cmyk, Hsl, Hsv, Hwb, Lab, Lch, Oklch, Rgb};
+
+/// A way of writing a colour's value, as listed in the detail panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Notation {
+    Hex,
+    Rgb,
+    Hsl,
+    Hsv,
+    Hwb,
+    Lab,
+    Lch,
+    Oklch,
+    Cmyk,
+}
+
+impl Notation {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Hex => "hex",
+            Self::Rgb => "rgb",
+            Self::Hsl => "hsl",
+            Self::Hsv => "hsv",
+            Self::Hwb => "hwb",
+            Self::Lab => "lab",
+            Self::Lch => "lch",
+            Self::Oklch => "oklch",
+            Self::Cmyk => "cmyk",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|n| n.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Hex => "HEX",
+            Self::Rgb => "RGB",
+            Self::Hsl => "HSL",
+            Self::Hsv => "HSV",
+            Self::Hwb => "HWB",
+            Self::Lab => "Lab",
+            Self::Lch => "LCh",
+            Self::Oklch => "OKLCH",
+            Self::Cmyk => "CMYK",
+        }
+    }
+
+    pub fn all() -> &'static [Notation] {
+        &[
+            Self::Hex,
+            Self::Rgb,
+            Self::Hsl,
+            Self::Hsv,
+            Self::Hwb,
+            Self::Lab,
+            Self::Lch,
+            Self::Oklch,
+            Self::Cmyk,
+        ]
+    }
+
+    pub fn format(&self, rgb: &Rgb) -> String {
+        match self {
+            Self::Hex => rgb_to_hex(rgb),
+            Self::Rgb => rgb.to_string(),
+            Self::Hsl => rgb_to_hsl(rgb).to_string(),
+            Self::Hsv => rgb_to_hsv(rgb).to_string(),
+            Self::Hwb => rgb_to_hwb(rgb).to_string(),
+            Self::Lab => rgb_to_lab(rgb).to_string(),
+            Self::Lch => rgb_to_lch(rgb).to_string(),
+            Self::Oklch => rgb_to_oklch(rgb).to_string(),
+            Self::Cmyk => rgb_to_cmyk(rgb).to_string(),
+        }
+    }
+
+    /// Read a colour written in any notation, e.g. `#bb2649`, `bb2649`, `#b24`,
+    /// `rgb(187 38 73)` or `oklch(50.4% 0.177 4.4)`. Commas, slashes and units
+    /// are optional; alpha is ignored.
+    pub fn parse(text: &str) -> Option<Rgb> {
+        let text = text.trim().to_lowercase();
+        let Some((function, args)) = text.split_once('(') else {
+            return parse_hex(&text);
+        };
+        let args = args.strip_suffix(')')?;
+        let values: Vec<(f32, bool)> = args
+            .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+            .filter(|value| !value.is_empty())
+            .map(|value| {
+                let percent = value.ends_with('%');
+                let number = value.trim_end_matches(['%']).trim_end_matches("deg");
+                number.parse::<f32>().ok().map(|n| (n, percent))
+            })
+            .collect::<Option<_>>()?;
+        if values.len() < 3 {
+            return None;
+        }
+        let (v0, v1, v2) = (values[0].0, values[1].0, values[2].0);
+
+        let rgb = match function.trim() {
+            "rgb" | "rgba" => {
+                let channel = |(n, percent): (f32, bool)| {
+                    let n = if percent { n * 2.55 } else { n };
+                    n.clamp(0.0, 255.0).round() as u8
+                };
+                Rgb {
+                    r: channel(values[0]),
+                    g: channel(values[1]),
+                    b: channel(values[2]),
+                }
+            }
+            "hsl" | "hsla" => hsl_to_rgb(&Hsl {
+                h: v0,
+                s: v1,
+                l: v2,
+            }),
+            "hsv" | "hsb" => hsv_to_rgb(&Hsv {
+                h: v0,
+                s: v1,
+                v: v2,
+            }),
+            "hwb" => hwb_to_rgb(&Hwb {
+                h: v0,
+                w: v1,
+                b: v2,
+            }),
+            "lab" => lab_to_rgb(&Lab {
+                l: v0,
+                a: v1,
+                b: v2,
+            }),
+            "lch" => lch_to_rgb(&Lch {
+                l: v0,
+                c: v1,
+                h: v2,
+            }),
+            "oklch" => {
+                // Lightness is a percentage or a fraction
+                let l = if values[0].1 { v0 / 100.0 } else { v0 };
+                oklch_to_rgb(&Oklch { l, c: v1, h: v2 })
+            }
+            "cmyk" if values.len() == 4 => cmyk_to_rgb(&Cmyk {
+                c: v0,
+                m: v1,
+                y: v2,
+                k: values[3].0,
+            }),
+            _ => return None,
+        };
+        Some(rgb)
+    }
+}
+
+/// Six hex digits, or three as shorthand, with or without `#`.
+fn parse_hex(text: &str) -> Option<Rgb> {
+    let hex = text.trim_start_matches('#');
+    if hex.len() == 3 && hex.is_ascii() {
+        let doubled: String = hex.chars().flat_map(|c| [c, c]).collect();
+        return hex_to_rgb(&doubled);
+    }
+    hex_to_rgb(hex)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let rgb = Rgb {
+            r: 255,
+            g: 128,
+            b: 0,
+        };
+        let formatted: Vec<String> = Notation::all().iter().map(|n| n.format(&rgb)).collect();
+        assert_eq!(formatted[0], "#ff8000");
+        assert_eq!(formatted[1], "rgb(255, 128, 0)");
+        assert_eq!(formatted[4], "hwb(30 0% 0%)");
+        assert_eq!(formatted[8], "cmyk(0%, 50%, 100%, 0%)");
+
+        for notation in Notation::all() {
+            assert_eq!(Notation::from_id(notation.id()), Some(*notation));
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let rgb = Rgb {
+            r: 187,
+            g: 38,
+            b: 73,
+        };
+        // Everything the detail panel shows reads back, give or take rounding
+        for notation in Notation::all() {
+            let parsed = Notation::parse(&notation.format(&rgb)).unwrap();
+            for (a, b) in [(parsed.r, rgb.r), (parsed.g, rgb.g), (parsed.b, rgb.b)] {
+                assert!(a.abs_diff(b) <= 2, "{}", notation.id());
+            }
+        }
+        assert_eq!(Notation::parse(" BB2649 "), Some(rgb));
+        assert_eq!(Notation::parse("rgb(187 38 73 / 50%)"), Some(rgb));
+        assert_eq!(
+            Notation::parse("#fff"),
+            Some(Rgb {
+                r: 255,
+                g: 255,
+                b: 255
+            })
+        );
+        assert_eq!(
+            Notation::parse("hsl(120deg, 100%, 50%)"),
+            Some(Rgb { r: 0, g: 255, b: 0 })
+        );
+        assert_eq!(Notation::parse("rgb(1, 2)"), None);
+        assert_eq!(Notation::parse("cmyk(0, 0, 0)"), None);
+        assert_eq!(Notation::parse("xyz(1 2 3)"), None);
+        assert_eq!(Notation::parse("#12345"), None);
+    }
+}
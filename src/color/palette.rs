@@ -0,0 +1,181 @@
+use super::model::PantoneColor;
+use super::versioned::Versioned;
+use serde::{Deserialize, Serialize};
+
+/// A named, ordered collection of colours the user put together.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Palette {
+    pub name: String,
+    pub entries: Vec<PaletteEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaletteEntry {
+    pub color: PantoneColor,
+    #[serde(default)]
+    pub notes: String,
+}
+
+impl Palette {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            entries: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, color: PantoneColor, notes: &str) {
+        self.entries.push(PaletteEntry {
+            color,
+            notes: notes.to_string(),
+        });
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<PaletteEntry> {
+        (index < self.entries.len()).then(|| self.entries.remove(index))
+    }
+
+    /// Move the entry at `from` so it ends up at `to`, shifting the ones between.
+    pub fn move_entry(&mut self, from: usize, to: usize) {
+        if from < self.entries.len() && to < self.entries.len() {
+            let entry = self.entries.remove(from);
+            self.entries.insert(to, entry);
+        }
+    }
+
+    pub fn set_notes(&mut self, index: usize, notes: &str) {
+        if let Some(entry) = self.entries.get_mut(index) {
+            entry.notes = notes.to_string();
+        }
+    }
+
+    pub fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Every palette the user has made, as stored on disk.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaletteCollection {
+    palettes: Vec<Palette>,
+}
+
+impl Versioned for PaletteCollection {
+    const KIND: &'static str = "palettes";
+    const VERSION: u32 = 1;
+}
+
+impl PaletteCollection {
+    pub fn palettes(&self) -> &[Palette] {
+        &self.palettes
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Palette> {
+        self.palettes.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Palette> {
+        self.palettes.get_mut(index)
+    }
+
+    /// Add an empty palette named `name`, or `name 2`, `name 3`… if taken.
+    /// Returns its index.
+    pub fn create(&mut self, name: &str) -> usize {
+        let name = self.unique_name(name);
+        self.palettes.push(Palette::new(&name));
+        self.palettes.len() - 1
+    }
+
+    /// Add a palette built elsewhere, e.g. extracted from an image.
+    pub fn push(&mut self, palette: Palette) -> usize {
+        self.palettes.push(palette);
+        self.palettes.len() - 1
+    }
+
+    pub fn rename(&mut self, index: usize, name: &str) {
+        if let Some(palette) = self.palettes.get_mut(index) {
+            palette.name = name.to_string();
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Palette> {
+        (index < self.palettes.len()).then(|| self.palettes.remove(index))
+    }
+
+    fn unique_name(&self, name: &str) -> String {
+        let taken = |candidate: &str| self.palettes.iter().any(|p| p.name == candidate);
+        if !taken(name) {
+            return name.to_string();
+        }
+        (2..)
+            .map(|n| format!("{} {}", name, n))
+            .find(|candidate| !taken(candidate))
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color::conversion::hex_to_rgb;
+    use crate::color::model::ColorLibrary;
+
+    #[test]
+    fn test_json_round_trip() {
+        let color = PantoneColor::test_in(ColorLibrary::FashionHomeTcx, "Viva Magenta", "#bb2649");
+        let rgb = color.rgb;
+        let mut palette = Palette::new("Sunset");
+        palette.push(color, "32% of photo.jpg");
+
+        let parsed = Palette::from_json(&palette.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.name, "Sunset");
+        assert_eq!(parsed.entries.len(), 1);
+        assert_eq!(parsed.entries[0].color.name, "Viva Magenta");
+        assert_eq!(parsed.entries[0].color.rgb, rgb);
+        assert_eq!(parsed.entries[0].notes, "32% of photo.jpg");
+    }
+
+    #[test]
+    fn test_edit_entries() {
+        let rgb = |hex| hex_to_rgb(hex).unwrap();
+        let mut palette = Palette::new("Brand");
+        palette.push(PantoneColor::custom("Ink", rgb("#1b1b1f")), "");
+        palette.push(PantoneColor::custom("Paper", rgb("#f4f1ea")), "");
+        palette.push(PantoneColor::custom("Accent", rgb("#bb2649")), "");
+
+        palette.move_entry(2, 0);
+        palette.set_notes(0, "buttons only");
+        let names: Vec<&str> = palette
+            .entries
+            .iter()
+            .map(|e| e.color.name.as_str())
+            .collect();
+        assert_eq!(names, ["Accent", "Ink", "Paper"]);
+        assert_eq!(palette.entries[0].notes, "buttons only");
+
+        palette.move_entry(0, 2);
+        assert_eq!(palette.remove(2).unwrap().color.name, "Accent");
+        assert!(palette.remove(5).is_none());
+        assert_eq!(palette.entries.len(), 2);
+        assert_eq!(palette.entries[1].color.library, ColorLibrary::Custom);
+        assert_eq!(palette.entries[1].color.hex, "#f4f1ea");
+    }
+
+    #[test]
+    fn test_collection() {
+        let mut collection = PaletteCollection::default();
+        assert_eq!(collection.create("Untitled"), 0);
+        assert_eq!(collection.create("Untitled"), 1);
+        assert_eq!(collection.create("Untitled"), 2);
+        collection.rename(0, "Spring 27 capsule");
+        assert_eq!(collection.remove(1).unwrap().name, "Untitled 2");
+
+        let parsed = PaletteCollection::from_json(&collection.to_json().unwrap()).unwrap();
+        let names: Vec<&str> = parsed.palettes().iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["Spring 27 capsule", "Untitled 3"]);
+    }
+}
@@ -0,0 +1,163 @@
+use super::conversion::hex_to_rgb;
+use super::model::PantoneColor;
+use super::palette::Palette;
+use super::swatch_file::SwatchFileError;
+
+const HEADER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<ooo:color-table xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0" xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0" xmlns:xlink="http://www.w3.org/1999/xlink" xmlns:svg="http://www.w3.org/2000/svg" xmlns:ooo="http://openoffice.org/2004/office">
+"#;
+const FOOTER: &str = "</ooo:color-table>\n";
+
+/// Read a LibreOffice colour table into a palette called `name`, as the
+/// format has no name of its own.
+pub fn read(text: &str, name: &str) -> Result<Palette, SwatchFileError> {
+    if !text.contains("color-table") {
+        return Err(SwatchFileError::WrongFormat);
+    }
+
+    let mut palette = Palette::new(name);
+    let mut rest = text;
+    while let Some(start) = rest.find("<draw:color") {
+        let element = &rest[start + "<draw:color".len()..];
+        let end = element.find('>').ok_or(SwatchFileError::Truncated)?;
+        let attributes = &element[..end];
+        rest = &element[end..];
+
+        let Some(value) = attribute(attributes, "draw:color") else {
+            continue;
+        };
+        let rgb = hex_to_rgb(&value)
+            .ok_or_else(|| SwatchFileError::Invalid(format!("“{}” is not a colour", value)))?;
+        let name = attribute(attributes, "draw:name").unwrap_or_default();
+        let mut color = PantoneColor::custom(&name, rgb);
+        if color.name.is_empty() {
+            color.name = color.hex.clone();
+        }
+        palette.push(color, "");
+    }
+    Ok(palette)
+}
+
+/// The unescaped value of the attribute `name` among an element's `attributes`.
+fn attribute(attributes: &str, name: &str) -> Option<String> {
+    let mut rest = attributes;
+    loop {
+        let index = rest.find(name)?;
+        let before = rest[..index].chars().next_back();
+        let after = rest[index + name.len()..].trim_start();
+        rest = &rest[index + name.len()..];
+        if !before.is_some_and(char::is_whitespace) {
+            continue;
+        }
+        let Some(after) = after.strip_prefix('=') else {
+            continue;
+        };
+        let after = after.trim_start();
+        let quote = after.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = &after[1..];
+        return Some(unescape(&value[..value.find(quote)?]));
+    }
+}
+
+fn unescape(text: &str) -> String {
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        result.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let Some(end) = rest.find(';') else {
+            break;
+        };
+        let entity = &rest[1..end];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => entity
+                .strip_prefix("#x")
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => {
+                result.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                result.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Write `palettes` as one colour table, since the format has no groups.
+pub fn write(palettes: &[Palette]) -> String {
+    let mut text = HEADER.to_string();
+    for entry in palettes.iter().flat_map(|palette| &palette.entries) {
+        text.push_str(&format!(
+            "  <draw:color draw:name=\"{}\" draw:color=\"{}\"/>\n",
+            escape(&entry.color.full_name()),
+            entry.color.hex
+        ));
+    }
+    text.push_str(FOOTER);
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read() {
+        let text = r##"<?xml version="1.0" encoding="UTF-8"?>
+<ooo:color-table xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0">
+<draw:color draw:name="Black" draw:color="#000000"/>
+<draw:color
+    draw:color='#1D4E89' draw:name='Harbour &amp; Sea &#x2013; Blue'/>
+<draw:color draw:color="#ffffff"/>
+</ooo:color-table>"##;
+        let palette = read(text, "standard").unwrap();
+        let names: Vec<&str> = palette
+            .entries
+            .iter()
+            .map(|entry| entry.color.name.as_str())
+            .collect();
+        assert_eq!(names, ["Black", "Harbour & Sea – Blue", "#ffffff"]);
+        assert_eq!(palette.entries[1].color.hex, "#1d4e89");
+
+        assert_eq!(
+            read("GIMP Palette", "x").unwrap_err(),
+            SwatchFileError::WrongFormat
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut palette = Palette::new("Brand");
+        palette.push(
+            PantoneColor::custom("Salt & \"Pepper\" <1>", hex_to_rgb("#a0a0a0").unwrap()),
+            "",
+        );
+        let text = write(&[palette]);
+        assert!(text.contains(r#"draw:name="Salt &amp; &quot;Pepper&quot; &lt;1&gt;""#));
+
+        let parsed = read(&text, "brand").unwrap();
+        assert_eq!(parsed.entries[0].color.name, "Salt & \"Pepper\" <1>");
+        assert_eq!(parsed.entries[0].color.hex, "#a0a0a0");
+    }
+}
@@ -0,0 +1,177 @@
+use super::aco;
+use super::ase::AseDocument;
+use super::gpl;
+use super::palette::Palette;
+use super::soc;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SwatchFileError {
+    /// The file ended part-way through an entry.
+    Truncated,
+    /// The file is not in the format its name suggests.
+    WrongFormat,
+    Invalid(String),
+}
+
+impl fmt::Display for SwatchFileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "The file is incomplete"),
+            Self::WrongFormat => write!(f, "The file is not a swatch file"),
+            Self::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// A swatch file format other apps read and write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwatchFormat {
+    /// Adobe Swatch Exchange, from Illustrator, InDesign and Photoshop.
+    Ase,
+    /// GIMP and Inkscape palettes.
+    Gpl,
+    /// Photoshop colour swatches.
+    Aco,
+    /// LibreOffice colour tables.
+    Soc,
+}
+
+impl SwatchFormat {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Ase => "Adobe Swatch Exchange",
+            Self::Gpl => "GIMP Palette",
+            Self::Aco => "Photoshop Swatches",
+            Self::Soc => "LibreOffice Colour Table",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Ase => "ase",
+            Self::Gpl => "gpl",
+            Self::Aco => "aco",
+            Self::Soc => "soc",
+        }
+    }
+
+    /// The format a file is in, going by its extension.
+    pub fn from_filename(name: &str) -> Option<Self> {
+        let (_, extension) = name.rsplit_once('.')?;
+        Self::all()
+            .iter()
+            .copied()
+            .find(|format| format.extension().eq_ignore_ascii_case(extension))
+    }
+
+    pub fn all() -> &'static [SwatchFormat] {
+        &[Self::Ase, Self::Gpl, Self::Aco, Self::Soc]
+    }
+
+    /// Read a file's swatches as palettes; those outside any group go in one
+    /// called `name`.
+    pub fn read(&self, bytes: &[u8], name: &str) -> Result<Vec<Palette>, SwatchFileError> {
+        match self {
+            Self::Ase => Ok(AseDocument::read(bytes)?.to_palettes(name)),
+            Self::Gpl => Ok(vec![gpl::read(text(bytes)?, name)?]),
+            Self::Aco => Ok(vec![aco::read(bytes, name)?]),
+            Self::Soc => Ok(vec![soc::read(text(bytes)?, name)?]),
+        }
+    }
+
+    /// Write `palettes` as one file, each palette a group where the format has them.
+    pub fn write(&self, palettes: &[Palette]) -> Vec<u8> {
+        match self {
+            Self::Ase => AseDocument::from_palettes(palettes).to_bytes(),
+            Self::Gpl => gpl::write(palettes).into_bytes(),
+            Self::Aco => aco::write(palettes),
+            Self::Soc => soc::write(palettes).into_bytes(),
+        }
+    }
+}
+
+fn text(bytes: &[u8]) -> Result<&str, SwatchFileError> {
+    std::str::from_utf8(bytes).map_err(|_| SwatchFileError::WrongFormat)
+}
+
+/// Big-endian reads over a byte slice, failing rather than running off the end.
+pub(super) struct ByteReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    pub fn is_at_end(&self) -> bool {
+        self.position >= self.bytes.len()
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], SwatchFileError> {
+        let end = self
+            .position
+            .checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or(SwatchFileError::Truncated)?;
+        let slice = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(slice)
+    }
+
+    pub fn u16(&mut self) -> Result<u16, SwatchFileError> {
+        let bytes = self.take(2)?;
+        Ok(u16::from_be_bytes([bytes[0], bytes[1]]))
+    }
+
+    pub fn u32(&mut self) -> Result<u32, SwatchFileError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    pub fn f32(&mut self) -> Result<f32, SwatchFileError> {
+        Ok(f32::from_bits(self.u32()?))
+    }
+
+    /// `len` UTF-16BE code units of text, dropping a terminating nul.
+    pub fn utf16(&mut self, len: usize) -> Result<String, SwatchFileError> {
+        let units = (0..len)
+            .map(|_| self.u16())
+            .collect::<Result<Vec<u16>, _>>()?;
+        let units = units.strip_suffix(&[0]).unwrap_or(&units);
+        String::from_utf16(units)
+            .map_err(|_| SwatchFileError::Invalid("A swatch name is not valid text".to_string()))
+    }
+}
+
+/// `text` as nul-terminated UTF-16BE, with its length in code units.
+pub(super) fn utf16_be(text: &str) -> (usize, Vec<u8>) {
+    let units: Vec<u16> = text.encode_utf16().chain([0]).collect();
+    let bytes = units.iter().flat_map(|unit| unit.to_be_bytes()).collect();
+    (units.len(), bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_filename() {
+        assert_eq!(
+            SwatchFormat::from_filename("Brand.ASE"),
+            Some(SwatchFormat::Ase)
+        );
+        assert_eq!(
+            SwatchFormat::from_filename("Tango.gpl"),
+            Some(SwatchFormat::Gpl)
+        );
+        assert_eq!(
+            SwatchFormat::from_filename("office.soc"),
+            Some(SwatchFormat::Soc)
+        );
+        assert_eq!(SwatchFormat::from_filename("brand"), None);
+        assert_eq!(SwatchFormat::from_filename("brand.txt"), None);
+    }
+}
@@ -0,0 +1,70 @@
+use serde::de::{DeserializeOwned, Error as _};
+use serde::{Deserialize, Serialize};
+
+/// A file of lon's own data, saved as its fields next to a layout version.
+pub trait Versioned: Serialize + DeserializeOwned {
+    /// What the file holds, for errors, e.g. `favourites`.
+    const KIND: &'static str;
+    /// Bumped whenever the file layout changes incompatibly.
+    const VERSION: u32;
+
+    fn to_json(&self) -> serde_json::Result<String> {
+        serde_json::to_string_pretty(&VersionedFile {
+            version: Self::VERSION,
+            data: self,
+        })
+    }
+
+    /// Parse a file, refusing ones written by a newer version.
+    fn from_json(json: &str) -> serde_json::Result<Self> {
+        let file: VersionedFile<Self> = serde_json::from_str(json)?;
+        if file.version > Self::VERSION {
+            return Err(serde_json::Error::custom(format!(
+                "unsupported {} version {}",
+                Self::KIND,
+                file.version
+            )));
+        }
+        Ok(file.data)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VersionedFile<T> {
+    version: u32,
+    #[serde(flatten)]
+    data: T,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+    struct Notes {
+        lines: Vec<String>,
+    }
+
+    impl Versioned for Notes {
+        const KIND: &'static str = "notes";
+        const VERSION: u32 = 2;
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let notes = Notes {
+            lines: vec!["Primary".to_string()],
+        };
+        let json = notes.to_json().unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], 2);
+        assert_eq!(value["lines"][0], "Primary");
+        assert_eq!(Notes::from_json(&json).unwrap(), notes);
+
+        // Older layouts are read; newer ones and ones without a version are not
+        assert!(Notes::from_json(r#"{"version": 1, "lines": []}"#).is_ok());
+        let newer = Notes::from_json(r#"{"version": 3, "lines": []}"#).unwrap_err();
+        assert!(newer.to_string().contains("unsupported notes version 3"));
+        assert!(Notes::from_json(r#"{"lines": []}"#).is_err());
+    }
+}
@@ -0,0 +1,88 @@
+use crate::color::Rgb;
+use gtk::prelude::*;
+use gtk::{gdk, glib};
+
+/// Pixels with less alpha than this are treated as background and skipped.
+const MIN_ALPHA: u8 = 128;
+
+/// The decoded RGBA pixels of a texture, for sampling colours out of an image.
+pub struct ImagePixels {
+    width: usize,
+    height: usize,
+    stride: usize,
+    data: glib::Bytes,
+}
+
+impl ImagePixels {
+    pub fn from_texture(texture: &gdk::Texture) -> Self {
+        let mut downloader = gdk::TextureDownloader::new(texture);
+        downloader.set_format(gdk::MemoryFormat::R8g8b8a8);
+        let (data, stride) = downloader.download_bytes();
+        Self {
+            width: texture.width() as usize,
+            height: texture.height() as usize,
+            stride,
+            data,
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The opaque colour at `(x, y)`, or `None` if it is outside the image or transparent.
+    pub fn get(&self, x: usize, y: usize) -> Option<Rgb> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let offset = y * self.stride + x * 4;
+        let p = &self.data[offset..offset + 4];
+        (p[3] >= MIN_ALPHA).then_some(Rgb {
+            r: p[0],
+            g: p[1],
+            b: p[2],
+        })
+    }
+
+    /// The mean of the opaque pixels in the square of `radius` around `(x, y)`,
+    /// clipped to the image. A radius of 0 reads the single pixel.
+    pub fn average(&self, x: usize, y: usize, radius: usize) -> Option<Rgb> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let xs = x.saturating_sub(radius)..=(x + radius).min(self.width - 1);
+        let ys = y.saturating_sub(radius)..=(y + radius).min(self.height - 1);
+        let (mut r, mut g, mut b, mut n) = (0u32, 0u32, 0u32, 0u32);
+        for py in ys {
+            for px in xs.clone() {
+                if let Some(rgb) = self.get(px, py) {
+                    r += rgb.r as u32;
+                    g += rgb.g as u32;
+                    b += rgb.b as u32;
+                    n += 1;
+                }
+            }
+        }
+        let mean = |sum: u32| ((sum as f32 / n as f32).round()) as u8;
+        (n > 0).then(|| Rgb {
+            r: mean(r),
+            g: mean(g),
+            b: mean(b),
+        })
+    }
+
+    /// Every opaque pixel, subsampled on a regular grid to at most about `max` pixels.
+    pub fn sample(&self, max: usize) -> Vec<Rgb> {
+        let total = self.width * self.height;
+        let step = ((total as f64 / max.max(1) as f64).sqrt().ceil() as usize).max(1);
+        (0..self.height)
+            .step_by(step)
+            .flat_map(|y| (0..self.width).step_by(step).map(move |x| (x, y)))
+            .filter_map(|(x, y)| self.get(x, y))
+            .collect()
+    }
+}
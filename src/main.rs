@@ -1,5 +1,9 @@
 mod application;
 mod color;
+mod image;
+mod portal;
+mod settings;
+mod storage;
 mod widgets;
 mod window;
 
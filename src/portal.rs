@@ -0,0 +1,269 @@
+use crate::color::Rgb;
+use gtk::gio;
+use gtk::glib;
+use gtk::prelude::*;
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+const PORTAL_BUS_NAME: &str = "org.freedesktop.portal.Desktop";
+const PORTAL_OBJECT_PATH: &str = "/org/freedesktop/portal/desktop";
+const SCREENSHOT_INTERFACE: &str = "org.freedesktop.portal.Screenshot";
+const REQUEST_INTERFACE: &str = "org.freedesktop.portal.Request";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PortalError {
+    /// The user dismissed the picker.
+    Cancelled,
+    Failed(String),
+}
+
+impl fmt::Display for PortalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cancelled => write!(f, "Cancelled"),
+            Self::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+/// Ask the desktop portal to let the user pick a colour anywhere on screen.
+///
+/// `parent_window` is the portal window identifier (e.g. `wayland:<handle>`), or
+/// an empty string. `callback` runs once, on the thread-default main context.
+pub fn pick_color<F>(connection: &gio::DBusConnection, parent_window: &str, callback: F)
+where
+    F: FnOnce(Result<Rgb, PortalError>) + 'static,
+{
+    let callback = Rc::new(RefCell::new(Some(callback)));
+    let finish = {
+        let callback = callback.clone();
+        move |result: Result<Rgb, PortalError>| {
+            if let Some(callback) = callback.borrow_mut().take() {
+                callback(result);
+            }
+        }
+    };
+
+    // Subscribe to the request's Response before calling, so it cannot be missed
+    let token = format!("lon{}", glib::random_int());
+    let sender = connection
+        .unique_name()
+        .map(|name| name.trim_start_matches(':').replace('.', "_"))
+        .unwrap_or_default();
+    let request_path = format!("{}/request/{}/{}", PORTAL_OBJECT_PATH, sender, token);
+
+    let subscription = Rc::new(RefCell::new(None));
+    let id = connection.signal_subscribe(
+        Some(PORTAL_BUS_NAME),
+        Some(REQUEST_INTERFACE),
+        Some("Response"),
+        Some(&request_path),
+        None,
+        gio::DBusSignalFlags::NONE,
+        glib::clone!(
+            #[strong]
+            subscription,
+            #[strong]
+            finish,
+            move |connection, _, _, _, _, params| {
+                if let Some(id) = subscription.borrow_mut().take() {
+                    connection.signal_unsubscribe(id);
+                }
+                finish(parse_response(params));
+            }
+        ),
+    );
+    subscription.replace(Some(id));
+
+    let options = glib::VariantDict::new(None);
+    options.insert("handle_token", &token);
+    let params = glib::Variant::tuple_from_iter([parent_window.to_variant(), options.end()]);
+
+    connection.call(
+        Some(PORTAL_BUS_NAME),
+        PORTAL_OBJECT_PATH,
+        SCREENSHOT_INTERFACE,
+        "PickColor",
+        Some(&params),
+        Some(glib::VariantTy::new("(o)").unwrap()),
+        gio::DBusCallFlags::NONE,
+        -1,
+        None::<&gio::Cancellable>,
+        glib::clone!(
+            #[strong]
+            connection,
+            move |result| {
+                if let Err(err) = result {
+                    if let Some(id) = subscription.borrow_mut().take() {
+                        connection.signal_unsubscribe(id);
+                    }
+                    finish(Err(PortalError::Failed(err.to_string())));
+                }
+            }
+        ),
+    );
+}
+
+/// Parse the `(ua{sv})` Response of a PickColor request.
+fn parse_response(params: &glib::Variant) -> Result<Rgb, PortalError> {
+    let response = params.try_child_value(0).and_then(|v| v.get::<u32>());
+    match response {
+        Some(0) => {}
+        Some(1) => return Err(PortalError::Cancelled),
+        _ => return Err(PortalError::Failed("The portal request failed".into())),
+    }
+
+    let results = params
+        .try_child_value(1)
+        .ok_or_else(|| PortalError::Failed("Missing results".into()))?;
+    let (r, g, b) = glib::VariantDict::new(Some(&results))
+        .lookup_value("color", Some(glib::VariantTy::new("(ddd)").unwrap()))
+        .and_then(|color| color.get::<(f64, f64, f64)>())
+        .ok_or_else(|| PortalError::Failed("No colour in the portal response".into()))?;
+
+    let channel = |c: f64| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+    Ok(Rgb {
+        r: channel(r),
+        g: channel(g),
+        b: channel(b),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCREENSHOT_XML: &str = r#"
+        <node>
+          <interface name="org.freedesktop.portal.Screenshot">
+            <method name="PickColor">
+              <arg type="s" name="parent_window" direction="in"/>
+              <arg type="a{sv}" name="options" direction="in"/>
+              <arg type="o" name="handle" direction="out"/>
+            </method>
+          </interface>
+        </node>"#;
+
+    fn connect(address: &str) -> gio::DBusConnection {
+        gio::DBusConnection::for_address_sync(
+            address,
+            gio::DBusConnectionFlags::AUTHENTICATION_CLIENT
+                | gio::DBusConnectionFlags::MESSAGE_BUS_CONNECTION,
+            None,
+            None::<&gio::Cancellable>,
+        )
+        .expect("Failed to connect to the test bus")
+    }
+
+    /// Serve a fake Screenshot portal on `connection` that answers every
+    /// PickColor request with `response` and, on success, `color`.
+    fn serve_mock_portal(connection: &gio::DBusConnection, response: u32, color: (f64, f64, f64)) {
+        connection
+            .call_sync(
+                Some("org.freedesktop.DBus"),
+                "/org/freedesktop/DBus",
+                "org.freedesktop.DBus",
+                "RequestName",
+                Some(&(PORTAL_BUS_NAME, 4u32).to_variant()),
+                None,
+                gio::DBusCallFlags::NONE,
+                -1,
+                None::<&gio::Cancellable>,
+            )
+            .expect("Failed to own the portal name");
+
+        let node = gio::DBusNodeInfo::for_xml(SCREENSHOT_XML).unwrap();
+        let interface = node.lookup_interface(SCREENSHOT_INTERFACE).unwrap();
+        connection
+            .register_object(PORTAL_OBJECT_PATH, &interface)
+            .method_call(move |connection, sender, _, _, _, params, invocation| {
+                let token = glib::VariantDict::new(Some(&params.child_value(1)))
+                    .lookup::<String>("handle_token")
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default();
+                let sender = sender.unwrap_or_default().to_string();
+                let handle = format!(
+                    "{}/request/{}/{}",
+                    PORTAL_OBJECT_PATH,
+                    sender.trim_start_matches(':').replace('.', "_"),
+                    token
+                );
+                let path = glib::variant::ObjectPath::try_from(handle.as_str()).unwrap();
+                invocation.return_value(Some(&glib::Variant::tuple_from_iter([path.to_variant()])));
+
+                let results = glib::VariantDict::new(None);
+                if response == 0 {
+                    results.insert("color", color);
+                }
+                let signal = glib::Variant::tuple_from_iter([response.to_variant(), results.end()]);
+                connection
+                    .emit_signal(
+                        Some(&sender),
+                        &handle,
+                        REQUEST_INTERFACE,
+                        "Response",
+                        Some(&signal),
+                    )
+                    .unwrap();
+            })
+            .build()
+            .expect("Failed to register the mock portal");
+    }
+
+    fn pick_with_mock_portal(response: u32, color: (f64, f64, f64)) -> Result<Rgb, PortalError> {
+        let context = glib::MainContext::new();
+        context
+            .with_thread_default(|| {
+                let bus = gio::TestDBus::new(gio::TestDBusFlags::NONE);
+                bus.up();
+                let address = bus.bus_address().unwrap();
+
+                let portal = connect(&address);
+                serve_mock_portal(&portal, response, color);
+
+                let client = connect(&address);
+                let result = Rc::new(RefCell::new(None));
+                pick_color(
+                    &client,
+                    "",
+                    glib::clone!(
+                        #[strong]
+                        result,
+                        move |r| {
+                            result.replace(Some(r));
+                        }
+                    ),
+                );
+                while result.borrow().is_none() {
+                    context.iteration(true);
+                }
+
+                client.close_sync(None::<&gio::Cancellable>).ok();
+                portal.close_sync(None::<&gio::Cancellable>).ok();
+                bus.down();
+                result.take().unwrap()
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_pick_color_from_mock_portal() {
+        let rgb = pick_with_mock_portal(0, (1.0, 0.5, 0.0)).unwrap();
+        assert_eq!(
+            rgb,
+            Rgb {
+                r: 255,
+                g: 128,
+                b: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_pick_color_cancelled() {
+        let result = pick_with_mock_portal(1, (0.0, 0.0, 0.0));
+        assert_eq!(result, Err(PortalError::Cancelled));
+    }
+}
@@ -0,0 +1,25 @@
+use gtk::gio;
+
+pub const APP_ID: &str = "dev.myyc.lon";
+
+/// The app's settings, from the installed schema or, when running
+/// uninstalled, the copy compiled at build time.
+pub fn settings() -> gio::Settings {
+    let installed = gio::SettingsSchemaSource::default()
+        .and_then(|source| source.lookup(APP_ID, true))
+        .is_some();
+    if installed {
+        return gio::Settings::new(APP_ID);
+    }
+
+    let source = gio::SettingsSchemaSource::from_directory(
+        env!("OUT_DIR"),
+        gio::SettingsSchemaSource::default().as_ref(),
+        false,
+    )
+    .expect("Failed to load the GSettings schema compiled at build time");
+    let schema = source
+        .lookup(APP_ID, false)
+        .expect("The GSettings schema is missing");
+    gio::Settings::new_full(&schema, None::<&gio::SettingsBackend>, None)
+}
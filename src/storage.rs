@@ -0,0 +1,70 @@
+use gtk::glib;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/// Where user data such as favourites lives: `$XDG_DATA_HOME/lon`.
+pub fn data_dir() -> PathBuf {
+    glib::user_data_dir().join("lon")
+}
+
+/// The contents of `name` in the data directory, or `None` if it does not exist yet.
+pub fn read(name: &str) -> io::Result<Option<String>> {
+    match fs::read_to_string(data_dir().join(name)) {
+        Ok(contents) => Ok(Some(contents)),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// Replace `name` in the data directory with `contents`, atomically, so a crash
+/// mid-write never leaves a truncated file behind.
+pub fn write(name: &str, contents: &str) -> io::Result<()> {
+    let dir = data_dir();
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(name);
+    let temp = dir.join(format!(".{}.tmp", name));
+    fs::write(&temp, contents)?;
+    fs::rename(&temp, &path)
+}
+
+/// Read and `parse` `name`, starting from the default when it does not exist.
+///
+/// A file that cannot be parsed (e.g. one written by a newer version) is
+/// renamed to `name.bak` first, so saving over it later cannot lose data.
+pub fn load<T: Default, E>(name: &str, parse: impl FnOnce(&str) -> Result<T, E>) -> T {
+    let Ok(Some(contents)) = read(name) else {
+        return T::default();
+    };
+    parse(&contents).unwrap_or_else(|_| {
+        let dir = data_dir();
+        let _ = fs::rename(dir.join(name), dir.join(format!("{}.bak", name)));
+        T::default()
+    })
+}
+
+/// Folders GIMP and Inkscape load palettes from, labelled with the app (and
+/// GIMP version) they belong to. Only folders that exist are listed.
+pub fn installed_palette_dirs() -> Vec<(String, PathBuf)> {
+    let config = glib::user_config_dir();
+    let mut dirs = Vec::new();
+    if let Ok(entries) = fs::read_dir(config.join("GIMP")) {
+        let mut versions: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        versions.sort();
+        for version in versions.into_iter().rev() {
+            let label = format!(
+                "GIMP {}",
+                version.file_name().unwrap_or_default().to_string_lossy()
+            );
+            dirs.push((label, version.join("palettes")));
+        }
+    }
+    dirs.push((
+        "Inkscape".to_string(),
+        config.join("inkscape").join("palettes"),
+    ));
+    dirs.retain(|(_, dir)| dir.is_dir());
+    dirs
+}
@@ -0,0 +1,219 @@
+use crate::color::{CodeFormat, Palette};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{gio, glib};
+use std::cell::{Cell, OnceCell, RefCell};
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct CodeExportDialog {
+        pub palette: RefCell<Palette>,
+        pub format: Cell<CodeFormat>,
+        pub buffer: OnceCell<gtk::TextBuffer>,
+        pub copy_button: OnceCell<gtk::Button>,
+        pub toast_overlay: OnceCell<adw::ToastOverlay>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CodeExportDialog {
+        const NAME: &'static str = "LonCodeExportDialog";
+        type Type = super::CodeExportDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for CodeExportDialog {}
+    impl WidgetImpl for CodeExportDialog {}
+    impl AdwDialogImpl for CodeExportDialog {}
+}
+
+glib::wrapper! {
+    pub struct CodeExportDialog(ObjectSubclass<imp::CodeExportDialog>)
+        @extends adw::Dialog, gtk::Widget;
+}
+
+impl CodeExportDialog {
+    /// Preview `palette` as code, to copy or save to a file.
+    pub fn new(palette: Palette) -> Self {
+        let dialog: Self = glib::Object::builder()
+            .property("title", format!("Export “{}” as Code", palette.name))
+            .property("content-width", 560)
+            .property("content-height", 520)
+            .build();
+        dialog.imp().palette.replace(palette);
+        dialog.setup();
+        dialog
+    }
+
+    fn setup(&self) {
+        let imp = self.imp();
+
+        let format_dropdown = gtk::DropDown::from_strings(
+            &CodeFormat::all()
+                .iter()
+                .map(|format| format.display_name())
+                .collect::<Vec<_>>(),
+        );
+        format_dropdown.set_tooltip_text(Some("Format"));
+        format_dropdown.connect_selected_notify(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |dropdown| {
+                if let Some(format) = CodeFormat::all().get(dropdown.selected() as usize) {
+                    dialog.imp().format.set(*format);
+                    dialog.render();
+                }
+            }
+        ));
+
+        let copy_button = gtk::Button::with_label("Copy");
+        copy_button.add_css_class("suggested-action");
+        copy_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| dialog.copy()
+        ));
+        let save_button = gtk::Button::with_label("Save…");
+        save_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| dialog.save()
+        ));
+
+        let header = adw::HeaderBar::new();
+        header.pack_start(&format_dropdown);
+        header.pack_end(&copy_button);
+        header.pack_end(&save_button);
+
+        let text_view = gtk::TextView::builder()
+            .editable(false)
+            .monospace(true)
+            .wrap_mode(gtk::WrapMode::None)
+            .top_margin(12)
+            .bottom_margin(12)
+            .left_margin(12)
+            .right_margin(12)
+            .build();
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&text_view));
+
+        let toast_overlay = adw::ToastOverlay::new();
+        toast_overlay.set_child(Some(&scrolled));
+
+        let toolbar = adw::ToolbarView::new();
+        toolbar.add_top_bar(&header);
+        toolbar.set_content(Some(&toast_overlay));
+        self.set_child(Some(&toolbar));
+
+        let _ = imp.buffer.set(text_view.buffer());
+        let _ = imp.copy_button.set(copy_button);
+        let _ = imp.toast_overlay.set(toast_overlay);
+        self.render();
+    }
+
+    fn code(&self) -> String {
+        let imp = self.imp();
+        imp.format.get().render(&imp.palette.borrow())
+    }
+
+    fn render(&self) {
+        let imp = self.imp();
+        if let Some(buffer) = imp.buffer.get() {
+            buffer.set_text(&self.code());
+        }
+        // A folder of files only makes sense saved
+        if let Some(button) = imp.copy_button.get() {
+            button.set_sensitive(!imp.format.get().is_folder());
+        }
+    }
+
+    fn show_toast(&self, title: &str) {
+        if let Some(overlay) = self.imp().toast_overlay.get() {
+            let toast = adw::Toast::new(title);
+            toast.set_timeout(2);
+            overlay.add_toast(toast);
+        }
+    }
+
+    fn copy(&self) {
+        self.clipboard().set_text(&self.code());
+        self.show_toast(&format!(
+            "Copied {}",
+            self.imp().format.get().display_name()
+        ));
+    }
+
+    fn save(&self) {
+        let imp = self.imp();
+        let format = imp.format.get();
+        let palette = imp.palette.borrow().clone();
+        let file_dialog = gtk::FileDialog::builder()
+            .title(if format.is_folder() {
+                "Choose Where to Save"
+            } else {
+                "Save Code"
+            })
+            .initial_name(format.file_name(&palette.name))
+            .build();
+
+        let root = self.root().and_downcast::<gtk::Window>();
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            async move {
+                let chosen = if format.is_folder() {
+                    file_dialog.select_folder_future(root.as_ref()).await
+                } else {
+                    file_dialog.save_future(root.as_ref()).await
+                };
+                let Ok(chosen) = chosen else {
+                    return;
+                };
+                let result = if format.is_folder() {
+                    write_files(&chosen, format.files(&palette)).await
+                } else {
+                    write_file(&chosen, format.render(&palette)).await
+                };
+                match result {
+                    Ok(()) => {
+                        dialog.close();
+                    }
+                    Err(err) => {
+                        let alert = adw::AlertDialog::new(Some("Could Not Save Code"), Some(&err));
+                        alert.add_response("close", "Close");
+                        alert.present(Some(&dialog));
+                    }
+                }
+            }
+        ));
+    }
+}
+
+async fn write_file(file: &gio::File, contents: String) -> Result<(), String> {
+    file.replace_contents_future(
+        contents.into_bytes(),
+        None,
+        false,
+        gio::FileCreateFlags::REPLACE_DESTINATION,
+    )
+    .await
+    .map(|_| ())
+    .map_err(|(_, err)| err.to_string())
+}
+
+/// Write each of `files`, by path relative to `folder`, creating folders as needed.
+async fn write_files(folder: &gio::File, files: Vec<(String, String)>) -> Result<(), String> {
+    for (path, contents) in files {
+        let file = folder.resolve_relative_path(&path);
+        if let Some(parent) = file.parent() {
+            match parent.make_directory_with_parents(gio::Cancellable::NONE) {
+                Err(err) if !err.matches(gio::IOErrorEnum::Exists) => return Err(err.to_string()),
+                _ => {}
+            }
+        }
+        write_file(&file, contents).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use crate::color::Rgb;
+use adw::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{gdk, glib, graphene, gsk};
+use std::cell::Cell;
+
+// Simple color preview widget
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct ColorPreview {
+        pub rgb: Cell<Option<Rgb>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ColorPreview {
+        const NAME: &'static str = "LonColorPreview";
+        type Type = super::ColorPreview;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for ColorPreview {}
+
+    impl WidgetImpl for ColorPreview {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let widget = self.obj();
+            let width = widget.width() as f32;
+            let height = widget.height() as f32;
+
+            if let Some(rgb) = self.rgb.get() {
+                let gdk_color = gdk::RGBA::new(
+                    rgb.r as f32 / 255.0,
+                    rgb.g as f32 / 255.0,
+                    rgb.b as f32 / 255.0,
+                    1.0,
+                );
+
+                let rect = graphene::Rect::new(0.0, 0.0, width, height);
+                let rounded = gsk::RoundedRect::from_rect(rect, 12.0_f32.min(height / 2.0));
+
+                snapshot.push_rounded_clip(&rounded);
+                snapshot.append_color(&gdk_color, &rect);
+                snapshot.pop();
+            }
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ColorPreview(ObjectSubclass<imp::ColorPreview>)
+        @extends gtk::Widget;
+}
+
+impl ColorPreview {
+    pub fn new(rgb: Rgb) -> Self {
+        let obj: Self = glib::Object::new();
+        obj.imp().rgb.set(Some(rgb));
+        obj
+    }
+
+    pub fn set_rgb(&self, rgb: Rgb) {
+        self.imp().rgb.set(Some(rgb));
+        self.queue_draw();
+    }
+}
@@ -1,16 +1,86 @@
-use crate::color::PantoneColor;
+use crate::color::{prefers_dark_text, PantoneColor};
 use adw::subclass::prelude::*;
 use gtk::glib;
 use gtk::prelude::*;
-use gtk::{gdk, graphene, gsk};
-use std::cell::RefCell;
+use gtk::{gdk, graphene, gsk, pango};
+use std::cell::{Cell, RefCell};
+
+pub const DEFAULT_SWATCH_SIZE: i32 = 72;
+pub const MIN_SWATCH_SIZE: i32 = 48;
+pub const MAX_SWATCH_SIZE: i32 = 160;
+
+/// Padding between the swatch edge and its label.
+const LABEL_MARGIN: f32 = 6.0;
+/// Size of the favourite star in the top corner.
+const STAR_SIZE: i32 = 16;
+
+/// Text drawn on a swatch, so the grid can be read without hovering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SwatchLabel {
+    #[default]
+    None,
+    /// The Pantone code, or the name when the library has no codes.
+    Code,
+    Name,
+    NameHex,
+}
+
+impl SwatchLabel {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::None => "none",
+            Self::Code => "code",
+            Self::Name => "name",
+            Self::NameHex => "name-hex",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all().iter().copied().find(|label| label.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Code => "Code",
+            Self::Name => "Name",
+            Self::NameHex => "Name and Hex",
+        }
+    }
+
+    pub fn all() -> &'static [SwatchLabel] {
+        &[Self::None, Self::Code, Self::Name, Self::NameHex]
+    }
+
+    fn text(&self, color: &PantoneColor) -> Option<String> {
+        match self {
+            Self::None => None,
+            Self::Code => Some(color.code().unwrap_or(&color.name).to_string()),
+            Self::Name => Some(color.name.clone()),
+            Self::NameHex => Some(format!("{}\n{}", color.name, color.hex)),
+        }
+    }
+}
 
 mod imp {
     use super::*;
 
-    #[derive(Default)]
     pub struct ColorSwatch {
         pub color: RefCell<Option<PantoneColor>>,
+        pub size: Cell<i32>,
+        pub label: Cell<SwatchLabel>,
+        pub starred: Cell<bool>,
+    }
+
+    impl Default for ColorSwatch {
+        fn default() -> Self {
+            Self {
+                color: RefCell::default(),
+                size: Cell::new(DEFAULT_SWATCH_SIZE),
+                label: Cell::default(),
+                starred: Cell::new(false),
+            }
+        }
     }
 
     #[glib::object_subclass]
@@ -24,9 +94,70 @@ mod imp {
         fn constructed(&self) {
             self.parent_constructed();
             let obj = self.obj();
-            obj.set_size_request(72, 72);
+            obj.set_size_request(DEFAULT_SWATCH_SIZE, DEFAULT_SWATCH_SIZE);
             obj.set_overflow(gtk::Overflow::Hidden);
             obj.set_cursor_from_name(Some("pointer"));
+            obj.set_focusable(true);
+            obj.setup_drag_source();
+        }
+    }
+
+    impl ColorSwatch {
+        /// Draw the label along the bottom edge in whichever of black or white
+        /// contrasts more, ellipsizing each line to the swatch width.
+        fn snapshot_label(
+            &self,
+            snapshot: &gtk::Snapshot,
+            color: &PantoneColor,
+            width: f32,
+            height: f32,
+        ) {
+            let Some(text) = self.label.get().text(color) else {
+                return;
+            };
+            let available = width - 2.0 * LABEL_MARGIN;
+            if available <= 0.0 {
+                return;
+            }
+
+            let layout = self.obj().create_pango_layout(Some(&text));
+            let attrs = pango::AttrList::new();
+            attrs.insert(pango::AttrFloat::new_scale(pango::SCALE_SMALL));
+            layout.set_attributes(Some(&attrs));
+            layout.set_width((available * pango::SCALE as f32) as i32);
+            layout.set_ellipsize(pango::EllipsizeMode::End);
+
+            let (_, logical) = layout.pixel_extents();
+            let top = height - LABEL_MARGIN - logical.height() as f32;
+            if top < LABEL_MARGIN {
+                return;
+            }
+
+            snapshot.save();
+            snapshot.translate(&graphene::Point::new(LABEL_MARGIN, top));
+            snapshot.append_layout(&layout, &ink_for(color));
+            snapshot.restore();
+        }
+
+        /// Draw the favourite star in the top-right corner, in the label colour.
+        fn snapshot_star(&self, snapshot: &gtk::Snapshot, color: &PantoneColor, width: f32) {
+            let widget = self.obj();
+            let icon = gtk::IconTheme::for_display(&widget.display()).lookup_icon(
+                "starred-symbolic",
+                &[],
+                STAR_SIZE,
+                widget.scale_factor(),
+                widget.direction(),
+                gtk::IconLookupFlags::empty(),
+            );
+            let size = STAR_SIZE as f32;
+            snapshot.save();
+            snapshot.translate(&graphene::Point::new(
+                width - size - LABEL_MARGIN,
+                LABEL_MARGIN,
+            ));
+            icon.snapshot_symbolic(snapshot, size as f64, size as f64, &[ink_for(color)]);
+            snapshot.restore();
         }
     }
 
@@ -54,15 +185,37 @@ mod imp {
                 snapshot.push_rounded_clip(&rounded);
                 snapshot.append_color(&gdk_color, &rect);
                 snapshot.pop();
+
+                self.snapshot_label(snapshot, color, width, height);
+                if self.starred.get() {
+                    self.snapshot_star(snapshot, color, width);
+                }
+
+                // Keyboard focus ring, in whichever of black/white stands out
+                if widget.has_visible_focus() {
+                    let ring = if prefers_dark_text(&color.rgb) {
+                        gdk::RGBA::new(0.0, 0.0, 0.0, 0.8)
+                    } else {
+                        gdk::RGBA::new(1.0, 1.0, 1.0, 0.9)
+                    };
+                    let inset = graphene::Rect::new(3.0, 3.0, width - 6.0, height - 6.0);
+                    snapshot.append_border(
+                        &gsk::RoundedRect::from_rect(inset, 9.0),
+                        &[2.0; 4],
+                        &[ring; 4],
+                    );
+                }
             }
         }
 
-        fn measure(&self, orientation: gtk::Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
-            match orientation {
-                gtk::Orientation::Horizontal => (72, 72, -1, -1),
-                gtk::Orientation::Vertical => (72, 72, -1, -1),
-                _ => (72, 72, -1, -1),
-            }
+        fn state_flags_changed(&self, old_flags: &gtk::StateFlags) {
+            self.parent_state_flags_changed(old_flags);
+            self.obj().queue_draw();
+        }
+
+        fn measure(&self, _orientation: gtk::Orientation, _for_size: i32) -> (i32, i32, i32, i32) {
+            let size = self.size.get();
+            (size, size, -1, -1)
         }
     }
 }
@@ -83,8 +236,92 @@ impl ColorSwatch {
         glib::Object::new()
     }
 
+    pub fn color(&self) -> Option<PantoneColor> {
+        self.imp().color.borrow().clone()
+    }
+
+    pub fn size(&self) -> i32 {
+        self.imp().size.get()
+    }
+
+    /// Minimum width and height, in pixels.
+    pub fn set_size(&self, size: i32) {
+        let size = size.clamp(MIN_SWATCH_SIZE, MAX_SWATCH_SIZE);
+        if self.imp().size.replace(size) != size {
+            self.set_size_request(size, size);
+            self.queue_resize();
+        }
+    }
+
+    pub fn set_label(&self, label: SwatchLabel) {
+        if self.imp().label.replace(label) != label {
+            self.queue_draw();
+        }
+    }
+
+    pub fn set_starred(&self, starred: bool) {
+        if self.imp().starred.replace(starred) != starred {
+            self.queue_draw();
+        }
+    }
+
     pub fn set_color(&self, color: &PantoneColor) {
         self.imp().color.replace(Some(color.clone()));
         self.queue_draw();
     }
+
+    /// Offer the colour as `gdk::RGBA` (application/x-color), hex text and
+    /// a JSON `PantoneColor`, so it can be dropped into other apps.
+    fn setup_drag_source(&self) {
+        let source = gtk::DragSource::new();
+        source.set_actions(gdk::DragAction::COPY);
+        source.connect_prepare(glib::clone!(
+            #[weak(rename_to = swatch)]
+            self,
+            #[upgrade_or]
+            None,
+            move |_, _, _| swatch.color().map(|color| content_provider(&color))
+        ));
+        source.connect_drag_begin(glib::clone!(
+            #[weak(rename_to = swatch)]
+            self,
+            move |source, _| {
+                let icon = gtk::WidgetPaintable::new(Some(&swatch));
+                source.set_icon(Some(&icon), swatch.width() / 2, swatch.height() / 2);
+            }
+        ));
+        self.add_controller(source);
+    }
+}
+
+/// Black or white, whichever reads better on `color`.
+fn ink_for(color: &PantoneColor) -> gdk::RGBA {
+    if prefers_dark_text(&color.rgb) {
+        gdk::RGBA::new(0.0, 0.0, 0.0, 0.87)
+    } else {
+        gdk::RGBA::new(1.0, 1.0, 1.0, 1.0)
+    }
+}
+
+/// MIME type of the JSON payload offered when dragging a swatch.
+const COLOR_JSON_MIME_TYPE: &str = "application/json";
+
+fn content_provider(color: &PantoneColor) -> gdk::ContentProvider {
+    let rgba = gdk::RGBA::new(
+        color.rgb.r as f32 / 255.0,
+        color.rgb.g as f32 / 255.0,
+        color.rgb.b as f32 / 255.0,
+        1.0,
+    );
+    let mut providers = vec![
+        gdk::ContentProvider::for_value(&rgba.to_value()),
+        gdk::ContentProvider::for_value(&color.hex.to_value()),
+    ];
+    if let Ok(json) = serde_json::to_string(color) {
+        providers.push(gdk::ContentProvider::for_bytes(
+            COLOR_JSON_MIME_TYPE,
+            &glib::Bytes::from_owned(json.into_bytes()),
+        ));
+    }
+    gdk::ContentProvider::new_union(&providers)
 }
@@ -0,0 +1,390 @@
+use crate::color::{hex_to_rgb, rgb_to_hex, rgb_to_lab, ColorDatabase, DeltaE, LabDifference, Rgb};
+use crate::widgets::{ColorPreview, ComparePatch, PatchLayout};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib;
+use std::cell::{OnceCell, RefCell};
+use std::rc::Rc;
+
+/// Most colours that can be compared at once.
+pub const MAX_COMPARED: usize = 4;
+const DEFAULT_TOLERANCE: f64 = 2.0;
+
+#[derive(Debug, Clone)]
+pub struct ComparedColor {
+    pub name: String,
+    pub rgb: Rgb,
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct CompareDialog {
+        pub db: OnceCell<Rc<ColorDatabase>>,
+        pub colors: RefCell<Vec<ComparedColor>>,
+        pub patches: OnceCell<gtk::Box>,
+        pub split: OnceCell<ComparePatch>,
+        pub nested: OnceCell<ComparePatch>,
+        pub table: OnceCell<gtk::Grid>,
+        pub empty: OnceCell<gtk::Label>,
+        pub formula_row: OnceCell<adw::ComboRow>,
+        pub tolerance_row: OnceCell<adw::SpinRow>,
+        pub entry_row: OnceCell<adw::EntryRow>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CompareDialog {
+        const NAME: &'static str = "LonCompareDialog";
+        type Type = super::CompareDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for CompareDialog {}
+    impl WidgetImpl for CompareDialog {}
+    impl AdwDialogImpl for CompareDialog {}
+}
+
+glib::wrapper! {
+    pub struct CompareDialog(ObjectSubclass<imp::CompareDialog>)
+        @extends adw::Dialog, gtk::Widget;
+}
+
+impl CompareDialog {
+    /// An empty comparison; typed names are looked up in `db`. `formula`
+    /// is the initial choice for pass/fail.
+    pub fn new(db: Rc<ColorDatabase>, formula: DeltaE) -> Self {
+        let dialog: Self = glib::Object::builder()
+            .property("title", "Compare")
+            .property("content-width", 720)
+            .property("content-height", 760)
+            .build();
+        let _ = dialog.imp().db.set(db);
+        dialog.setup(formula);
+        dialog.refresh();
+        dialog
+    }
+
+    /// Add a colour to the comparison. The first one pinned is the reference.
+    /// Returns `false` when the comparison is already full.
+    pub fn pin(&self, name: &str, rgb: Rgb) -> bool {
+        let mut colors = self.imp().colors.borrow_mut();
+        if colors.len() >= MAX_COMPARED {
+            return false;
+        }
+        colors.push(ComparedColor {
+            name: name.to_string(),
+            rgb,
+        });
+        drop(colors);
+        self.refresh();
+        true
+    }
+
+    pub fn n_colors(&self) -> usize {
+        self.imp().colors.borrow().len()
+    }
+
+    fn unpin(&self, index: usize) {
+        {
+            let mut colors = self.imp().colors.borrow_mut();
+            if index < colors.len() {
+                colors.remove(index);
+            }
+        }
+        self.refresh();
+    }
+
+    fn setup(&self, formula: DeltaE) {
+        let imp = self.imp();
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 18);
+        content.set_margin_start(16);
+        content.set_margin_end(16);
+        content.set_margin_top(8);
+        content.set_margin_bottom(16);
+
+        let patches = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+        patches.set_homogeneous(true);
+        content.append(&patches);
+
+        let empty = gtk::Label::new(Some(
+            "Pin colours with Compare in their details, or type one below",
+        ));
+        empty.add_css_class("dim-label");
+        empty.set_wrap(true);
+        content.append(&empty);
+
+        let split = ComparePatch::new(PatchLayout::Split);
+        let nested = ComparePatch::new(PatchLayout::Nested);
+        let joined = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+        joined.set_homogeneous(true);
+        for patch in [&split, &nested] {
+            patch.set_height_request(180);
+            patch.set_hexpand(true);
+            joined.append(patch);
+        }
+        content.append(&joined);
+
+        let table = gtk::Grid::new();
+        table.set_column_spacing(24);
+        table.set_row_spacing(6);
+        table.set_halign(gtk::Align::Center);
+        content.append(&table);
+
+        // Pass/fail settings
+        let formulas = gtk::StringList::new(
+            &DeltaE::all()
+                .iter()
+                .map(|f| f.display_name())
+                .collect::<Vec<_>>(),
+        );
+        let formula_row = adw::ComboRow::builder()
+            .title("Tolerance Formula")
+            .model(&formulas)
+            .build();
+        if let Some(index) = DeltaE::all().iter().position(|f| *f == formula) {
+            formula_row.set_selected(index as u32);
+        }
+        formula_row.connect_selected_notify(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| dialog.refresh()
+        ));
+
+        let tolerance_row = adw::SpinRow::with_range(0.1, 20.0, 0.1);
+        tolerance_row.set_title("Tolerance");
+        tolerance_row.set_digits(1);
+        tolerance_row.set_value(DEFAULT_TOLERANCE);
+        tolerance_row.connect_value_notify(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| dialog.refresh()
+        ));
+
+        let entry_row = adw::EntryRow::builder()
+            .title("Add Colour (Hex or Name)")
+            .show_apply_button(true)
+            .build();
+        entry_row.connect_apply(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |row| dialog.add_typed(row)
+        ));
+        entry_row.connect_entry_activated(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |row| dialog.add_typed(row)
+        ));
+
+        let settings = gtk::ListBox::new();
+        settings.add_css_class("boxed-list");
+        settings.set_selection_mode(gtk::SelectionMode::None);
+        settings.append(&entry_row);
+        settings.append(&formula_row);
+        settings.append(&tolerance_row);
+        content.append(&settings);
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+        scrolled.set_propagate_natural_height(true);
+        scrolled.set_child(Some(&content));
+
+        let toolbar = adw::ToolbarView::new();
+        toolbar.add_top_bar(&adw::HeaderBar::new());
+        toolbar.set_content(Some(&scrolled));
+        self.set_child(Some(&toolbar));
+
+        let _ = imp.patches.set(patches);
+        let _ = imp.empty.set(empty);
+        let _ = imp.split.set(split);
+        let _ = imp.nested.set(nested);
+        let _ = imp.table.set(table);
+        let _ = imp.formula_row.set(formula_row);
+        let _ = imp.tolerance_row.set(tolerance_row);
+        let _ = imp.entry_row.set(entry_row);
+    }
+
+    fn add_typed(&self, row: &adw::EntryRow) {
+        let text = row.text();
+        let text = text.trim();
+        let found = match hex_to_rgb(text) {
+            Some(rgb) => Some((rgb_to_hex(&rgb), rgb)),
+            None => self
+                .imp()
+                .db
+                .get()
+                .and_then(|db| db.find_by_name(text))
+                .map(|color| (color.name, color.rgb)),
+        };
+        match found {
+            Some((name, rgb)) if self.pin(&name, rgb) => {
+                row.set_text("");
+                row.remove_css_class("error");
+            }
+            _ => row.add_css_class("error"),
+        }
+    }
+
+    fn formula(&self) -> DeltaE {
+        self.imp()
+            .formula_row
+            .get()
+            .and_then(|row| DeltaE::all().get(row.selected() as usize).copied())
+            .unwrap_or_default()
+    }
+
+    fn tolerance(&self) -> f32 {
+        self.imp()
+            .tolerance_row
+            .get()
+            .map_or(DEFAULT_TOLERANCE, |row| row.value()) as f32
+    }
+
+    fn refresh(&self) {
+        let imp = self.imp();
+        let colors = imp.colors.borrow().clone();
+        let (Some(patches), Some(empty), Some(split), Some(nested), Some(table)) = (
+            imp.patches.get(),
+            imp.empty.get(),
+            imp.split.get(),
+            imp.nested.get(),
+            imp.table.get(),
+        ) else {
+            return;
+        };
+
+        while let Some(child) = patches.first_child() {
+            patches.remove(&child);
+        }
+        for (index, color) in colors.iter().enumerate() {
+            patches.append(&self.patch_card(index, color));
+        }
+        if let Some(entry_row) = imp.entry_row.get() {
+            entry_row.set_sensitive(colors.len() < MAX_COMPARED);
+        }
+
+        let comparable = colors.len() >= 2;
+        empty.set_visible(!comparable);
+        split.set_visible(comparable);
+        nested.set_visible(comparable);
+        table.set_visible(comparable);
+        let rgbs: Vec<Rgb> = colors.iter().map(|c| c.rgb).collect();
+        split.set_colors(rgbs.clone());
+        nested.set_colors(rgbs);
+
+        while let Some(child) = table.first_child() {
+            table.remove(&child);
+        }
+        if comparable {
+            self.fill_table(table, &colors);
+        }
+    }
+
+    fn patch_card(&self, index: usize, color: &ComparedColor) -> gtk::Box {
+        let card = gtk::Box::new(gtk::Orientation::Vertical, 4);
+
+        let preview = ColorPreview::new(color.rgb);
+        preview.set_height_request(140);
+        preview.set_hexpand(true);
+        card.append(&preview);
+
+        let header = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+        let labels = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        labels.set_hexpand(true);
+        let name = gtk::Label::new(Some(&color.name));
+        name.add_css_class("heading");
+        name.set_xalign(0.0);
+        name.set_ellipsize(gtk::pango::EllipsizeMode::End);
+        labels.append(&name);
+        let detail = if index == 0 {
+            format!("Reference · {}", rgb_to_hex(&color.rgb))
+        } else {
+            rgb_to_hex(&color.rgb)
+        };
+        let hex = gtk::Label::new(Some(&detail));
+        hex.add_css_class("dim-label");
+        hex.add_css_class("caption");
+        hex.set_xalign(0.0);
+        labels.append(&hex);
+        header.append(&labels);
+
+        let remove = gtk::Button::from_icon_name("window-close-symbolic");
+        remove.add_css_class("flat");
+        remove.add_css_class("circular");
+        remove.set_valign(gtk::Align::Center);
+        remove.set_tooltip_text(Some("Remove"));
+        remove.connect_clicked(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| dialog.unpin(index)
+        ));
+        header.append(&remove);
+        card.append(&header);
+        card
+    }
+
+    /// One column per sample, measured against the reference.
+    fn fill_table(&self, table: &gtk::Grid, colors: &[ComparedColor]) {
+        let reference = rgb_to_lab(&colors[0].rgb);
+        let formula = self.formula();
+        let tolerance = self.tolerance();
+
+        let cell = |text: &str, classes: &[&str]| {
+            let label = gtk::Label::new(Some(text));
+            label.set_xalign(1.0);
+            for class in classes {
+                label.add_css_class(class);
+            }
+            label
+        };
+
+        let mut row_titles: Vec<String> = DeltaE::all()
+            .iter()
+            .map(|f| f.display_name().to_string())
+            .collect();
+        row_titles.extend(["ΔL*", "ΔC*", "ΔH*"].map(String::from));
+        row_titles.push(format!("{} ≤ {:.1}", formula.display_name(), tolerance));
+        for (row, title) in row_titles.iter().enumerate() {
+            let label = cell(title, &["dim-label"]);
+            label.set_xalign(0.0);
+            table.attach(&label, 0, row as i32 + 1, 1, 1);
+        }
+
+        for (column, color) in colors.iter().enumerate().skip(1) {
+            let column = column as i32;
+            let heading = cell(&color.name, &["heading"]);
+            heading.set_ellipsize(gtk::pango::EllipsizeMode::End);
+            heading.set_max_width_chars(16);
+            table.attach(&heading, column, 0, 1, 1);
+
+            let sample = rgb_to_lab(&color.rgb);
+            let mut row = 1;
+            for f in DeltaE::all() {
+                let value = cell(
+                    &format!("{:.2}", f.compute(&reference, &sample)),
+                    &["numeric"],
+                );
+                table.attach(&value, column, row, 1, 1);
+                row += 1;
+            }
+            let d = LabDifference::between(&reference, &sample);
+            for value in [d.dl, d.dc, d.dh] {
+                table.attach(
+                    &cell(&format!("{:+.2}", value), &["numeric"]),
+                    column,
+                    row,
+                    1,
+                    1,
+                );
+                row += 1;
+            }
+            let pass = formula.compute(&reference, &sample) <= tolerance;
+            let verdict = if pass {
+                cell("Pass", &["success", "heading"])
+            } else {
+                cell("Fail", &["error", "heading"])
+            };
+            table.attach(&verdict, column, row, 1, 1);
+        }
+    }
+}
@@ -0,0 +1,104 @@
+use crate::color::Rgb;
+use adw::subclass::prelude::*;
+use gtk::prelude::*;
+use gtk::{gdk, glib, graphene, gsk};
+use std::cell::{Cell, RefCell};
+
+/// How a `ComparePatch` lays out its colours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PatchLayout {
+    /// Side by side strips with no gap between them.
+    #[default]
+    Split,
+    /// Concentric squares, the first colour outermost.
+    Nested,
+}
+
+// Several colours in one patch, so their edges touch
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct ComparePatch {
+        pub colors: RefCell<Vec<Rgb>>,
+        pub layout: Cell<PatchLayout>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ComparePatch {
+        const NAME: &'static str = "LonComparePatch";
+        type Type = super::ComparePatch;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for ComparePatch {}
+
+    impl WidgetImpl for ComparePatch {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let widget = self.obj();
+            let width = widget.width() as f32;
+            let height = widget.height() as f32;
+            let colors = self.colors.borrow();
+            if width <= 0.0 || height <= 0.0 || colors.is_empty() {
+                return;
+            }
+
+            let rgba = |rgb: &Rgb| {
+                gdk::RGBA::new(
+                    rgb.r as f32 / 255.0,
+                    rgb.g as f32 / 255.0,
+                    rgb.b as f32 / 255.0,
+                    1.0,
+                )
+            };
+            let bounds = graphene::Rect::new(0.0, 0.0, width, height);
+            snapshot.push_rounded_clip(&gsk::RoundedRect::from_rect(bounds, 12.0));
+
+            let n = colors.len() as f32;
+            match self.layout.get() {
+                PatchLayout::Split => {
+                    let strip = width / n;
+                    for (i, rgb) in colors.iter().enumerate() {
+                        // Round edges to whole pixels so no seam shows between strips
+                        let x = (i as f32 * strip).round();
+                        let next = ((i + 1) as f32 * strip).round();
+                        let rect = graphene::Rect::new(x, 0.0, next - x, height);
+                        snapshot.append_color(&rgba(rgb), &rect);
+                    }
+                }
+                PatchLayout::Nested => {
+                    let step = width.min(height) / (2.0 * n);
+                    for (i, rgb) in colors.iter().enumerate() {
+                        let inset = (i as f32 * step).round();
+                        let rect = graphene::Rect::new(
+                            inset,
+                            inset,
+                            width - 2.0 * inset,
+                            height - 2.0 * inset,
+                        );
+                        snapshot.append_color(&rgba(rgb), &rect);
+                    }
+                }
+            }
+            snapshot.pop();
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ComparePatch(ObjectSubclass<imp::ComparePatch>)
+        @extends gtk::Widget;
+}
+
+impl ComparePatch {
+    pub fn new(layout: PatchLayout) -> Self {
+        let obj: Self = glib::Object::new();
+        obj.imp().layout.set(layout);
+        obj
+    }
+
+    pub fn set_colors(&self, colors: Vec<Rgb>) {
+        self.imp().colors.replace(colors);
+        self.queue_draw();
+    }
+}
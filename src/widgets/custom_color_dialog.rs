@@ -0,0 +1,306 @@
+use crate::color::{
+    rgb_to_hex, ColorDatabase, ColorKey, ColorLibrary, CustomColor, DeltaE, Notation, Rgb,
+};
+use crate::portal::{self, PortalError};
+use crate::widgets::{match_row, ColorPreview};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib::subclass::Signal;
+use gtk::{gio, glib};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+/// Pantone colours listed under the preview.
+const MATCH_COUNT: usize = 3;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct CustomColorDialog {
+        pub db: OnceCell<Rc<ColorDatabase>>,
+        pub formula: Cell<DeltaE>,
+        /// The name of the colour being edited, or `None` when adding one.
+        pub original_name: RefCell<Option<String>>,
+        pub rgb: Cell<Option<Rgb>>,
+        pub preview: OnceCell<ColorPreview>,
+        pub value_row: OnceCell<adw::EntryRow>,
+        pub name_row: OnceCell<adw::EntryRow>,
+        pub code_row: OnceCell<adw::EntryRow>,
+        pub notes_row: OnceCell<adw::EntryRow>,
+        pub matches: OnceCell<adw::PreferencesGroup>,
+        pub match_rows: RefCell<Vec<adw::ActionRow>>,
+        pub save_button: OnceCell<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for CustomColorDialog {
+        const NAME: &'static str = "LonCustomColorDialog";
+        type Type = super::CustomColorDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for CustomColorDialog {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("saved").build()])
+        }
+    }
+
+    impl WidgetImpl for CustomColorDialog {}
+    impl AdwDialogImpl for CustomColorDialog {}
+}
+
+glib::wrapper! {
+    pub struct CustomColorDialog(ObjectSubclass<imp::CustomColorDialog>)
+        @extends adw::Dialog, gtk::Widget;
+}
+
+impl CustomColorDialog {
+    /// Add a colour to the Custom library, or edit `existing` if given.
+    pub fn new(db: Rc<ColorDatabase>, formula: DeltaE, existing: Option<&CustomColor>) -> Self {
+        let title = if existing.is_some() {
+            "Edit Custom Colour"
+        } else {
+            "New Custom Colour"
+        };
+        let dialog: Self = glib::Object::builder()
+            .property("title", title)
+            .property("content-width", 360)
+            .build();
+        let imp = dialog.imp();
+        let _ = imp.db.set(db);
+        imp.formula.set(formula);
+        imp.original_name
+            .replace(existing.map(|color| color.name.clone()));
+        dialog.setup(existing);
+        dialog
+    }
+
+    fn setup(&self, existing: Option<&CustomColor>) {
+        let imp = self.imp();
+
+        let preview = ColorPreview::new(Rgb { r: 0, g: 0, b: 0 });
+        preview.set_height_request(96);
+        preview.set_hexpand(true);
+
+        let value_row = adw::EntryRow::builder().title("Value").build();
+        value_row.set_tooltip_text(Some(
+            "Any notation, e.g. #bb2649, rgb(187 38 73) or oklch(50% 0.18 4)",
+        ));
+        let pick_button = gtk::Button::from_icon_name("color-select-symbolic");
+        pick_button.add_css_class("flat");
+        pick_button.set_valign(gtk::Align::Center);
+        pick_button.set_tooltip_text(Some("Pick Colour from Screen"));
+        pick_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| dialog.pick_color()
+        ));
+        value_row.add_suffix(&pick_button);
+
+        let name_row = adw::EntryRow::builder().title("Name").build();
+        let code_row = adw::EntryRow::builder().title("Code").build();
+        let notes_row = adw::EntryRow::builder().title("Notes").build();
+        for row in [&value_row, &name_row] {
+            row.connect_changed(glib::clone!(
+                #[weak(rename_to = dialog)]
+                self,
+                move |_| dialog.update()
+            ));
+        }
+
+        let fields = adw::PreferencesGroup::new();
+        fields.add(&value_row);
+        fields.add(&name_row);
+        fields.add(&code_row);
+        fields.add(&notes_row);
+
+        let matches = adw::PreferencesGroup::builder()
+            .title("Nearest Pantone")
+            .build();
+
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 18);
+        content.set_margin_start(16);
+        content.set_margin_end(16);
+        content.set_margin_top(8);
+        content.set_margin_bottom(16);
+        content.append(&preview);
+        content.append(&fields);
+        content.append(&matches);
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+        scrolled.set_propagate_natural_height(true);
+        scrolled.set_child(Some(&content));
+
+        let save_label = if existing.is_some() { "Save" } else { "Add" };
+        let save_button = gtk::Button::with_label(save_label);
+        save_button.add_css_class("suggested-action");
+        save_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| {
+                dialog.emit_by_name::<()>("saved", &[]);
+                dialog.close();
+            }
+        ));
+        let header = adw::HeaderBar::new();
+        header.pack_end(&save_button);
+
+        let toolbar = adw::ToolbarView::new();
+        toolbar.add_top_bar(&header);
+        toolbar.set_content(Some(&scrolled));
+        self.set_child(Some(&toolbar));
+        self.set_focus(Some(&value_row));
+
+        let _ = imp.preview.set(preview);
+        let _ = imp.value_row.set(value_row.clone());
+        let _ = imp.name_row.set(name_row.clone());
+        let _ = imp.code_row.set(code_row.clone());
+        let _ = imp.notes_row.set(notes_row.clone());
+        let _ = imp.matches.set(matches);
+        let _ = imp.save_button.set(save_button);
+
+        if let Some(color) = existing {
+            value_row.set_text(&color.hex);
+            name_row.set_text(&color.name);
+            code_row.set_text(&color.code);
+            notes_row.set_text(&color.notes);
+        }
+        self.update();
+    }
+
+    /// Read the value, refresh the preview and matches, and allow saving once
+    /// there is a colour and an unused name.
+    fn update(&self) {
+        let imp = self.imp();
+        let (Some(value_row), Some(name_row), Some(save_button)) = (
+            imp.value_row.get(),
+            imp.name_row.get(),
+            imp.save_button.get(),
+        ) else {
+            return;
+        };
+
+        let text = value_row.text();
+        let rgb = Notation::parse(&text);
+        imp.rgb.set(rgb);
+        if rgb.is_some() || text.trim().is_empty() {
+            value_row.remove_css_class("error");
+        } else {
+            value_row.add_css_class("error");
+        }
+        if let (Some(rgb), Some(preview)) = (rgb, imp.preview.get()) {
+            preview.set_rgb(rgb);
+        }
+        self.update_matches();
+
+        let name = name_row.text();
+        let name = name.trim();
+        let taken = self.name_taken(name);
+        if taken {
+            name_row.add_css_class("error");
+            name_row.set_tooltip_text(Some("A custom colour already has this name"));
+        } else {
+            name_row.remove_css_class("error");
+            name_row.set_tooltip_text(None);
+        }
+        save_button.set_sensitive(rgb.is_some() && !name.is_empty() && !taken);
+    }
+
+    fn name_taken(&self, name: &str) -> bool {
+        let imp = self.imp();
+        if imp.original_name.borrow().as_deref() == Some(name) {
+            return false;
+        }
+        imp.db.get().is_some_and(|db| {
+            db.find(&ColorKey::new(ColorLibrary::Custom.id(), name))
+                .is_some()
+        })
+    }
+
+    fn update_matches(&self) {
+        let imp = self.imp();
+        let (Some(group), Some(db)) = (imp.matches.get(), imp.db.get()) else {
+            return;
+        };
+        for row in imp.match_rows.take() {
+            group.remove(&row);
+        }
+        let Some(rgb) = imp.rgb.get() else {
+            group.set_visible(false);
+            return;
+        };
+
+        let formula = imp.formula.get();
+        let mut rows = Vec::new();
+        for color_match in db.nearest(&rgb, formula, MATCH_COUNT) {
+            let row = match_row(&color_match, formula);
+            group.add(&row);
+            rows.push(row);
+        }
+        group.set_visible(!rows.is_empty());
+        imp.match_rows.replace(rows);
+    }
+
+    /// Fill the value from a colour picked off the screen.
+    fn pick_color(&self) {
+        let Some(connection) = gio::Application::default().and_then(|app| app.dbus_connection())
+        else {
+            return;
+        };
+        portal::pick_color(
+            &connection,
+            "",
+            glib::clone!(
+                #[weak(rename_to = dialog)]
+                self,
+                move |result| match result {
+                    Ok(rgb) => {
+                        if let Some(row) = dialog.imp().value_row.get() {
+                            row.set_text(&rgb_to_hex(&rgb));
+                        }
+                    }
+                    Err(PortalError::Cancelled) => {}
+                    // Mark the value, as the dialog has no room for a toast
+                    Err(_) => {
+                        if let Some(row) = dialog.imp().value_row.get() {
+                            row.add_css_class("error");
+                        }
+                    }
+                }
+            ),
+        );
+    }
+
+    /// The name the colour had when the dialog opened, if it was being edited.
+    pub fn original_name(&self) -> Option<String> {
+        self.imp().original_name.borrow().clone()
+    }
+
+    /// The colour as entered, once the value reads as a colour.
+    pub fn custom_color(&self) -> Option<CustomColor> {
+        let imp = self.imp();
+        let text = |row: &OnceCell<adw::EntryRow>| {
+            row.get()
+                .map(|row| row.text().trim().to_string())
+                .unwrap_or_default()
+        };
+        Some(CustomColor {
+            name: text(&imp.name_row),
+            hex: rgb_to_hex(&imp.rgb.get()?),
+            code: text(&imp.code_row),
+            notes: text(&imp.notes_row),
+        })
+    }
+
+    pub fn connect_saved<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "saved",
+            false,
+            glib::closure_local!(move |dialog: &Self| f(dialog)),
+        )
+    }
+}
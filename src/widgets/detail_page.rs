@@ -1,15 +1,57 @@
-use crate::color::PantoneColor;
+use crate::color::{
+    prefers_dark_text, Annotation, ColorMatch, CopyFormat, CustomColor, DeltaE, Notation,
+    PantoneColor,
+};
+use crate::widgets::{add_to_palette_menu, match_row, ColorPreview};
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use gtk::{gdk, glib, graphene, gsk};
-use std::cell::RefCell;
+use gtk::{gdk, gio, glib};
+use std::cell::{Cell, RefCell};
+
+/// Recently viewed colours shown above the preview.
+const RECENT_STRIP_LENGTH: usize = 16;
 
 mod imp {
     use super::*;
 
-    #[derive(Default)]
     pub struct ColorDetailPanel {
         pub color: RefCell<Option<PantoneColor>>,
+        pub notations: RefCell<Vec<Notation>>,
+        pub starred: Cell<bool>,
+        pub star_button: RefCell<Option<gtk::Button>>,
+        pub palette_names: RefCell<Vec<String>>,
+        pub palette_button: RefCell<Option<gtk::MenuButton>>,
+        pub recent: RefCell<Vec<PantoneColor>>,
+        pub recent_strip: RefCell<Option<gtk::Box>>,
+        pub custom: RefCell<Option<CustomColor>>,
+        pub edit_button: RefCell<Option<gtk::Button>>,
+        pub matches: RefCell<Vec<ColorMatch>>,
+        pub formula: Cell<DeltaE>,
+        pub extras: RefCell<Option<gtk::Box>>,
+        pub annotation: RefCell<Annotation>,
+        pub annotation_box: RefCell<Option<gtk::Box>>,
+    }
+
+    impl Default for ColorDetailPanel {
+        fn default() -> Self {
+            Self {
+                color: RefCell::default(),
+                notations: RefCell::new(Notation::all().to_vec()),
+                starred: Cell::new(false),
+                star_button: RefCell::default(),
+                palette_names: RefCell::default(),
+                palette_button: RefCell::default(),
+                recent: RefCell::default(),
+                recent_strip: RefCell::default(),
+                custom: RefCell::default(),
+                edit_button: RefCell::default(),
+                matches: RefCell::default(),
+                formula: Cell::default(),
+                extras: RefCell::default(),
+                annotation: RefCell::default(),
+                annotation_box: RefCell::default(),
+            }
+        }
     }
 
     #[glib::object_subclass]
@@ -23,7 +65,7 @@ mod imp {
         fn constructed(&self) {
             self.parent_constructed();
             self.obj().set_orientation(gtk::Orientation::Vertical);
-            self.obj().set_spacing(8);
+            self.obj().set_spacing(16);
             self.obj().set_margin_start(16);
             self.obj().set_margin_end(16);
             self.obj().set_margin_top(16);
@@ -46,6 +88,231 @@ impl ColorDetailPanel {
         glib::Object::new()
     }
 
+    pub fn color(&self) -> Option<PantoneColor> {
+        self.imp().color.borrow().clone()
+    }
+
+    /// Choose which values are listed, in order.
+    pub fn set_notations(&self, notations: Vec<Notation>) {
+        self.imp().notations.replace(notations);
+        if let Some(color) = self.color() {
+            self.set_color(&color);
+        }
+    }
+
+    /// Whether the shown colour is a favourite.
+    pub fn set_starred(&self, starred: bool) {
+        self.imp().starred.set(starred);
+        self.update_star_button();
+    }
+
+    fn update_star_button(&self) {
+        let Some(button) = self.imp().star_button.borrow().clone() else {
+            return;
+        };
+        if self.imp().starred.get() {
+            button.set_icon_name("starred-symbolic");
+            button.set_tooltip_text(Some("Remove from Favourites"));
+        } else {
+            button.set_icon_name("non-starred-symbolic");
+            button.set_tooltip_text(Some("Add to Favourites"));
+        }
+    }
+
+    /// Recently viewed colours, most recent first, for the strip above the preview.
+    pub fn set_recent(&self, colors: Vec<PantoneColor>) {
+        self.imp().recent.replace(colors);
+        self.update_recent_strip();
+    }
+
+    /// Back and forward buttons beside a strip of recently viewed colours.
+    fn history_bar(&self) -> gtk::Box {
+        let back = gtk::Button::from_icon_name("go-previous-symbolic");
+        back.set_tooltip_text(Some("Back"));
+        back.set_action_name(Some("win.history-back"));
+        let forward = gtk::Button::from_icon_name("go-next-symbolic");
+        forward.set_tooltip_text(Some("Forward"));
+        forward.set_action_name(Some("win.history-forward"));
+        let navigation = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        navigation.add_css_class("linked");
+        navigation.set_valign(gtk::Align::Center);
+        navigation.append(&back);
+        navigation.append(&forward);
+
+        let strip = gtk::Box::new(gtk::Orientation::Horizontal, 4);
+        let scroller = gtk::ScrolledWindow::new();
+        scroller.add_css_class("no-scrollbar");
+        scroller.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Never);
+        scroller.set_hexpand(true);
+        scroller.set_child(Some(&strip));
+
+        let bar = gtk::Box::new(gtk::Orientation::Horizontal, 8);
+        bar.append(&navigation);
+        bar.append(&scroller);
+        self.imp().recent_strip.replace(Some(strip));
+        self.update_recent_strip();
+        bar
+    }
+
+    fn update_recent_strip(&self) {
+        let imp = self.imp();
+        let Some(strip) = imp.recent_strip.borrow().clone() else {
+            return;
+        };
+        while let Some(child) = strip.first_child() {
+            strip.remove(&child);
+        }
+        for (index, color) in imp
+            .recent
+            .borrow()
+            .iter()
+            .take(RECENT_STRIP_LENGTH)
+            .enumerate()
+        {
+            let preview = ColorPreview::new(color.rgb);
+            preview.set_size_request(24, 24);
+            let button = gtk::Button::new();
+            button.add_css_class("flat");
+            button.set_child(Some(&preview));
+            button.set_tooltip_text(Some(&color.name));
+            button.set_action_name(Some("win.show-recent"));
+            button.set_action_target_value(Some(&(index as u32).to_variant()));
+            strip.append(&button);
+        }
+    }
+
+    /// The Custom library entry behind the shown colour, offering to edit it.
+    pub fn set_custom(&self, custom: Option<CustomColor>) {
+        self.imp().custom.replace(custom);
+        self.update_extras();
+    }
+
+    /// Pantone colours close to the shown one, listed under its values.
+    pub fn set_matches(&self, matches: Vec<ColorMatch>, formula: DeltaE) {
+        self.imp().matches.replace(matches);
+        self.imp().formula.set(formula);
+        self.update_extras();
+    }
+
+    fn update_extras(&self) {
+        let imp = self.imp();
+        let custom = imp.custom.borrow();
+        if let Some(button) = imp.edit_button.borrow().as_ref() {
+            button.set_visible(custom.is_some());
+            if let Some(custom) = custom.as_ref() {
+                button.set_action_target_value(Some(&custom.name.to_variant()));
+            }
+        }
+        let Some(extras) = imp.extras.borrow().clone() else {
+            return;
+        };
+        while let Some(child) = extras.first_child() {
+            extras.remove(&child);
+        }
+
+        let matches = imp.matches.borrow();
+        if !matches.is_empty() {
+            let group = adw::PreferencesGroup::builder()
+                .title("Nearest Pantone")
+                .build();
+            for color_match in matches.iter() {
+                let key = color_match.color.key();
+                let row = match_row(color_match, imp.formula.get());
+                row.set_activatable(true);
+                row.set_action_name(Some("win.show-color"));
+                row.set_action_target_value(Some(
+                    &(key.library.as_str(), key.name.as_str()).to_variant(),
+                ));
+                group.add(&row);
+            }
+            extras.append(&group);
+        }
+        extras.set_visible(extras.first_child().is_some());
+    }
+
+    /// The user's notes and tags on the shown colour.
+    pub fn set_annotation(&self, annotation: Annotation) {
+        self.imp().annotation.replace(annotation);
+        self.update_annotation();
+    }
+
+    fn update_annotation(&self) {
+        let imp = self.imp();
+        let Some(container) = imp.annotation_box.borrow().clone() else {
+            return;
+        };
+        while let Some(child) = container.first_child() {
+            container.remove(&child);
+        }
+        let annotation = imp.annotation.borrow();
+
+        // Enter or the apply button saves, so half-typed text is not stored
+        let notes_row = adw::EntryRow::builder()
+            .title("Notes")
+            .text(annotation.notes.as_str())
+            .show_apply_button(true)
+            .build();
+        notes_row.connect_apply(|row| {
+            let _ = row.activate_action("win.set-notes", Some(&row.text().to_variant()));
+        });
+        let tag_row = adw::EntryRow::builder()
+            .title("Add Tag")
+            .show_apply_button(true)
+            .build();
+        tag_row.connect_apply(|row| {
+            let _ = row.activate_action("win.add-tag", Some(&row.text().to_variant()));
+        });
+
+        let group = adw::PreferencesGroup::builder()
+            .title("Notes and Tags")
+            .build();
+        group.add(&notes_row);
+        group.add(&tag_row);
+        container.append(&group);
+
+        if annotation.tags.is_empty() {
+            return;
+        }
+        let tags = gtk::FlowBox::new();
+        tags.set_selection_mode(gtk::SelectionMode::None);
+        tags.set_column_spacing(6);
+        tags.set_row_spacing(6);
+        tags.set_max_children_per_line(8);
+        for tag in &annotation.tags {
+            // Tap a tag to find every colour carrying it
+            let search = gtk::Button::with_label(tag);
+            search.set_tooltip_text(Some("Show Colours with This Tag"));
+            search.set_action_name(Some("win.search-tag"));
+            search.set_action_target_value(Some(&tag.to_variant()));
+            let remove = gtk::Button::from_icon_name("window-close-symbolic");
+            remove.set_tooltip_text(Some("Remove Tag"));
+            remove.set_action_name(Some("win.remove-tag"));
+            remove.set_action_target_value(Some(&tag.to_variant()));
+            let chip = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+            chip.add_css_class("linked");
+            chip.append(&search);
+            chip.append(&remove);
+            tags.append(&chip);
+        }
+        container.append(&tags);
+    }
+
+    /// Palettes offered by the "Add to Palette" button.
+    pub fn set_palette_names(&self, names: Vec<String>) {
+        self.imp().palette_names.replace(names);
+        self.update_palette_menu();
+    }
+
+    fn update_palette_menu(&self) {
+        let imp = self.imp();
+        let (Some(button), Some(color)) = (imp.palette_button.borrow().clone(), self.color())
+        else {
+            return;
+        };
+        let menu = add_to_palette_menu(&imp.palette_names.borrow(), &color.key());
+        button.set_menu_model(Some(&menu));
+    }
+
     pub fn set_color(&self, color: &PantoneColor) {
         self.imp().color.replace(Some(color.clone()));
 
@@ -54,71 +321,147 @@ impl ColorDetailPanel {
             self.remove(&child);
         }
 
-        // Color preview (tap to copy)
-        let preview = ColorPreview::new(color);
+        self.append(&self.history_bar());
+        self.append(&Self::header(color));
+
+        let compare_button = gtk::Button::with_label("Compare");
+        compare_button.add_css_class("pill");
+        compare_button.set_action_name(Some("win.compare-color"));
+
+        let key = color.key();
+        let star_button = gtk::Button::new();
+        star_button.add_css_class("circular");
+        star_button.set_valign(gtk::Align::Center);
+        star_button.set_action_name(Some("win.toggle-favourite"));
+        star_button.set_action_target_value(Some(
+            &(key.library.as_str(), key.name.as_str()).to_variant(),
+        ));
+        self.imp().star_button.replace(Some(star_button.clone()));
+        self.update_star_button();
+
+        let palette_button = gtk::MenuButton::new();
+        palette_button.set_icon_name("list-add-symbolic");
+        palette_button.set_tooltip_text(Some("Add to Palette"));
+        palette_button.add_css_class("circular");
+        palette_button.set_valign(gtk::Align::Center);
+        self.imp()
+            .palette_button
+            .replace(Some(palette_button.clone()));
+        self.update_palette_menu();
+
+        let edit_button = gtk::Button::from_icon_name("document-edit-symbolic");
+        edit_button.set_tooltip_text(Some("Edit Custom Colour"));
+        edit_button.add_css_class("circular");
+        edit_button.set_valign(gtk::Align::Center);
+        edit_button.set_action_name(Some("win.edit-custom-color"));
+        self.imp().edit_button.replace(Some(edit_button.clone()));
+
+        let actions = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+        actions.set_halign(gtk::Align::Center);
+        actions.append(&compare_button);
+        actions.append(&star_button);
+        actions.append(&palette_button);
+        actions.append(&edit_button);
+        self.append(&actions);
+
+        let notations = self.imp().notations.borrow().clone();
+        if !notations.is_empty() {
+            let values = adw::PreferencesGroup::builder().title("Values").build();
+            for notation in notations {
+                values.add(&value_row(
+                    notation.display_name(),
+                    &notation.format(&color.rgb),
+                ));
+            }
+            self.append(&values);
+        }
+
+        let catalogue = adw::PreferencesGroup::builder().title("Catalogue").build();
+        catalogue.add(&value_row("Family", color.family.display_name()));
+        catalogue.add(&value_row("Library", color.library.display_name()));
+        if let Some(code) = color.code() {
+            catalogue.add(&value_row("Code", code));
+        }
+        self.append(&catalogue);
+
+        let annotation_box = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        self.append(&annotation_box);
+        self.imp().annotation_box.replace(Some(annotation_box));
+        self.update_annotation();
+
+        let extras = gtk::Box::new(gtk::Orientation::Vertical, 16);
+        self.append(&extras);
+        self.imp().extras.replace(Some(extras));
+        self.update_extras();
+    }
+
+    /// The preview with the name and hex on top, in black or white, whichever reads better.
+    fn header(color: &PantoneColor) -> gtk::Overlay {
+        // Tap to copy in the default format; right-click or long-press for the others
+        let preview = ColorPreview::new(color.rgb);
         preview.set_height_request(200);
         preview.set_hexpand(true);
         preview.set_cursor_from_name(Some("pointer"));
-
         let gesture = gtk::GestureClick::new();
-        let hex_value = color.hex.clone();
-        gesture.connect_released(move |gesture, _, _, _| {
-            if let Some(display) = gdk::Display::default() {
-                display.clipboard().set_text(&hex_value);
-                if let Some(widget) = gesture.widget() {
-                    let mut parent = widget.parent();
-                    while let Some(p) = parent {
-                        if let Ok(overlay) = p.clone().downcast::<adw::ToastOverlay>() {
-                            let toast = adw::Toast::new("Copied");
-                            toast.set_timeout(1);
-                            overlay.add_toast(toast);
-                            break;
-                        }
-                        parent = p.parent();
-                    }
-                }
+        gesture.connect_released(|gesture, _, _, _| {
+            if let Some(widget) = gesture.widget() {
+                let _ = widget.activate_action("win.copy-detail", None);
             }
         });
         preview.add_controller(gesture);
 
-        self.append(&preview);
+        let context_click = gtk::GestureClick::new();
+        context_click.set_button(gdk::BUTTON_SECONDARY);
+        let menu_color = color.clone();
+        context_click.connect_pressed(move |gesture, _, x, y| {
+            if let Some(widget) = gesture.widget() {
+                // Claimed, so the tap-to-copy gesture does not copy as well
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                show_copy_menu(&widget, &menu_color, x, y);
+            }
+        });
+        preview.add_controller(context_click);
+
+        let long_press = gtk::GestureLongPress::new();
+        let menu_color = color.clone();
+        long_press.connect_pressed(move |gesture, x, y| {
+            if let Some(widget) = gesture.widget() {
+                // Claimed, so the tap-to-copy gesture does not copy as well
+                gesture.set_state(gtk::EventSequenceState::Claimed);
+                show_copy_menu(&widget, &menu_color, x, y);
+            }
+        });
+        preview.add_controller(long_press);
 
-        // Color name
+        let contrast_class = if prefers_dark_text(&color.rgb) {
+            "dark"
+        } else {
+            "light"
+        };
         let name_label = gtk::Label::new(Some(&color.name));
         name_label.add_css_class("title-2");
-        name_label.set_halign(gtk::Align::Center);
-        name_label.set_margin_top(8);
-        self.append(&name_label);
-
-        // HEX value (tap to copy)
+        name_label.set_wrap(true);
+        name_label.set_xalign(0.0);
         let hex_label = gtk::Label::new(Some(&color.hex));
-        hex_label.add_css_class("dim-label");
-        hex_label.set_halign(gtk::Align::Center);
-        hex_label.set_cursor_from_name(Some("pointer"));
+        hex_label.add_css_class("monospace");
+        hex_label.set_xalign(0.0);
 
-        let gesture = gtk::GestureClick::new();
-        let hex_value = color.hex.clone();
-        gesture.connect_released(move |gesture, _, _, _| {
-            if let Some(display) = gdk::Display::default() {
-                display.clipboard().set_text(&hex_value);
-                // Show toast
-                if let Some(widget) = gesture.widget() {
-                    let mut parent = widget.parent();
-                    while let Some(p) = parent {
-                        if let Ok(overlay) = p.clone().downcast::<adw::ToastOverlay>() {
-                            let toast = adw::Toast::new("Copied");
-                            toast.set_timeout(1);
-                            overlay.add_toast(toast);
-                            break;
-                        }
-                        parent = p.parent();
-                    }
-                }
-            }
-        });
-        hex_label.add_controller(gesture);
+        let labels = gtk::Box::new(gtk::Orientation::Vertical, 2);
+        labels.set_halign(gtk::Align::Start);
+        labels.set_valign(gtk::Align::End);
+        labels.set_margin_start(16);
+        labels.set_margin_end(16);
+        labels.set_margin_bottom(14);
+        labels.set_can_target(false);
+        labels.add_css_class("preview-label");
+        labels.add_css_class(contrast_class);
+        labels.append(&name_label);
+        labels.append(&hex_label);
 
-        self.append(&hex_label);
+        let overlay = gtk::Overlay::new();
+        overlay.set_child(Some(&preview));
+        overlay.add_overlay(&labels);
+        overlay
     }
 }
 
@@ -128,64 +471,66 @@ impl Default for ColorDetailPanel {
     }
 }
 
-// Simple color preview widget
-mod color_preview {
-    use super::*;
-
-    mod imp {
-        use super::*;
-
-        #[derive(Default)]
-        pub struct ColorPreview {
-            pub color: RefCell<Option<PantoneColor>>,
-        }
+/// A read-only property row with its own copy button.
+fn value_row(title: &str, value: &str) -> adw::ActionRow {
+    let row = adw::ActionRow::builder()
+        .title(title)
+        .subtitle(glib::markup_escape_text(value))
+        .subtitle_selectable(true)
+        .build();
+    row.add_css_class("property");
 
-        #[glib::object_subclass]
-        impl ObjectSubclass for ColorPreview {
-            const NAME: &'static str = "LonColorPreview";
-            type Type = super::ColorPreview;
-            type ParentType = gtk::Widget;
-        }
-
-        impl ObjectImpl for ColorPreview {}
-
-        impl WidgetImpl for ColorPreview {
-            fn snapshot(&self, snapshot: &gtk::Snapshot) {
-                let widget = self.obj();
-                let width = widget.width() as f32;
-                let height = widget.height() as f32;
-
-                if let Some(color) = self.color.borrow().as_ref() {
-                    let gdk_color = gdk::RGBA::new(
-                        color.rgb.r as f32 / 255.0,
-                        color.rgb.g as f32 / 255.0,
-                        color.rgb.b as f32 / 255.0,
-                        1.0,
-                    );
-
-                    let rect = graphene::Rect::new(0.0, 0.0, width, height);
-                    let rounded = gsk::RoundedRect::from_rect(rect, 12.0);
+    let copy_button = gtk::Button::from_icon_name("edit-copy-symbolic");
+    copy_button.add_css_class("flat");
+    copy_button.set_valign(gtk::Align::Center);
+    copy_button.set_tooltip_text(Some(&format!("Copy {}", title)));
+    let value = value.to_string();
+    let toast = format!("Copied {}", title);
+    copy_button.connect_clicked(move |button| copy_with_toast(button, &value, &toast));
+    row.add_suffix(&copy_button);
+    row
+}
 
-                    snapshot.push_rounded_clip(&rounded);
-                    snapshot.append_color(&gdk_color, &rect);
-                    snapshot.pop();
-                }
-            }
-        }
+/// Pop up every copy format for `color` at `(x, y)` in `widget`.
+fn show_copy_menu(widget: &gtk::Widget, color: &PantoneColor, x: f64, y: f64) {
+    let menu = gio::Menu::new();
+    for format in CopyFormat::all() {
+        let label = match format {
+            CopyFormat::Template => format.display_name().to_string(),
+            _ => format.format(color, ""),
+        };
+        let item = gio::MenuItem::new(Some(&label), None);
+        item.set_action_and_target_value(
+            Some("win.copy-detail-as"),
+            Some(&format.id().to_variant()),
+        );
+        menu.append_item(&item);
     }
 
-    glib::wrapper! {
-        pub struct ColorPreview(ObjectSubclass<imp::ColorPreview>)
-            @extends gtk::Widget;
-    }
+    let popover = gtk::PopoverMenu::from_model(Some(&menu));
+    popover.set_parent(widget);
+    popover.set_has_arrow(false);
+    popover.set_halign(gtk::Align::Start);
+    popover.set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+    // Unparent once the menu item has had its chance to activate
+    popover.connect_closed(|popover| {
+        let popover = popover.clone();
+        glib::idle_add_local_once(move || popover.unparent());
+    });
+    popover.popup();
+}
 
-    impl ColorPreview {
-        pub fn new(color: &PantoneColor) -> Self {
-            let obj: Self = glib::Object::new();
-            obj.imp().color.replace(Some(color.clone()));
-            obj
+/// Put `text` on the clipboard and confirm it in the nearest toast overlay.
+fn copy_with_toast(widget: &impl IsA<gtk::Widget>, text: &str, message: &str) {
+    widget.clipboard().set_text(text);
+    let mut parent = widget.parent();
+    while let Some(p) = parent {
+        if let Ok(overlay) = p.clone().downcast::<adw::ToastOverlay>() {
+            let toast = adw::Toast::new(message);
+            toast.set_timeout(1);
+            overlay.add_toast(toast);
+            break;
         }
+        parent = p.parent();
     }
 }
-
-use color_preview::ColorPreview;
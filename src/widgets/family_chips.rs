@@ -0,0 +1,141 @@
+use crate::color::{prefers_dark_text, ColorFamily};
+use adw::subclass::prelude::*;
+use gtk::glib;
+use gtk::glib::subclass::Signal;
+use gtk::prelude::*;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::sync::OnceLock;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct FamilyChips {
+        pub buttons: RefCell<Vec<(ColorFamily, gtk::ToggleButton)>>,
+        pub selected: RefCell<HashSet<ColorFamily>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for FamilyChips {
+        const NAME: &'static str = "LonFamilyChips";
+        type Type = super::FamilyChips;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for FamilyChips {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| vec![Signal::builder("changed").build()])
+        }
+    }
+
+    impl WidgetImpl for FamilyChips {}
+    impl BoxImpl for FamilyChips {}
+}
+
+glib::wrapper! {
+    pub struct FamilyChips(ObjectSubclass<imp::FamilyChips>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Orientable;
+}
+
+impl FamilyChips {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn setup(&self) {
+        self.set_orientation(gtk::Orientation::Horizontal);
+        self.set_spacing(6);
+        self.add_css_class("family-chips");
+
+        // Each family gets its own class so the chip can be tinted with its colour
+        let mut css = String::new();
+        for family in ColorFamily::all() {
+            let rgb = family.representative_rgb();
+            let fg = if prefers_dark_text(&rgb) {
+                "#000000"
+            } else {
+                "#ffffff"
+            };
+            css.push_str(&format!(
+                ".family-chip.{class} {{ box-shadow: inset 0 0 0 2px rgb({r}, {g}, {b}); }}
+                 .family-chip.{class}:checked {{ background: rgb({r}, {g}, {b}); color: {fg}; }}\n",
+                class = Self::css_class(*family),
+                r = rgb.r,
+                g = rgb.g,
+                b = rgb.b,
+            ));
+        }
+        let provider = gtk::CssProvider::new();
+        provider.load_from_string(&css);
+        gtk::style_context_add_provider_for_display(
+            &self.display(),
+            &provider,
+            gtk::STYLE_PROVIDER_PRIORITY_APPLICATION,
+        );
+
+        let mut buttons = Vec::new();
+        for family in ColorFamily::all() {
+            let family = *family;
+            let button = gtk::ToggleButton::with_label(family.display_name());
+            button.add_css_class("family-chip");
+            button.add_css_class("pill");
+            button.add_css_class(&Self::css_class(family));
+            button.connect_toggled(glib::clone!(
+                #[weak(rename_to = chips)]
+                self,
+                move |button| {
+                    {
+                        let mut selected = chips.imp().selected.borrow_mut();
+                        if button.is_active() {
+                            selected.insert(family);
+                        } else {
+                            selected.remove(&family);
+                        }
+                    }
+                    chips.emit_by_name::<()>("changed", &[]);
+                }
+            ));
+            self.append(&button);
+            buttons.push((family, button));
+        }
+        self.imp().buttons.replace(buttons);
+    }
+
+    fn css_class(family: ColorFamily) -> String {
+        format!("family-{}", family.display_name().to_lowercase())
+    }
+
+    /// Families currently toggled on. An empty set means no filtering.
+    pub fn selected(&self) -> HashSet<ColorFamily> {
+        self.imp().selected.borrow().clone()
+    }
+
+    pub fn set_counts(&self, counts: &HashMap<ColorFamily, usize>) {
+        for (family, button) in self.imp().buttons.borrow().iter() {
+            let count = counts.get(family).copied().unwrap_or(0);
+            button.set_label(&format!("{} · {}", family.display_name(), count));
+        }
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            false,
+            glib::closure_local!(move |chips: &Self| f(chips)),
+        )
+    }
+}
+
+impl Default for FamilyChips {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,317 @@
+use crate::color::{rgb_to_hex, ColorDatabase, ColorMatch, DeltaE, Rgb};
+use crate::widgets::{match_row, ColorObject, ColorPreview, ImageView};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib::subclass::Signal;
+use gtk::{gdk, glib};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+const MATCH_COUNT: usize = 3;
+/// Sample sizes offered, as radii around the clicked pixel.
+const SAMPLE_SIZES: [(&str, usize); 3] = [
+    ("Single Pixel", 0),
+    ("3 × 3 Average", 1),
+    ("5 × 5 Average", 2),
+];
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct ImageInspector {
+        pub db: OnceCell<Rc<ColorDatabase>>,
+        pub formula: Cell<DeltaE>,
+        pub view: OnceCell<ImageView>,
+        pub preview: OnceCell<ColorPreview>,
+        pub hex_label: OnceCell<gtk::Label>,
+        pub match_list: OnceCell<gtk::ListBox>,
+        pub tray: OnceCell<gtk::Box>,
+        pub tray_group: OnceCell<gtk::Box>,
+        /// The last clicked sample, shown whenever the pointer is off the image.
+        pub pinned: Cell<Option<Rgb>>,
+        pub shown: Cell<Option<Rgb>>,
+        pub matches: RefCell<Vec<ColorMatch>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ImageInspector {
+        const NAME: &'static str = "LonImageInspector";
+        type Type = super::ImageInspector;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for ImageInspector {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("color-activated")
+                    .param_types([ColorObject::static_type()])
+                    .build()]
+            })
+        }
+    }
+
+    impl WidgetImpl for ImageInspector {}
+    impl AdwDialogImpl for ImageInspector {}
+}
+
+glib::wrapper! {
+    pub struct ImageInspector(ObjectSubclass<imp::ImageInspector>)
+        @extends adw::Dialog, gtk::Widget;
+}
+
+impl ImageInspector {
+    /// Inspect `texture` pixel by pixel, matching the colour under the loupe live.
+    pub fn new(name: &str, texture: &gdk::Texture, db: Rc<ColorDatabase>, formula: DeltaE) -> Self {
+        let dialog: Self = glib::Object::builder()
+            .property("title", name)
+            .property("content-width", 720)
+            .property("content-height", 760)
+            .build();
+        let _ = dialog.imp().db.set(db);
+        dialog.imp().formula.set(formula);
+        dialog.setup(texture);
+        dialog
+    }
+
+    fn setup(&self, texture: &gdk::Texture) {
+        let imp = self.imp();
+
+        let view = ImageView::new();
+        view.set_texture(texture);
+        view.set_vexpand(true);
+        view.set_hexpand(true);
+        view.connect_hover_changed(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |view| dialog.show_rgb(view.hovered_color().or(dialog.imp().pinned.get()))
+        ));
+        view.connect_pixel_clicked(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |view| {
+                if let Some(rgb) = view.hovered_color() {
+                    dialog.add_sample(rgb);
+                }
+            }
+        ));
+
+        let sample_size =
+            gtk::DropDown::from_strings(&SAMPLE_SIZES.iter().map(|(n, _)| *n).collect::<Vec<_>>());
+        sample_size.set_tooltip_text(Some("Sample Size"));
+        sample_size.connect_selected_notify(glib::clone!(
+            #[weak]
+            view,
+            move |dropdown| {
+                let radius = SAMPLE_SIZES
+                    .get(dropdown.selected() as usize)
+                    .map_or(0, |(_, r)| *r);
+                view.set_sample_radius(radius);
+            }
+        ));
+
+        let zoom_box = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+        zoom_box.add_css_class("linked");
+        for (icon, tooltip) in [
+            ("zoom-out-symbolic", "Zoom Out"),
+            ("zoom-fit-best-symbolic", "Fit to View"),
+            ("zoom-in-symbolic", "Zoom In"),
+        ] {
+            let button = gtk::Button::from_icon_name(icon);
+            button.set_tooltip_text(Some(tooltip));
+            button.connect_clicked(glib::clone!(
+                #[weak]
+                view,
+                move |_| match icon {
+                    "zoom-out-symbolic" => view.zoom_out(),
+                    "zoom-in-symbolic" => view.zoom_in(),
+                    _ => view.zoom_to_fit(),
+                }
+            ));
+            zoom_box.append(&button);
+        }
+
+        let header = adw::HeaderBar::new();
+        header.pack_start(&sample_size);
+        header.pack_end(&zoom_box);
+
+        // Current colour and its nearest library matches
+        let preview = ColorPreview::new(Rgb { r: 0, g: 0, b: 0 });
+        preview.set_size_request(48, 48);
+        preview.set_valign(gtk::Align::Center);
+        preview.set_visible(false);
+
+        let hex_label = gtk::Label::new(Some("Hover over the image to sample it"));
+        hex_label.add_css_class("dim-label");
+        hex_label.set_xalign(0.0);
+        hex_label.set_selectable(true);
+
+        let current = gtk::Box::new(gtk::Orientation::Horizontal, 12);
+        current.append(&preview);
+        current.append(&hex_label);
+
+        let match_list = gtk::ListBox::new();
+        match_list.add_css_class("boxed-list");
+        match_list.set_selection_mode(gtk::SelectionMode::None);
+        match_list.set_visible(false);
+        match_list.connect_row_activated(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_, row| {
+                let color = dialog
+                    .imp()
+                    .matches
+                    .borrow()
+                    .get(row.index() as usize)
+                    .map(|m| m.color.clone());
+                if let Some(color) = color {
+                    dialog.emit_by_name::<()>("color-activated", &[&ColorObject::new(color)]);
+                    dialog.close();
+                }
+            }
+        ));
+
+        // Every clicked sample, most recent last; clicking one pins it again
+        let tray = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        let tray_scroller = gtk::ScrolledWindow::new();
+        tray_scroller.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Never);
+        tray_scroller.set_hexpand(true);
+        tray_scroller.set_child(Some(&tray));
+
+        let clear_button = gtk::Button::from_icon_name("edit-clear-all-symbolic");
+        clear_button.set_tooltip_text(Some("Clear Samples"));
+        clear_button.set_valign(gtk::Align::Center);
+        clear_button.add_css_class("flat");
+        clear_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| dialog.clear_samples()
+        ));
+
+        let tray_group = gtk::Box::new(gtk::Orientation::Horizontal, 6);
+        tray_group.append(&tray_scroller);
+        tray_group.append(&clear_button);
+        tray_group.set_visible(false);
+
+        let panel = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        panel.set_margin_start(16);
+        panel.set_margin_end(16);
+        panel.set_margin_top(12);
+        panel.set_margin_bottom(16);
+        panel.append(&current);
+        panel.append(&match_list);
+        panel.append(&tray_group);
+
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        content.append(&view);
+        content.append(&gtk::Separator::new(gtk::Orientation::Horizontal));
+        content.append(&panel);
+
+        let toolbar = adw::ToolbarView::new();
+        toolbar.add_top_bar(&header);
+        toolbar.set_content(Some(&content));
+        self.set_child(Some(&toolbar));
+
+        let _ = imp.view.set(view);
+        let _ = imp.preview.set(preview);
+        let _ = imp.hex_label.set(hex_label);
+        let _ = imp.match_list.set(match_list);
+        let _ = imp.tray.set(tray);
+        let _ = imp.tray_group.set(tray_group);
+    }
+
+    fn show_rgb(&self, rgb: Option<Rgb>) {
+        let imp = self.imp();
+        if imp.shown.replace(rgb) == rgb {
+            return;
+        }
+        let (Some(db), Some(preview), Some(hex_label), Some(match_list)) = (
+            imp.db.get(),
+            imp.preview.get(),
+            imp.hex_label.get(),
+            imp.match_list.get(),
+        ) else {
+            return;
+        };
+
+        match_list.remove_all();
+        let Some(rgb) = rgb else {
+            preview.set_visible(false);
+            match_list.set_visible(false);
+            hex_label.set_label("Hover over the image to sample it");
+            hex_label.add_css_class("dim-label");
+            imp.matches.replace(Vec::new());
+            return;
+        };
+
+        preview.set_rgb(rgb);
+        preview.set_visible(true);
+        hex_label.set_label(&format!("{}  ·  {}", rgb_to_hex(&rgb), rgb));
+        hex_label.remove_css_class("dim-label");
+
+        let formula = imp.formula.get();
+        let matches = db.nearest(&rgb, formula, MATCH_COUNT);
+        for color_match in &matches {
+            let row = match_row(color_match, formula);
+            row.set_activatable(true);
+            match_list.append(&row);
+        }
+        match_list.set_visible(!matches.is_empty());
+        imp.matches.replace(matches);
+    }
+
+    fn add_sample(&self, rgb: Rgb) {
+        let imp = self.imp();
+        let (Some(tray), Some(tray_group)) = (imp.tray.get(), imp.tray_group.get()) else {
+            return;
+        };
+
+        let swatch = ColorPreview::new(rgb);
+        swatch.set_size_request(28, 28);
+        let button = gtk::Button::new();
+        button.set_child(Some(&swatch));
+        button.add_css_class("flat");
+        button.set_tooltip_text(Some(&rgb_to_hex(&rgb)));
+        button.connect_clicked(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| dialog.pin(rgb)
+        ));
+        tray.append(&button);
+        tray_group.set_visible(true);
+        self.pin(rgb);
+    }
+
+    fn pin(&self, rgb: Rgb) {
+        self.imp().pinned.set(Some(rgb));
+        self.show_rgb(Some(rgb));
+    }
+
+    fn clear_samples(&self) {
+        let imp = self.imp();
+        if let Some(tray) = imp.tray.get() {
+            while let Some(child) = tray.first_child() {
+                tray.remove(&child);
+            }
+        }
+        if let Some(tray_group) = imp.tray_group.get() {
+            tray_group.set_visible(false);
+        }
+        imp.pinned.set(None);
+        let hovered = imp.view.get().and_then(|view| view.hovered_color());
+        self.show_rgb(hovered);
+    }
+
+    pub fn connect_color_activated<F: Fn(&Self, &ColorObject) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "color-activated",
+            false,
+            glib::closure_local!(move |dialog: &Self, color: &ColorObject| f(dialog, color)),
+        )
+    }
+}
@@ -0,0 +1,417 @@
+use crate::color::Rgb;
+use crate::image::ImagePixels;
+use adw::subclass::prelude::*;
+use gtk::glib::subclass::Signal;
+use gtk::prelude::*;
+use gtk::{gdk, glib, graphene, gsk};
+use std::cell::{Cell, RefCell};
+use std::sync::OnceLock;
+
+const MIN_ZOOM: f64 = 1.0;
+const MAX_ZOOM: f64 = 64.0;
+const ZOOM_STEP: f64 = 1.25;
+/// Diameter of the magnifier loupe, in widget pixels.
+const LOUPE_SIZE: f32 = 128.0;
+/// Size of one image pixel inside the loupe.
+const LOUPE_PIXEL: f32 = 12.0;
+/// Drags shorter than this count as a click.
+const CLICK_SLOP: f64 = 4.0;
+
+// Zoomable, pannable image with a magnifier loupe following the pointer
+mod imp {
+    use super::*;
+
+    pub struct ImageView {
+        pub texture: RefCell<Option<gdk::Texture>>,
+        pub pixels: RefCell<Option<ImagePixels>>,
+        pub zoom: Cell<f64>,
+        pub pan: Cell<(f64, f64)>,
+        pub pointer: Cell<Option<(f64, f64)>>,
+        pub hovered: Cell<Option<(usize, usize)>>,
+        pub sample_radius: Cell<usize>,
+        pub drag_pan: Cell<(f64, f64)>,
+        pub pinch_zoom: Cell<f64>,
+    }
+
+    impl Default for ImageView {
+        fn default() -> Self {
+            Self {
+                texture: RefCell::default(),
+                pixels: RefCell::default(),
+                zoom: Cell::new(MIN_ZOOM),
+                pan: Cell::default(),
+                pointer: Cell::default(),
+                hovered: Cell::default(),
+                sample_radius: Cell::default(),
+                drag_pan: Cell::default(),
+                pinch_zoom: Cell::new(MIN_ZOOM),
+            }
+        }
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for ImageView {
+        const NAME: &'static str = "LonImageView";
+        type Type = super::ImageView;
+        type ParentType = gtk::Widget;
+    }
+
+    impl ObjectImpl for ImageView {
+        fn constructed(&self) {
+            self.parent_constructed();
+            let obj = self.obj();
+            obj.set_overflow(gtk::Overflow::Hidden);
+            obj.set_cursor_from_name(Some("crosshair"));
+            obj.setup_controllers();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("hover-changed").build(),
+                    Signal::builder("pixel-clicked").build(),
+                ]
+            })
+        }
+    }
+
+    impl WidgetImpl for ImageView {
+        fn snapshot(&self, snapshot: &gtk::Snapshot) {
+            let widget = self.obj();
+            let Some(texture) = self.texture.borrow().clone() else {
+                return;
+            };
+            let width = widget.width() as f32;
+            let height = widget.height() as f32;
+            if width <= 0.0 || height <= 0.0 {
+                return;
+            }
+
+            let scale = widget.scale();
+            let (ox, oy) = widget.origin();
+            let filter = if scale >= 4.0 {
+                gsk::ScalingFilter::Nearest
+            } else if scale < 1.0 {
+                gsk::ScalingFilter::Trilinear
+            } else {
+                gsk::ScalingFilter::Linear
+            };
+            let bounds = graphene::Rect::new(
+                ox as f32,
+                oy as f32,
+                (texture.width() as f64 * scale) as f32,
+                (texture.height() as f64 * scale) as f32,
+            );
+            snapshot.push_clip(&graphene::Rect::new(0.0, 0.0, width, height));
+            snapshot.append_scaled_texture(&texture, filter, &bounds);
+            snapshot.pop();
+
+            if let (Some((px, py)), Some((hx, hy))) = (self.pointer.get(), self.hovered.get()) {
+                self.snapshot_loupe(snapshot, &texture, (px as f32, py as f32), (hx, hy));
+            }
+        }
+    }
+
+    impl ImageView {
+        fn snapshot_loupe(
+            &self,
+            snapshot: &gtk::Snapshot,
+            texture: &gdk::Texture,
+            pointer: (f32, f32),
+            pixel: (usize, usize),
+        ) {
+            let widget = self.obj();
+            let width = widget.width() as f32;
+            let height = widget.height() as f32;
+            let radius = LOUPE_SIZE / 2.0;
+
+            // Float above and to the right of the pointer, flipping at the edges
+            let offset = radius + 16.0;
+            let mut cx = pointer.0 + offset;
+            if cx + radius > width {
+                cx = pointer.0 - offset;
+            }
+            let mut cy = pointer.1 - offset;
+            if cy - radius < 0.0 {
+                cy = (pointer.1 + offset).min(height - radius);
+            }
+
+            let rect = graphene::Rect::new(cx - radius, cy - radius, LOUPE_SIZE, LOUPE_SIZE);
+            let circle = gsk::RoundedRect::from_rect(rect, radius);
+            snapshot.push_rounded_clip(&circle);
+            snapshot.append_color(&gdk::RGBA::new(0.5, 0.5, 0.5, 1.0), &rect);
+            let magnified = graphene::Rect::new(
+                cx - (pixel.0 as f32 + 0.5) * LOUPE_PIXEL,
+                cy - (pixel.1 as f32 + 0.5) * LOUPE_PIXEL,
+                texture.width() as f32 * LOUPE_PIXEL,
+                texture.height() as f32 * LOUPE_PIXEL,
+            );
+            snapshot.append_scaled_texture(texture, gsk::ScalingFilter::Nearest, &magnified);
+
+            // Outline the pixels that make up the sample
+            let side = (self.sample_radius.get() * 2 + 1) as f32 * LOUPE_PIXEL;
+            let sample = graphene::Rect::new(cx - side / 2.0, cy - side / 2.0, side, side);
+            let outer = graphene::Rect::new(
+                sample.x() - 1.0,
+                sample.y() - 1.0,
+                sample.width() + 2.0,
+                sample.height() + 2.0,
+            );
+            snapshot.append_border(
+                &gsk::RoundedRect::from_rect(outer, 0.0),
+                &[1.0; 4],
+                &[gdk::RGBA::new(0.0, 0.0, 0.0, 0.8); 4],
+            );
+            snapshot.append_border(
+                &gsk::RoundedRect::from_rect(sample, 0.0),
+                &[1.0; 4],
+                &[gdk::RGBA::new(1.0, 1.0, 1.0, 0.9); 4],
+            );
+            snapshot.pop();
+
+            snapshot.append_border(&circle, &[3.0; 4], &[gdk::RGBA::new(1.0, 1.0, 1.0, 0.9); 4]);
+        }
+    }
+}
+
+glib::wrapper! {
+    pub struct ImageView(ObjectSubclass<imp::ImageView>)
+        @extends gtk::Widget;
+}
+
+impl Default for ImageView {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ImageView {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    pub fn set_texture(&self, texture: &gdk::Texture) {
+        let imp = self.imp();
+        imp.pixels.replace(Some(ImagePixels::from_texture(texture)));
+        imp.texture.replace(Some(texture.clone()));
+        self.zoom_to_fit();
+    }
+
+    /// Average clicks over a `(2 * radius + 1)` square of pixels.
+    pub fn set_sample_radius(&self, radius: usize) {
+        self.imp().sample_radius.set(radius);
+        self.emit_by_name::<()>("hover-changed", &[]);
+        self.queue_draw();
+    }
+
+    /// The (averaged) colour under the pointer, if it is over the image.
+    pub fn hovered_color(&self) -> Option<Rgb> {
+        let imp = self.imp();
+        let (x, y) = imp.hovered.get()?;
+        imp.pixels
+            .borrow()
+            .as_ref()?
+            .average(x, y, imp.sample_radius.get())
+    }
+
+    pub fn zoom_in(&self) {
+        self.zoom_to(self.imp().zoom.get() * ZOOM_STEP, None);
+    }
+
+    pub fn zoom_out(&self) {
+        self.zoom_to(self.imp().zoom.get() / ZOOM_STEP, None);
+    }
+
+    pub fn zoom_to_fit(&self) {
+        let imp = self.imp();
+        imp.zoom.set(MIN_ZOOM);
+        imp.pan.set((0.0, 0.0));
+        self.update_hovered();
+        self.queue_draw();
+    }
+
+    /// Zoom keeping the image point under `anchor` (widget coordinates, or the
+    /// centre) where it is.
+    fn zoom_to(&self, zoom: f64, anchor: Option<(f64, f64)>) {
+        let imp = self.imp();
+        let zoom = zoom.clamp(MIN_ZOOM, MAX_ZOOM);
+        let (ax, ay) = anchor.unwrap_or((self.width() as f64 / 2.0, self.height() as f64 / 2.0));
+        let old_scale = self.scale();
+        let (ox, oy) = self.origin();
+        let (u, v) = ((ax - ox) / old_scale, (ay - oy) / old_scale);
+
+        imp.zoom.set(zoom);
+        let scale = self.scale();
+        let (tw, th) = self.texture_size();
+        imp.pan.set((
+            ax - u * scale - (self.width() as f64 - tw * scale) / 2.0,
+            ay - v * scale - (self.height() as f64 - th * scale) / 2.0,
+        ));
+        self.clamp_pan();
+        self.update_hovered();
+        self.queue_draw();
+    }
+
+    fn texture_size(&self) -> (f64, f64) {
+        self.imp()
+            .texture
+            .borrow()
+            .as_ref()
+            .map(|t| (t.width() as f64, t.height() as f64))
+            .unwrap_or((1.0, 1.0))
+    }
+
+    /// Widget pixels per image pixel.
+    fn scale(&self) -> f64 {
+        let (tw, th) = self.texture_size();
+        let fit = (self.width() as f64 / tw).min(self.height() as f64 / th);
+        fit.max(f64::EPSILON) * self.imp().zoom.get()
+    }
+
+    /// Top-left corner of the image in widget coordinates.
+    fn origin(&self) -> (f64, f64) {
+        let (tw, th) = self.texture_size();
+        let scale = self.scale();
+        let (pan_x, pan_y) = self.imp().pan.get();
+        (
+            (self.width() as f64 - tw * scale) / 2.0 + pan_x,
+            (self.height() as f64 - th * scale) / 2.0 + pan_y,
+        )
+    }
+
+    /// Keep at least the image centre inside the view.
+    fn clamp_pan(&self) {
+        let imp = self.imp();
+        let (tw, th) = self.texture_size();
+        let scale = self.scale();
+        let max_x = (tw * scale / 2.0).max(0.0);
+        let max_y = (th * scale / 2.0).max(0.0);
+        let (x, y) = imp.pan.get();
+        imp.pan
+            .set((x.clamp(-max_x, max_x), y.clamp(-max_y, max_y)));
+    }
+
+    fn image_position(&self, x: f64, y: f64) -> Option<(usize, usize)> {
+        let (tw, th) = self.texture_size();
+        let scale = self.scale();
+        let (ox, oy) = self.origin();
+        let (u, v) = ((x - ox) / scale, (y - oy) / scale);
+        (u >= 0.0 && v >= 0.0 && u < tw && v < th).then_some((u as usize, v as usize))
+    }
+
+    fn update_hovered(&self) {
+        let imp = self.imp();
+        let hovered = imp
+            .pointer
+            .get()
+            .and_then(|(x, y)| self.image_position(x, y));
+        if imp.hovered.replace(hovered) != hovered {
+            self.emit_by_name::<()>("hover-changed", &[]);
+        }
+    }
+
+    fn set_pointer(&self, pointer: Option<(f64, f64)>) {
+        self.imp().pointer.set(pointer);
+        self.update_hovered();
+        self.queue_draw();
+    }
+
+    fn setup_controllers(&self) {
+        let motion = gtk::EventControllerMotion::new();
+        motion.connect_motion(glib::clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_, x, y| view.set_pointer(Some((x, y)))
+        ));
+        motion.connect_leave(glib::clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_| view.set_pointer(None)
+        ));
+        self.add_controller(motion);
+
+        // Dragging pans; a press without movement samples the pixel
+        let drag = gtk::GestureDrag::new();
+        drag.connect_drag_begin(glib::clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_, x, y| {
+                view.imp().drag_pan.set(view.imp().pan.get());
+                view.set_pointer(Some((x, y)));
+            }
+        ));
+        drag.connect_drag_update(glib::clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |gesture, dx, dy| {
+                if dx.hypot(dy) < CLICK_SLOP {
+                    return;
+                }
+                let imp = view.imp();
+                let (x, y) = imp.drag_pan.get();
+                imp.pan.set((x + dx, y + dy));
+                view.clamp_pan();
+                if let Some((sx, sy)) = gesture.start_point() {
+                    view.set_pointer(Some((sx + dx, sy + dy)));
+                }
+            }
+        ));
+        drag.connect_drag_end(glib::clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_, dx, dy| {
+                if dx.hypot(dy) < CLICK_SLOP && view.imp().hovered.get().is_some() {
+                    view.emit_by_name::<()>("pixel-clicked", &[]);
+                }
+            }
+        ));
+        self.add_controller(drag);
+
+        let scroll = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        scroll.connect_scroll(glib::clone!(
+            #[weak(rename_to = view)]
+            self,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, _, dy| {
+                let zoom = view.imp().zoom.get() * ZOOM_STEP.powf(-dy);
+                view.zoom_to(zoom, view.imp().pointer.get());
+                glib::Propagation::Stop
+            }
+        ));
+        self.add_controller(scroll);
+
+        let pinch = gtk::GestureZoom::new();
+        pinch.connect_begin(glib::clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |_, _| view.imp().pinch_zoom.set(view.imp().zoom.get())
+        ));
+        pinch.connect_scale_changed(glib::clone!(
+            #[weak(rename_to = view)]
+            self,
+            move |gesture, scale| {
+                let anchor = gesture.bounding_box_center();
+                view.zoom_to(view.imp().pinch_zoom.get() * scale, anchor);
+            }
+        ));
+        self.add_controller(pinch);
+    }
+
+    pub fn connect_hover_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "hover-changed",
+            false,
+            glib::closure_local!(move |view: &Self| f(view)),
+        )
+    }
+
+    pub fn connect_pixel_clicked<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "pixel-clicked",
+            false,
+            glib::closure_local!(move |view: &Self| f(view)),
+        )
+    }
+}
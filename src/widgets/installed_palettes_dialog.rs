@@ -0,0 +1,139 @@
+use crate::color::SwatchFormat;
+use crate::storage;
+use crate::widgets::ColorPreview;
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib;
+use std::fs;
+use std::path::Path;
+
+/// Colours previewed beside each palette.
+const PREVIEW_SWATCHES: usize = 5;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct InstalledPalettesDialog {}
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for InstalledPalettesDialog {
+        const NAME: &'static str = "LonInstalledPalettesDialog";
+        type Type = super::InstalledPalettesDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for InstalledPalettesDialog {}
+    impl WidgetImpl for InstalledPalettesDialog {}
+    impl AdwDialogImpl for InstalledPalettesDialog {}
+}
+
+glib::wrapper! {
+    pub struct InstalledPalettesDialog(ObjectSubclass<imp::InstalledPalettesDialog>)
+        @extends adw::Dialog, gtk::Widget;
+}
+
+impl InstalledPalettesDialog {
+    /// Lists the GIMP and Inkscape palettes on this computer. Choosing one
+    /// activates `win.import-installed` with its path.
+    pub fn new() -> Self {
+        let dialog: Self = glib::Object::builder()
+            .property("title", "Installed Palettes")
+            .property("content-width", 420)
+            .property("content-height", 560)
+            .build();
+        dialog.setup();
+        dialog
+    }
+
+    fn setup(&self) {
+        let page = adw::PreferencesPage::new();
+        let mut found = false;
+        for (label, dir) in storage::installed_palette_dirs() {
+            let Ok(entries) = fs::read_dir(&dir) else {
+                continue;
+            };
+            let mut paths: Vec<_> = entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.file_name()
+                        .and_then(|name| SwatchFormat::from_filename(&name.to_string_lossy()))
+                        == Some(SwatchFormat::Gpl)
+                })
+                .collect();
+            if paths.is_empty() {
+                continue;
+            }
+            paths.sort();
+
+            let group = adw::PreferencesGroup::builder()
+                .title(label.as_str())
+                .description(glib::markup_escape_text(&dir.to_string_lossy()))
+                .build();
+            for path in paths {
+                if let Some(row) = self.palette_row(&path) {
+                    group.add(&row);
+                }
+            }
+            page.add(&group);
+            found = true;
+        }
+
+        let toolbar = adw::ToolbarView::new();
+        toolbar.add_top_bar(&adw::HeaderBar::new());
+        if found {
+            toolbar.set_content(Some(&page));
+        } else {
+            let status = adw::StatusPage::builder()
+                .icon_name("color-select-symbolic")
+                .title("No Installed Palettes")
+                .description("Palettes saved in GIMP’s or Inkscape’s palettes folder appear here")
+                .build();
+            toolbar.set_content(Some(&status));
+        }
+        self.set_child(Some(&toolbar));
+    }
+
+    /// A row for the palette at `path`, or `None` if it cannot be read.
+    fn palette_row(&self, path: &Path) -> Option<adw::ActionRow> {
+        let stem = path.file_stem()?.to_string_lossy().into_owned();
+        let bytes = fs::read(path).ok()?;
+        let palette = SwatchFormat::Gpl.read(&bytes, &stem).ok()?.pop()?;
+
+        let count = palette.entries.len();
+        let row = adw::ActionRow::builder()
+            .title(glib::markup_escape_text(&palette.name))
+            .subtitle(if count == 1 {
+                "1 colour".to_string()
+            } else {
+                format!("{} colours", count)
+            })
+            .activatable(true)
+            .build();
+        row.set_action_name(Some("win.import-installed"));
+        row.set_action_target_value(Some(&path.to_string_lossy().to_variant()));
+
+        let swatches = gtk::Box::new(gtk::Orientation::Horizontal, 2);
+        swatches.set_valign(gtk::Align::Center);
+        for entry in palette.entries.iter().take(PREVIEW_SWATCHES) {
+            let swatch = ColorPreview::new(entry.color.rgb);
+            swatch.set_size_request(16, 24);
+            swatches.append(&swatch);
+        }
+        row.add_suffix(&swatches);
+        row.connect_activated(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| {
+                dialog.close();
+            }
+        ));
+        Some(row)
+    }
+}
+
+impl Default for InstalledPalettesDialog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
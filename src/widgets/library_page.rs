@@ -0,0 +1,1097 @@
+use crate::color::{
+    AnnotationKey, ColorFamily, ColorKey, ColorLibrary, Grouping, PantoneColor, SortOrder, TagQuery,
+};
+use crate::widgets::{
+    add_to_palette_menu, ColorObject, ColorSwatch, SectionIndex, SwatchLabel, DEFAULT_SWATCH_SIZE,
+    MAX_SWATCH_SIZE, MIN_SWATCH_SIZE,
+};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib::subclass::Signal;
+use gtk::{gdk, gio, glib, graphene};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+/// Pixels added or removed per zoom step.
+pub const SWATCH_SIZE_STEP: i32 = 8;
+
+/// Wheel steps of scrolling past either end before wrap-around jumps to the
+/// other, so a bounce at the end does not wrap by accident.
+const WRAP_OVERSCROLL: f64 = 3.0;
+/// Touchpad and touch travel, in pixels, counted as one wheel step.
+const OVERSCROLL_STEP: f64 = 48.0;
+
+/// Narrowest and widest grids the column limits are sized for.
+const MIN_GRID_WIDTH: i32 = 360;
+const MAX_GRID_WIDTH: i32 = 1920;
+
+/// Neutral background shown around the swatches, since the surround changes
+/// how a colour is perceived.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Surround {
+    /// Whatever the current light or dark style uses.
+    #[default]
+    Window,
+    Black,
+    Grey,
+    White,
+    /// Paper white under D50 viewing light, as in ISO 12647-2.
+    Paper,
+}
+
+impl Surround {
+    pub fn id(&self) -> &'static str {
+        match self {
+            Self::Window => "window",
+            Self::Black => "black",
+            Self::Grey => "grey",
+            Self::White => "white",
+            Self::Paper => "paper",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|surround| surround.id() == id)
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Window => "Match Style",
+            Self::Black => "Black",
+            Self::Grey => "Mid Grey",
+            Self::White => "White",
+            Self::Paper => "D50 Paper",
+        }
+    }
+
+    pub fn all() -> &'static [Surround] {
+        &[
+            Self::Window,
+            Self::Black,
+            Self::Grey,
+            Self::White,
+            Self::Paper,
+        ]
+    }
+
+    fn css_class(&self) -> String {
+        format!("surround-{}", self.id())
+    }
+}
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct LibraryPage {
+        /// `None` for pages that mix libraries, such as favourites.
+        pub library: Cell<Option<ColorLibrary>>,
+        pub title: RefCell<String>,
+        pub store: OnceCell<gio::ListStore>,
+        pub starred: RefCell<HashSet<ColorKey>>,
+        pub palette_names: RefCell<Vec<String>>,
+        pub empty_status: OnceCell<adw::StatusPage>,
+        pub families: RefCell<HashSet<ColorFamily>>,
+        pub search: RefCell<String>,
+        pub query: RefCell<TagQuery>,
+        pub tags: RefCell<HashMap<AnnotationKey, Vec<String>>>,
+        pub grouping: Cell<Grouping>,
+        pub sort_order: Cell<SortOrder>,
+        pub filter: OnceCell<gtk::CustomFilter>,
+        pub sorter: OnceCell<gtk::CustomSorter>,
+        pub model: OnceCell<gtk::SortListModel>,
+        pub wrap_around: Cell<bool>,
+        /// Wheel steps scrolled past the bottom (positive) or top (negative).
+        pub overscroll: Cell<f64>,
+        /// Touch drag offset at the last update, to turn into overscroll.
+        pub drag_offset: Cell<f64>,
+        pub swatch_size: Cell<i32>,
+        pub swatch_label: Cell<SwatchLabel>,
+        pub surround: Cell<Surround>,
+        /// Ctrl+scroll travel not yet turned into a zoom step.
+        pub scroll_delta: Cell<f64>,
+        pub grid_view: OnceCell<gtk::GridView>,
+        pub scrolled: OnceCell<gtk::ScrolledWindow>,
+        pub header: OnceCell<gtk::Label>,
+        /// How many colours each section holds, for the header's count.
+        pub section_sizes: RefCell<HashMap<u32, u32>>,
+        pub header_queued: Cell<bool>,
+        pub index: OnceCell<SectionIndex>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for LibraryPage {
+        const NAME: &'static str = "LonLibraryPage";
+        type Type = super::LibraryPage;
+        type ParentType = adw::Bin;
+    }
+
+    impl ObjectImpl for LibraryPage {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("color-activated")
+                        .param_types([ColorObject::static_type()])
+                        .build(),
+                    Signal::builder("swatch-size-changed")
+                        .param_types([i32::static_type()])
+                        .build(),
+                ]
+            })
+        }
+    }
+
+    impl WidgetImpl for LibraryPage {}
+    impl BinImpl for LibraryPage {}
+}
+
+glib::wrapper! {
+    pub struct LibraryPage(ObjectSubclass<imp::LibraryPage>)
+        @extends adw::Bin, gtk::Widget;
+}
+
+impl LibraryPage {
+    pub fn new(library: ColorLibrary, colors: &[PantoneColor]) -> Self {
+        let page = Self::with_title(library.short_name(), colors);
+        page.imp().library.set(Some(library));
+        page
+    }
+
+    /// Starred colours from every library; filled in with `set_colors`.
+    pub fn favourites() -> Self {
+        let page = Self::with_title("Favourites", &[]);
+        if let Some(status) = page.imp().empty_status.get() {
+            status.set_icon_name(Some("starred-symbolic"));
+            status.set_title("No Favourites");
+            status.set_description(Some(
+                "Star colours from the detail panel or a swatch’s menu",
+            ));
+        }
+        page
+    }
+
+    /// The user's own colours; filled in with `set_colors`.
+    pub fn custom() -> Self {
+        let page = Self::new(ColorLibrary::Custom, &[]);
+        if let Some(status) = page.imp().empty_status.get() {
+            status.set_icon_name(Some("applications-graphics-symbolic"));
+            status.set_title("No Custom Colours");
+            status.set_description(Some("Add colours that are not in a Pantone library"));
+            let add_button = gtk::Button::with_label("Add Colour");
+            add_button.add_css_class("pill");
+            add_button.add_css_class("suggested-action");
+            add_button.set_halign(gtk::Align::Center);
+            add_button.set_action_name(Some("win.add-custom-color"));
+            status.set_child(Some(&add_button));
+        }
+        page
+    }
+
+    fn with_title(title: &str, colors: &[PantoneColor]) -> Self {
+        let page: Self = glib::Object::new();
+        page.imp().title.replace(title.to_string());
+        page.imp().sort_order.set(SortOrder::Hue);
+        page.imp().wrap_around.set(true);
+        page.imp().swatch_size.set(DEFAULT_SWATCH_SIZE);
+        page.setup(colors);
+        page
+    }
+
+    fn setup(&self, colors: &[PantoneColor]) {
+        let imp = self.imp();
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.add_css_class("no-scrollbar");
+        scrolled.set_margin_bottom(1);
+        scrolled.set_hexpand(true);
+        scrolled.set_vexpand(true);
+
+        // Create and populate base model
+        let base_model = gio::ListStore::new::<ColorObject>();
+        for color in colors {
+            base_model.append(&ColorObject::new(color.clone()));
+        }
+
+        // Filter by the families selected in the chips row and the search text
+        let filter = gtk::CustomFilter::new(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[upgrade_or]
+            true,
+            move |item| {
+                let Some(color) = item.downcast_ref::<ColorObject>().map(|obj| obj.color()) else {
+                    return false;
+                };
+                let imp = page.imp();
+                let families = imp.families.borrow();
+                let tags = imp.tags.borrow();
+                let color_tags = tags
+                    .get(&AnnotationKey::for_color(&color))
+                    .map(|tags| tags.as_slice())
+                    .unwrap_or(&[]);
+                (families.is_empty() || families.contains(&color.family))
+                    && imp.query.borrow().matches(&color, color_tags)
+            }
+        ));
+        let filter_model =
+            gtk::FilterListModel::new(Some(base_model.clone()), Some(filter.clone()));
+
+        // Sort within sections, and split into sections by the grouping
+        let sorter = gtk::CustomSorter::new(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[upgrade_or]
+            gtk::Ordering::Equal,
+            move |a, b| {
+                let (Some(a), Some(b)) = (
+                    a.downcast_ref::<ColorObject>(),
+                    b.downcast_ref::<ColorObject>(),
+                ) else {
+                    return gtk::Ordering::Equal;
+                };
+                let grouping = page.imp().grouping.get();
+                let sort = page.imp().sort_order.get();
+                grouping.compare(&a.color(), &b.color(), sort).into()
+            }
+        ));
+        let section_sorter = gtk::CustomSorter::new(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[upgrade_or]
+            gtk::Ordering::Equal,
+            move |a, b| {
+                let (Some(a), Some(b)) = (
+                    a.downcast_ref::<ColorObject>(),
+                    b.downcast_ref::<ColorObject>(),
+                ) else {
+                    return gtk::Ordering::Equal;
+                };
+                let grouping = page.imp().grouping.get();
+                let sort = page.imp().sort_order.get();
+                grouping
+                    .section(&a.color(), sort)
+                    .cmp(&grouping.section(&b.color(), sort))
+                    .into()
+            }
+        ));
+        let sort_model = gtk::SortListModel::new(Some(filter_model), Some(sorter.clone()));
+        sort_model.set_section_sorter(Some(&section_sorter));
+
+        // Create GridView
+        let factory = gtk::SignalListItemFactory::new();
+
+        factory.connect_setup(|_, list_item| {
+            let list_item = list_item.downcast_ref::<gtk::ListItem>().unwrap();
+            let swatch = ColorSwatch::new();
+            // Let the swatch itself take keyboard focus so it can draw the focus ring
+            list_item.set_focusable(false);
+            list_item.set_child(Some(&swatch));
+        });
+
+        factory.connect_bind(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_, list_item| {
+                let list_item = list_item.downcast_ref::<gtk::ListItem>().unwrap();
+                let color_obj = list_item
+                    .item()
+                    .and_downcast::<ColorObject>()
+                    .expect("Item must be ColorObject");
+                let swatch = list_item
+                    .child()
+                    .and_downcast::<ColorSwatch>()
+                    .expect("Child must be ColorSwatch");
+                swatch.set_size(page.imp().swatch_size.get());
+                swatch.set_label(page.imp().swatch_label.get());
+                swatch.set_starred(
+                    page.imp()
+                        .starred
+                        .borrow()
+                        .contains(&color_obj.color().key()),
+                );
+                swatch.set_color(&color_obj.color());
+                swatch.set_tooltip_text(Some(&color_obj.color().name));
+            }
+        ));
+
+        let selection = gtk::SingleSelection::new(Some(sort_model.clone()));
+        let grid_view = gtk::GridView::new(Some(selection), Some(factory));
+        let (min_columns, max_columns) = columns_for_size(DEFAULT_SWATCH_SIZE);
+        grid_view.set_min_columns(min_columns);
+        grid_view.set_max_columns(max_columns);
+        grid_view.set_enable_rubberband(false);
+        grid_view.set_single_click_activate(true);
+
+        grid_view.connect_activate(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |grid, position| {
+                let model = grid.model().unwrap();
+                if let Some(item) = model.item(position) {
+                    let color_obj = item.downcast::<ColorObject>().unwrap();
+                    page.emit_by_name::<()>("color-activated", &[&color_obj]);
+                }
+            }
+        ));
+
+        // Enter/Space open the focused swatch. Handled in the capture phase so the
+        // list item's own Space binding (selection) does not swallow it.
+        let shortcuts = gtk::ShortcutController::new();
+        shortcuts.set_propagation_phase(gtk::PropagationPhase::Capture);
+        let activate = gtk::CallbackAction::new(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, _| {
+                let Some(swatch) = page
+                    .root()
+                    .and_then(|root| root.focus())
+                    .and_downcast::<ColorSwatch>()
+                else {
+                    return glib::Propagation::Proceed;
+                };
+                let Some(color) = swatch.color() else {
+                    return glib::Propagation::Proceed;
+                };
+                page.emit_by_name::<()>("color-activated", &[&ColorObject::new(color)]);
+                glib::Propagation::Stop
+            }
+        ));
+        shortcuts.add_shortcut(gtk::Shortcut::new(
+            gtk::ShortcutTrigger::parse_string("Return|KP_Enter|space"),
+            Some(activate),
+        ));
+        grid_view.add_controller(shortcuts);
+
+        scrolled.set_child(Some(&grid_view));
+        self.setup_zoom(&scrolled);
+        self.setup_wrap_around(&scrolled, &grid_view);
+        self.setup_context_menu(&grid_view);
+
+        // Floating header naming the section at the top of the viewport
+        let header = gtk::Label::new(None);
+        header.add_css_class("section-header");
+        header.add_css_class("caption-heading");
+        header.set_halign(gtk::Align::Start);
+        header.set_valign(gtk::Align::Start);
+        header.set_margin_start(12);
+        header.set_margin_top(8);
+        header.set_can_target(false);
+
+        scrolled.vadjustment().connect_value_changed(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_| page.queue_header_update()
+        ));
+
+        // Fast-scroll index along the edge
+        let index = SectionIndex::new();
+        index.set_halign(gtk::Align::End);
+        index.set_margin_end(4);
+        index.connect_jump(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_, start| page.jump_to_section(start)
+        ));
+
+        let empty_status = adw::StatusPage::builder()
+            .icon_name("edit-find-symbolic")
+            .title("No Matches")
+            .visible(false)
+            .build();
+        sort_model.connect_items_changed(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_, _, _, _| page.update_empty_status()
+        ));
+
+        let overlay = gtk::Overlay::new();
+        overlay.set_child(Some(&scrolled));
+        overlay.add_overlay(&empty_status);
+        overlay.add_overlay(&header);
+        overlay.add_overlay(&index);
+        self.set_child(Some(&overlay));
+
+        let _ = imp.filter.set(filter);
+        let _ = imp.sorter.set(sorter);
+        let _ = imp.model.set(sort_model);
+        let _ = imp.grid_view.set(grid_view);
+        let _ = imp.scrolled.set(scrolled);
+        let _ = imp.header.set(header);
+        let _ = imp.index.set(index);
+        let _ = imp.store.set(base_model);
+        let _ = imp.empty_status.set(empty_status);
+
+        self.update_sections();
+        self.update_empty_status();
+    }
+
+    pub fn library(&self) -> Option<ColorLibrary> {
+        self.imp().library.get()
+    }
+
+    pub fn title(&self) -> String {
+        self.imp().title.borrow().clone()
+    }
+
+    /// Replace every colour on the page.
+    pub fn set_colors(&self, colors: &[PantoneColor]) {
+        let Some(store) = self.imp().store.get() else {
+            return;
+        };
+        let objects: Vec<ColorObject> = colors
+            .iter()
+            .map(|color| ColorObject::new(color.clone()))
+            .collect();
+        store.splice(0, store.n_items(), &objects);
+        self.update_sections();
+    }
+
+    /// Every colour on the page, ignoring the search and filters.
+    pub fn colors(&self) -> Vec<PantoneColor> {
+        let Some(store) = self.imp().store.get() else {
+            return Vec::new();
+        };
+        store
+            .iter::<ColorObject>()
+            .filter_map(Result::ok)
+            .map(|object| object.color())
+            .collect()
+    }
+
+    /// The colours left by the search and filters, in display order.
+    pub fn visible_colors(&self) -> Vec<PantoneColor> {
+        let Some(model) = self.imp().model.get() else {
+            return Vec::new();
+        };
+        (0..model.n_items())
+            .filter_map(|position| model.item(position).and_downcast::<ColorObject>())
+            .map(|object| object.color())
+            .collect()
+    }
+
+    /// How many colours on the page fall in each family, ignoring filters.
+    pub fn family_counts(&self) -> HashMap<ColorFamily, usize> {
+        let mut counts = HashMap::new();
+        if let Some(store) = self.imp().store.get() {
+            for obj in store.iter::<ColorObject>().flatten() {
+                *counts.entry(obj.color().family).or_insert(0) += 1;
+            }
+        }
+        counts
+    }
+
+    /// Mark the swatches of starred colours.
+    pub fn set_starred(&self, starred: HashSet<ColorKey>) {
+        self.imp().starred.replace(starred);
+        let starred = self.imp().starred.borrow();
+        self.for_each_swatch(|swatch| {
+            let key = swatch.color().map(|color| color.key());
+            swatch.set_starred(key.is_some_and(|key| starred.contains(&key)));
+        });
+    }
+
+    /// Palettes offered in the swatch menu's "Add to Palette".
+    pub fn set_palette_names(&self, names: Vec<String>) {
+        self.imp().palette_names.replace(names);
+    }
+
+    fn update_empty_status(&self) {
+        let imp = self.imp();
+        let (Some(status), Some(model)) = (imp.empty_status.get(), imp.model.get()) else {
+            return;
+        };
+        status.set_visible(model.n_items() == 0);
+    }
+
+    pub fn set_families(&self, families: HashSet<ColorFamily>) {
+        self.imp().families.replace(families);
+        self.refilter();
+        self.scroll_to_top();
+    }
+
+    pub fn set_search(&self, query: &str) {
+        if *self.imp().search.borrow() == query {
+            return;
+        }
+        self.imp().search.replace(query.to_string());
+        self.imp().query.replace(TagQuery::parse(query));
+        self.refilter();
+        self.scroll_to_top();
+    }
+
+    /// The user's tags on each colour, for `tag:` searches. Only a search
+    /// for tags needs filtering again, and it keeps the scroll position, since
+    /// tags change while the user annotates a colour.
+    pub fn set_tags(&self, tags: HashMap<AnnotationKey, Vec<String>>) {
+        self.imp().tags.replace(tags);
+        if !self.imp().query.borrow().tags.is_empty() {
+            self.refilter();
+        }
+    }
+
+    fn refilter(&self) {
+        if let Some(filter) = self.imp().filter.get() {
+            filter.changed(gtk::FilterChange::Different);
+        }
+        self.update_sections();
+    }
+
+    pub fn set_grouping(&self, grouping: Grouping) {
+        self.imp().grouping.set(grouping);
+        self.resort();
+    }
+
+    pub fn set_sort_order(&self, sort_order: SortOrder) {
+        self.imp().sort_order.set(sort_order);
+        self.resort();
+    }
+
+    fn resort(&self) {
+        if let Some(sorter) = self.imp().sorter.get() {
+            sorter.changed(gtk::SorterChange::Different);
+        }
+        self.update_sections();
+        self.scroll_to_top();
+    }
+
+    /// Start from the top after the search, filters or order changed.
+    fn scroll_to_top(&self) {
+        let imp = self.imp();
+        if let (Some(grid), Some(model)) = (imp.grid_view.get(), imp.model.get()) {
+            if model.n_items() > 0 {
+                grid.scroll_to(0, gtk::ListScrollFlags::NONE, None);
+            }
+        }
+    }
+
+    /// The swatch in the top-left corner of the viewport, found from where
+    /// the grid has laid out its rows rather than estimated from the offset,
+    /// since rows vary in height across sections.
+    fn top_swatch(&self) -> Option<ColorSwatch> {
+        let grid_view = self.imp().grid_view.get()?;
+        let mut top: Option<(f32, f32, ColorSwatch)> = None;
+        let mut child = grid_view.first_child();
+        while let Some(item) = child {
+            child = item.next_sibling();
+            let Some(swatch) = item.first_child().and_downcast::<ColorSwatch>() else {
+                continue;
+            };
+            let Some(bounds) = item.compute_bounds(grid_view) else {
+                continue;
+            };
+            // Skip rows scrolled out above the viewport
+            if !item.is_child_visible() || bounds.y() + bounds.height() <= 0.0 {
+                continue;
+            }
+            let corner = (bounds.y(), bounds.x());
+            if top.as_ref().is_none_or(|(y, x, _)| corner < (*y, *x)) {
+                top = Some((corner.0, corner.1, swatch));
+            }
+        }
+        top.map(|(_, _, swatch)| swatch)
+    }
+
+    fn update_sections(&self) {
+        let imp = self.imp();
+        let Some(model) = imp.model.get() else {
+            return;
+        };
+
+        let grouping = imp.grouping.get();
+        let sort = imp.sort_order.get();
+        let mut entries = Vec::new();
+        let mut sizes = HashMap::new();
+        let mut position = 0;
+        while position < model.n_items() {
+            let (start, end) = model.section(position);
+            if let Some(obj) = model.item(start).and_downcast::<ColorObject>() {
+                let section = grouping.section(&obj.color(), sort);
+                entries.push((grouping.section_label(section, sort), start));
+                sizes.insert(section, end - start);
+            }
+            position = end.max(position + 1);
+        }
+        if let Some(index) = imp.index.get() {
+            index.set_sections(entries);
+        }
+        imp.section_sizes.replace(sizes);
+        self.queue_header_update();
+    }
+
+    /// Update the header once the grid has laid out its rows for the new
+    /// scroll offset or model.
+    fn queue_header_update(&self) {
+        if self.imp().header_queued.replace(true) {
+            return;
+        }
+        glib::idle_add_local_once(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move || {
+                page.imp().header_queued.set(false);
+                page.update_header();
+            }
+        ));
+    }
+
+    fn update_header(&self) {
+        let imp = self.imp();
+        let Some(header) = imp.header.get() else {
+            return;
+        };
+        let Some(color) = self.top_swatch().and_then(|swatch| swatch.color()) else {
+            header.set_visible(false);
+            return;
+        };
+
+        let grouping = imp.grouping.get();
+        let sort = imp.sort_order.get();
+        let section = grouping.section(&color, sort);
+        let count = imp
+            .section_sizes
+            .borrow()
+            .get(&section)
+            .copied()
+            .unwrap_or(0);
+        header.set_label(&format!(
+            "{} · {}",
+            grouping.section_title(section, sort),
+            count
+        ));
+        header.set_visible(true);
+    }
+
+    fn jump_to_section(&self, start: u32) {
+        if let Some(grid) = self.imp().grid_view.get() {
+            grid.scroll_to(start, gtk::ListScrollFlags::NONE, None);
+        }
+    }
+
+    /// Right-click or long-press a swatch for a menu of actions on its colour.
+    fn setup_context_menu(&self, grid_view: &gtk::GridView) {
+        let context_click = gtk::GestureClick::new();
+        context_click.set_button(gdk::BUTTON_SECONDARY);
+        context_click.connect_pressed(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |gesture, _, x, y| {
+                if page.show_context_menu(x, y) {
+                    gesture.set_state(gtk::EventSequenceState::Claimed);
+                }
+            }
+        ));
+        grid_view.add_controller(context_click);
+
+        let long_press = gtk::GestureLongPress::new();
+        long_press.connect_pressed(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |gesture, x, y| {
+                if page.show_context_menu(x, y) {
+                    gesture.set_state(gtk::EventSequenceState::Claimed);
+                }
+            }
+        ));
+        grid_view.add_controller(long_press);
+    }
+
+    /// Pop up the menu for the swatch at `(x, y)` in the grid, if there is one.
+    fn show_context_menu(&self, x: f64, y: f64) -> bool {
+        let Some(grid_view) = self.imp().grid_view.get() else {
+            return false;
+        };
+        let Some(swatch) = grid_view
+            .pick(x, y, gtk::PickFlags::DEFAULT)
+            .and_then(|widget| widget.ancestor(ColorSwatch::static_type()))
+            .and_downcast::<ColorSwatch>()
+        else {
+            return false;
+        };
+        let Some(color) = swatch.color() else {
+            return false;
+        };
+
+        let key = color.key();
+        let label = if self.imp().starred.borrow().contains(&key) {
+            "Remove from Favourites"
+        } else {
+            "Add to Favourites"
+        };
+        let menu = gio::Menu::new();
+        let item = gio::MenuItem::new(Some(label), None);
+        item.set_action_and_target_value(
+            Some("win.toggle-favourite"),
+            Some(&(key.library.as_str(), key.name.as_str()).to_variant()),
+        );
+        menu.append_item(&item);
+        menu.append_submenu(
+            Some("Add to Palette"),
+            &add_to_palette_menu(&self.imp().palette_names.borrow(), &key),
+        );
+        if self.library() == Some(ColorLibrary::Custom) {
+            let custom_section = gio::Menu::new();
+            for (label, action) in [
+                ("Edit…", "win.edit-custom-color"),
+                ("Delete", "win.delete-custom-color"),
+            ] {
+                let item = gio::MenuItem::new(Some(label), None);
+                item.set_action_and_target_value(Some(action), Some(&key.name.to_variant()));
+                custom_section.append_item(&item);
+            }
+            menu.append_section(None, &custom_section);
+        }
+
+        let Some(point) =
+            grid_view.compute_point(&swatch, &graphene::Point::new(x as f32, y as f32))
+        else {
+            return false;
+        };
+        let popover = gtk::PopoverMenu::from_model(Some(&menu));
+        popover.set_parent(&swatch);
+        popover.set_has_arrow(false);
+        popover.set_halign(gtk::Align::Start);
+        popover.set_pointing_to(Some(&gdk::Rectangle::new(
+            point.x() as i32,
+            point.y() as i32,
+            1,
+            1,
+        )));
+        // Unparent once the menu item has had its chance to activate
+        popover.connect_closed(|popover| {
+            let popover = popover.clone();
+            glib::idle_add_local_once(move || popover.unparent());
+        });
+        popover.popup();
+        true
+    }
+
+    /// Ctrl+scroll and pinch resize the swatches; the new size is announced
+    /// through `swatch-size-changed`.
+    fn setup_zoom(&self, scrolled: &gtk::ScrolledWindow) {
+        // Capture phase, so the scrolled window does not scroll as well
+        let scroll = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        scroll.set_propagation_phase(gtk::PropagationPhase::Capture);
+        scroll.connect_scroll(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |controller, _, dy| {
+                if !controller
+                    .current_event_state()
+                    .contains(gdk::ModifierType::CONTROL_MASK)
+                {
+                    return glib::Propagation::Proceed;
+                }
+                // Wheels send whole steps; touchpads send fractions that add up
+                let imp = page.imp();
+                let delta = imp.scroll_delta.get() - dy;
+                let steps = delta.trunc();
+                imp.scroll_delta.set(delta - steps);
+                if steps != 0.0 {
+                    page.zoom_by(steps as i32 * SWATCH_SIZE_STEP);
+                }
+                glib::Propagation::Stop
+            }
+        ));
+        scrolled.add_controller(scroll);
+
+        let pinch = gtk::GestureZoom::new();
+        let start_size = Rc::new(Cell::new(DEFAULT_SWATCH_SIZE));
+        pinch.connect_begin(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[strong]
+            start_size,
+            move |_, _| start_size.set(page.swatch_size())
+        ));
+        pinch.connect_scale_changed(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_, scale| {
+                let size = (start_size.get() as f64 * scale).round() as i32;
+                page.zoom_by(size - page.swatch_size());
+            }
+        ));
+        scrolled.add_controller(pinch);
+    }
+
+    fn zoom_by(&self, delta: i32) {
+        let size = (self.swatch_size() + delta).clamp(MIN_SWATCH_SIZE, MAX_SWATCH_SIZE);
+        if size != self.swatch_size() {
+            self.set_swatch_size(size);
+            self.emit_by_name::<()>("swatch-size-changed", &[&size]);
+        }
+    }
+
+    pub fn swatch_size(&self) -> i32 {
+        self.imp().swatch_size.get()
+    }
+
+    /// Resize every swatch, keeping the column limits in proportion.
+    pub fn set_swatch_size(&self, size: i32) {
+        let imp = self.imp();
+        let size = size.clamp(MIN_SWATCH_SIZE, MAX_SWATCH_SIZE);
+        if imp.swatch_size.replace(size) == size {
+            return;
+        }
+        if let Some(grid_view) = imp.grid_view.get() {
+            let (min_columns, max_columns) = columns_for_size(size);
+            grid_view.set_min_columns(min_columns);
+            grid_view.set_max_columns(max_columns);
+        }
+
+        self.for_each_swatch(|swatch| swatch.set_size(size));
+    }
+
+    pub fn set_swatch_label(&self, label: SwatchLabel) {
+        if self.imp().swatch_label.replace(label) != label {
+            self.for_each_swatch(|swatch| swatch.set_label(label));
+        }
+    }
+
+    /// Bound swatches are updated in place; recycled ones pick changes up on bind.
+    fn for_each_swatch(&self, f: impl Fn(&ColorSwatch)) {
+        let Some(grid_view) = self.imp().grid_view.get() else {
+            return;
+        };
+        let mut child = grid_view.first_child();
+        while let Some(item) = child {
+            if let Some(swatch) = item.first_child().and_downcast::<ColorSwatch>() {
+                f(&swatch);
+            }
+            child = item.next_sibling();
+        }
+    }
+
+    pub fn set_surround(&self, surround: Surround) {
+        let previous = self.imp().surround.replace(surround);
+        self.remove_css_class(&previous.css_class());
+        self.add_css_class(&surround.css_class());
+    }
+
+    pub fn wrap_around(&self) -> bool {
+        self.imp().wrap_around.get()
+    }
+
+    /// When enabled, scrolling past either end continues from the other one.
+    pub fn set_wrap_around(&self, wrap_around: bool) {
+        self.imp().wrap_around.set(wrap_around);
+    }
+
+    /// Continue from the other end after scrolling or dragging well past the
+    /// first or last row, or moving the focus past the first or last swatch.
+    fn setup_wrap_around(&self, scrolled: &gtk::ScrolledWindow, grid_view: &gtk::GridView) {
+        // Only watch the scrolling; the scrolled window still handles it
+        let scroll = gtk::EventControllerScroll::new(gtk::EventControllerScrollFlags::VERTICAL);
+        scroll.set_propagation_phase(gtk::PropagationPhase::Capture);
+        scroll.connect_scroll(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |controller, _, dy| {
+                let steps = match controller.unit() {
+                    gdk::ScrollUnit::Wheel => dy,
+                    _ => dy / OVERSCROLL_STEP,
+                };
+                page.overscroll_by(steps);
+                glib::Propagation::Proceed
+            }
+        ));
+        scrolled.add_controller(scroll);
+
+        let drag = gtk::GestureDrag::new();
+        drag.set_touch_only(true);
+        drag.set_propagation_phase(gtk::PropagationPhase::Capture);
+        drag.connect_drag_begin(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_, _, _| page.imp().drag_offset.set(0.0)
+        ));
+        drag.connect_drag_update(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_, _, offset_y| {
+                // Dragging up scrolls down
+                let delta = page.imp().drag_offset.replace(offset_y) - offset_y;
+                page.overscroll_by(delta / OVERSCROLL_STEP);
+            }
+        ));
+        scrolled.add_controller(drag);
+
+        // Scrolling back from an end starts the count again
+        scrolled.vadjustment().connect_value_changed(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_| page.imp().overscroll.set(0.0)
+        ));
+
+        // Before the grid's own bindings, which stop at either end
+        let keys = gtk::EventControllerKey::new();
+        keys.set_propagation_phase(gtk::PropagationPhase::Capture);
+        keys.connect_key_pressed(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            #[upgrade_or]
+            glib::Propagation::Proceed,
+            move |_, key, _, state| {
+                if !state.is_empty() || !page.wrap_focus(key) {
+                    return glib::Propagation::Proceed;
+                }
+                glib::Propagation::Stop
+            }
+        ));
+        grid_view.add_controller(keys);
+    }
+
+    /// Count scrolling of `steps` (positive downwards) that the grid cannot
+    /// follow because it is already at that end, wrapping once there is enough.
+    fn overscroll_by(&self, steps: f64) {
+        let imp = self.imp();
+        if !imp.wrap_around.get() {
+            return;
+        }
+        let Some(scrolled) = imp.scrolled.get() else {
+            return;
+        };
+        let adj = scrolled.vadjustment();
+        if adj.upper() - adj.lower() <= adj.page_size() {
+            return;
+        }
+        let at_top = adj.value() <= adj.lower() + 0.5;
+        let at_bottom = adj.value() + adj.page_size() >= adj.upper() - 0.5;
+
+        let total = imp.overscroll.get();
+        let total = if steps > 0.0 && at_bottom {
+            total.max(0.0) + steps
+        } else if steps < 0.0 && at_top {
+            total.min(0.0) + steps
+        } else {
+            0.0
+        };
+        if total >= WRAP_OVERSCROLL {
+            imp.overscroll.set(0.0);
+            self.wrap_to_edge(gtk::PositionType::Bottom, gtk::ListScrollFlags::NONE);
+        } else if total <= -WRAP_OVERSCROLL {
+            imp.overscroll.set(0.0);
+            self.wrap_to_edge(gtk::PositionType::Top, gtk::ListScrollFlags::NONE);
+        } else {
+            imp.overscroll.set(total);
+        }
+    }
+
+    /// Move the focus to the other end when `key` would take it past the
+    /// first or last swatch. Returns whether it did.
+    fn wrap_focus(&self, key: gdk::Key) -> bool {
+        let imp = self.imp();
+        let Some(model) = imp.model.get() else {
+            return false;
+        };
+        if !imp.wrap_around.get() || model.n_items() == 0 {
+            return false;
+        }
+        let Some(position) = self.focused_position() else {
+            return false;
+        };
+        let last = model.n_items() - 1;
+        let columns = self.columns();
+        let edge = match key {
+            gdk::Key::Right | gdk::Key::KP_Right if position == last => gtk::PositionType::Bottom,
+            gdk::Key::Down | gdk::Key::KP_Down if position + columns > last => {
+                gtk::PositionType::Bottom
+            }
+            gdk::Key::Left | gdk::Key::KP_Left if position == 0 => gtk::PositionType::Top,
+            gdk::Key::Up | gdk::Key::KP_Up if position < columns => gtk::PositionType::Top,
+            _ => return false,
+        };
+        self.wrap_to_edge(edge, gtk::ListScrollFlags::FOCUS);
+        true
+    }
+
+    /// Where the focused swatch's colour sits in the grid.
+    fn focused_position(&self) -> Option<u32> {
+        let model = self.imp().model.get()?;
+        let key = self
+            .root()
+            .and_then(|root| root.focus())
+            .and_downcast::<ColorSwatch>()?
+            .color()?
+            .key();
+        (0..model.n_items()).find(|&position| {
+            model
+                .item(position)
+                .and_downcast::<ColorObject>()
+                .is_some_and(|obj| obj.color().key() == key)
+        })
+    }
+
+    /// How many columns the grid is showing, from its widest laid-out row.
+    fn columns(&self) -> u32 {
+        let Some(grid_view) = self.imp().grid_view.get() else {
+            return 1;
+        };
+        let mut rows: HashMap<i32, u32> = HashMap::new();
+        let mut child = grid_view.first_child();
+        while let Some(item) = child {
+            child = item.next_sibling();
+            if let Some(bounds) = item.compute_bounds(grid_view) {
+                *rows.entry(bounds.y().round() as i32).or_insert(0) += 1;
+            }
+        }
+        rows.into_values().max().unwrap_or(1)
+    }
+
+    fn wrap_to_edge(&self, edge: gtk::PositionType, flags: gtk::ListScrollFlags) {
+        let imp = self.imp();
+        let (Some(grid), Some(model)) = (imp.grid_view.get(), imp.model.get()) else {
+            return;
+        };
+        if model.n_items() == 0 {
+            return;
+        }
+        match edge {
+            gtk::PositionType::Bottom => grid.scroll_to(0, flags, None),
+            gtk::PositionType::Top => grid.scroll_to(model.n_items() - 1, flags, None),
+            _ => {}
+        }
+    }
+
+    pub fn connect_swatch_size_changed<F: Fn(&Self, i32) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "swatch-size-changed",
+            false,
+            glib::closure_local!(move |page: &Self, size: i32| f(page, size)),
+        )
+    }
+
+    pub fn connect_color_activated<F: Fn(&Self, &ColorObject) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "color-activated",
+            false,
+            glib::closure_local!(move |page: &Self, color: &ColorObject| f(page, color)),
+        )
+    }
+}
+
+/// Column limits that fill a phone-width grid at the narrow end and a wide
+/// desktop window at the other.
+fn columns_for_size(size: i32) -> (u32, u32) {
+    let min = (MIN_GRID_WIDTH / size).max(1);
+    let max = (MAX_GRID_WIDTH / size).max(min);
+    (min as u32, max as u32)
+}
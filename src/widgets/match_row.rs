@@ -0,0 +1,29 @@
+use crate::color::{ColorMatch, DeltaE};
+use crate::widgets::ColorPreview;
+use adw::prelude::*;
+use gtk::glib;
+
+/// A row for a Pantone match: its swatch, name and library, and how far it is
+/// from the colour matched under `formula`.
+pub fn match_row(color_match: &ColorMatch, formula: DeltaE) -> adw::ActionRow {
+    let color = &color_match.color;
+    let row = adw::ActionRow::builder()
+        .title(glib::markup_escape_text(&color.name))
+        .subtitle(format!("{} · {}", color.library.short_name(), color.hex))
+        .build();
+
+    let swatch = ColorPreview::new(color.rgb);
+    swatch.set_size_request(32, 32);
+    swatch.set_valign(gtk::Align::Center);
+    row.add_prefix(&swatch);
+
+    let delta = gtk::Label::new(Some(&format!(
+        "{} {:.2}",
+        formula.display_name(),
+        color_match.delta_e
+    )));
+    delta.add_css_class("dim-label");
+    delta.add_css_class("numeric");
+    row.add_suffix(&delta);
+    row
+}
@@ -0,0 +1,117 @@
+use crate::color::{rgb_to_hex, ColorMatch, DeltaE, Rgb};
+use crate::widgets::{match_row, ColorObject, ColorPreview};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib;
+use gtk::glib::subclass::Signal;
+use std::cell::RefCell;
+use std::sync::OnceLock;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct MatchesDialog {
+        pub matches: RefCell<Vec<ColorMatch>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for MatchesDialog {
+        const NAME: &'static str = "LonMatchesDialog";
+        type Type = super::MatchesDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for MatchesDialog {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("color-activated")
+                    .param_types([ColorObject::static_type()])
+                    .build()]
+            })
+        }
+    }
+
+    impl WidgetImpl for MatchesDialog {}
+    impl AdwDialogImpl for MatchesDialog {}
+}
+
+glib::wrapper! {
+    pub struct MatchesDialog(ObjectSubclass<imp::MatchesDialog>)
+        @extends adw::Dialog, gtk::Widget;
+}
+
+impl MatchesDialog {
+    /// Show `matches` for an arbitrary `target` colour, nearest first.
+    pub fn new(target: Rgb, matches: Vec<ColorMatch>, formula: DeltaE) -> Self {
+        let dialog: Self = glib::Object::builder()
+            .property("title", format!("Matches for {}", rgb_to_hex(&target)))
+            .property("content-width", 360)
+            .property("content-height", 560)
+            .build();
+        dialog.setup(target, &matches, formula);
+        dialog.imp().matches.replace(matches);
+        dialog
+    }
+
+    fn setup(&self, target: Rgb, matches: &[ColorMatch], formula: DeltaE) {
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        content.set_margin_start(16);
+        content.set_margin_end(16);
+        content.set_margin_top(8);
+        content.set_margin_bottom(16);
+
+        let preview = ColorPreview::new(target);
+        preview.set_height_request(96);
+        preview.set_hexpand(true);
+        content.append(&preview);
+
+        let list = gtk::ListBox::new();
+        list.add_css_class("boxed-list");
+        list.set_selection_mode(gtk::SelectionMode::None);
+        for color_match in matches {
+            let row = match_row(color_match, formula);
+            row.set_activatable(true);
+            list.append(&row);
+        }
+        list.connect_row_activated(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_, row| {
+                let color = dialog
+                    .imp()
+                    .matches
+                    .borrow()
+                    .get(row.index() as usize)
+                    .map(|m| m.color.clone());
+                if let Some(color) = color {
+                    dialog.emit_by_name::<()>("color-activated", &[&ColorObject::new(color)]);
+                    dialog.close();
+                }
+            }
+        ));
+        content.append(&list);
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+        scrolled.set_propagate_natural_height(true);
+        scrolled.set_child(Some(&content));
+
+        let toolbar = adw::ToolbarView::new();
+        toolbar.add_top_bar(&adw::HeaderBar::new());
+        toolbar.set_content(Some(&scrolled));
+        self.set_child(Some(&toolbar));
+    }
+
+    pub fn connect_color_activated<F: Fn(&Self, &ColorObject) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "color-activated",
+            false,
+            glib::closure_local!(move |dialog: &Self, color: &ColorObject| f(dialog, color)),
+        )
+    }
+}
@@ -1,9 +1,45 @@
+mod code_export_dialog;
 mod color_object;
+mod color_preview;
 mod color_swatch;
+mod compare_dialog;
+mod compare_patch;
+mod custom_color_dialog;
 mod detail_page;
-mod infinite_model;
+mod family_chips;
+mod image_inspector;
+mod image_view;
+mod installed_palettes_dialog;
+mod library_page;
+mod match_row;
+mod matches_dialog;
+mod palette_browser;
+mod palette_dialog;
+mod preferences_dialog;
+mod recent_page;
+mod section_index;
+mod swatch_file_dialog;
 
+pub use code_export_dialog::CodeExportDialog;
 pub use color_object::ColorObject;
-pub use color_swatch::ColorSwatch;
+pub use color_preview::ColorPreview;
+pub use color_swatch::{
+    ColorSwatch, SwatchLabel, DEFAULT_SWATCH_SIZE, MAX_SWATCH_SIZE, MIN_SWATCH_SIZE,
+};
+pub use compare_dialog::CompareDialog;
+pub use compare_patch::{ComparePatch, PatchLayout};
+pub use custom_color_dialog::CustomColorDialog;
 pub use detail_page::ColorDetailPanel;
-pub use infinite_model::InfiniteListModel;
+pub use family_chips::FamilyChips;
+pub use image_inspector::ImageInspector;
+pub use image_view::ImageView;
+pub use installed_palettes_dialog::InstalledPalettesDialog;
+pub use library_page::{LibraryPage, Surround, SWATCH_SIZE_STEP};
+pub use match_row::match_row;
+pub use matches_dialog::MatchesDialog;
+pub use palette_browser::{add_to_palette_menu, PaletteBrowser, FLOATING_BUTTONS_WIDTH};
+pub use palette_dialog::PaletteDialog;
+pub use preferences_dialog::PreferencesDialog;
+pub use recent_page::RecentPage;
+pub use section_index::SectionIndex;
+pub use swatch_file_dialog::{export_swatches, swatch_filters};
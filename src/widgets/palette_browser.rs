@@ -0,0 +1,854 @@
+use crate::color::{
+    hex_to_rgb, rgb_to_hex, ColorDatabase, ColorKey, Palette, PaletteCollection, PantoneColor,
+    SwatchFormat, Versioned,
+};
+use crate::storage;
+use crate::widgets::{export_swatches, CodeExportDialog, ColorObject, ColorPreview};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib::subclass::Signal;
+use gtk::{gdk, gio, glib};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::fs;
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+/// Every user palette, under `storage::data_dir()`.
+const PALETTES_FILE: &str = "palettes.json";
+/// Colours previewed beside each palette in the list.
+const PREVIEW_SWATCHES: usize = 6;
+/// Name given to palettes until the user renames them.
+const UNTITLED: &str = "Untitled Palette";
+/// Width kept clear of the window's floating buttons.
+pub const FLOATING_BUTTONS_WIDTH: i32 = 128;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct PaletteBrowser {
+        pub palettes: RefCell<PaletteCollection>,
+        pub db: OnceCell<Rc<ColorDatabase>>,
+        pub navigation: OnceCell<adw::NavigationView>,
+        pub list: OnceCell<gtk::ListBox>,
+        pub empty_status: OnceCell<adw::StatusPage>,
+        /// The palette shown on the pushed page, if any.
+        pub open: Cell<Option<usize>>,
+        pub open_page: RefCell<Option<adw::NavigationPage>>,
+        pub entries: RefCell<Option<gtk::ListBox>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PaletteBrowser {
+        const NAME: &'static str = "LonPaletteBrowser";
+        type Type = super::PaletteBrowser;
+        type ParentType = adw::Bin;
+    }
+
+    impl ObjectImpl for PaletteBrowser {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![
+                    Signal::builder("color-activated")
+                        .param_types([ColorObject::static_type()])
+                        .build(),
+                    // Palettes were added, renamed or removed
+                    Signal::builder("changed").build(),
+                ]
+            })
+        }
+    }
+
+    impl WidgetImpl for PaletteBrowser {}
+    impl BinImpl for PaletteBrowser {}
+}
+
+glib::wrapper! {
+    pub struct PaletteBrowser(ObjectSubclass<imp::PaletteBrowser>)
+        @extends adw::Bin, gtk::Widget;
+}
+
+impl PaletteBrowser {
+    /// Lists the palettes saved on disk; `db` resolves colour names typed in.
+    pub fn new(db: Rc<ColorDatabase>) -> Self {
+        let browser: Self = glib::Object::new();
+        let _ = browser.imp().db.set(db);
+        browser
+            .imp()
+            .palettes
+            .replace(storage::load(PALETTES_FILE, PaletteCollection::from_json));
+        browser.setup();
+        browser
+    }
+
+    fn setup(&self) {
+        let imp = self.imp();
+        let navigation = adw::NavigationView::new();
+        navigation.add(&self.list_page());
+        navigation.connect_popped(glib::clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |_, page| {
+                let imp = browser.imp();
+                if imp.open_page.borrow().as_ref() == Some(page) {
+                    imp.open.set(None);
+                    imp.open_page.replace(None);
+                    imp.entries.replace(None);
+                }
+            }
+        ));
+        self.set_child(Some(&navigation));
+        let _ = imp.navigation.set(navigation);
+        self.rebuild_list();
+    }
+
+    fn list_page(&self) -> adw::NavigationPage {
+        let imp = self.imp();
+
+        let new_button = gtk::Button::from_icon_name("list-add-symbolic");
+        new_button.set_tooltip_text(Some("New Palette"));
+        new_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |_| browser.new_palette()
+        ));
+
+        let list = gtk::ListBox::new();
+        list.add_css_class("boxed-list");
+        list.set_selection_mode(gtk::SelectionMode::None);
+        list.set_valign(gtk::Align::Start);
+        list.connect_row_activated(glib::clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |_, row| browser.open_palette(row.index() as usize)
+        ));
+
+        let create_button = gtk::Button::with_label("New Palette");
+        create_button.add_css_class("pill");
+        create_button.add_css_class("suggested-action");
+        create_button.set_halign(gtk::Align::Center);
+        create_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |_| browser.new_palette()
+        ));
+        let empty_status = adw::StatusPage::builder()
+            .icon_name("color-select-symbolic")
+            .title("No Palettes")
+            .description("Collect colours from any library into named palettes")
+            .child(&create_button)
+            .vexpand(true)
+            .build();
+
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        content.set_margin_start(16);
+        content.set_margin_end(16);
+        content.set_margin_top(8);
+        content.set_margin_bottom(16);
+        content.append(&list);
+        content.append(&empty_status);
+
+        let _ = imp.list.set(list);
+        let _ = imp.empty_status.set(empty_status);
+        adw::NavigationPage::builder()
+            .title("Palettes")
+            .tag("list")
+            .child(&page_toolbar(&content, &new_button))
+            .build()
+    }
+
+    fn rebuild_list(&self) {
+        let imp = self.imp();
+        let (Some(list), Some(empty_status)) = (imp.list.get(), imp.empty_status.get()) else {
+            return;
+        };
+        list.remove_all();
+        let palettes = imp.palettes.borrow();
+        for palette in palettes.palettes() {
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&palette.name))
+                .subtitle(colour_count(palette.entries.len()))
+                .activatable(true)
+                .build();
+
+            let strip = gtk::Box::new(gtk::Orientation::Horizontal, 2);
+            strip.set_valign(gtk::Align::Center);
+            for entry in palette.entries.iter().take(PREVIEW_SWATCHES) {
+                let swatch = ColorPreview::new(entry.color.rgb);
+                swatch.set_size_request(12, 32);
+                strip.append(&swatch);
+            }
+            row.add_suffix(&strip);
+            row.add_suffix(&gtk::Image::from_icon_name("go-next-symbolic"));
+            list.append(&row);
+        }
+        list.set_visible(!palettes.palettes().is_empty());
+        empty_status.set_visible(palettes.palettes().is_empty());
+    }
+
+    fn open_palette(&self, index: usize) {
+        let imp = self.imp();
+        let Some(name) = imp.palettes.borrow().get(index).map(|p| p.name.clone()) else {
+            return;
+        };
+
+        let add_row = adw::EntryRow::builder()
+            .title("Add hex or colour name")
+            .show_apply_button(true)
+            .build();
+        add_row.connect_apply(glib::clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |row| browser.add_typed(row)
+        ));
+        add_row.connect_entry_activated(glib::clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |row| browser.add_typed(row)
+        ));
+        let add_list = gtk::ListBox::new();
+        add_list.add_css_class("boxed-list");
+        add_list.set_selection_mode(gtk::SelectionMode::None);
+        add_list.append(&add_row);
+
+        let placeholder = gtk::Label::new(Some(
+            "Add colours here, or from a swatch’s menu in any library",
+        ));
+        placeholder.add_css_class("dim-label");
+        placeholder.set_wrap(true);
+        placeholder.set_margin_top(24);
+        placeholder.set_margin_bottom(24);
+        placeholder.set_margin_start(12);
+        placeholder.set_margin_end(12);
+
+        let entries = gtk::ListBox::new();
+        entries.add_css_class("boxed-list");
+        entries.set_selection_mode(gtk::SelectionMode::None);
+        entries.set_placeholder(Some(&placeholder));
+        entries.connect_row_activated(glib::clone!(
+            #[weak(rename_to = browser)]
+            self,
+            move |_, row| {
+                let color = browser.open_entry_color(row.index() as usize);
+                if let Some(color) = color {
+                    browser.emit_by_name::<()>("color-activated", &[&ColorObject::new(color)]);
+                }
+            }
+        ));
+
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 18);
+        content.set_margin_start(16);
+        content.set_margin_end(16);
+        content.set_margin_top(8);
+        content.set_margin_bottom(16);
+        content.append(&add_list);
+        content.append(&entries);
+
+        let menu = gio::Menu::new();
+        menu.append(Some("Rename…"), Some("palette.rename"));
+        menu.append(Some("Export Swatches…"), Some("palette.export"));
+        menu.append(Some("Add to GIMP or Inkscape…"), Some("palette.install"));
+        menu.append(Some("Export as Code…"), Some("palette.export-code"));
+        menu.append(Some("Delete…"), Some("palette.delete"));
+        let menu_button = gtk::MenuButton::new();
+        menu_button.set_icon_name("view-more-symbolic");
+        menu_button.set_tooltip_text(Some("Palette Menu"));
+        menu_button.set_menu_model(Some(&menu));
+
+        let page = adw::NavigationPage::builder()
+            .title(name.as_str())
+            .child(&page_toolbar(&content, &menu_button))
+            .build();
+        page.insert_action_group("palette", Some(&self.palette_actions()));
+
+        imp.open.set(Some(index));
+        imp.open_page.replace(Some(page.clone()));
+        imp.entries.replace(Some(entries));
+        self.rebuild_entries();
+        if let Some(navigation) = imp.navigation.get() {
+            navigation.push(&page);
+        }
+    }
+
+    /// Actions for the open palette and, by index, its entries.
+    fn palette_actions(&self) -> gio::SimpleActionGroup {
+        let group = gio::SimpleActionGroup::new();
+
+        let rename = gio::ActionEntry::builder("rename")
+            .activate(glib::clone!(
+                #[weak(rename_to = browser)]
+                self,
+                move |_: &gio::SimpleActionGroup, _, _| browser.rename_open_palette()
+            ))
+            .build();
+        let delete = gio::ActionEntry::builder("delete")
+            .activate(glib::clone!(
+                #[weak(rename_to = browser)]
+                self,
+                move |_: &gio::SimpleActionGroup, _, _| browser.delete_open_palette()
+            ))
+            .build();
+
+        let export = gio::ActionEntry::builder("export")
+            .activate(glib::clone!(
+                #[weak(rename_to = browser)]
+                self,
+                move |_: &gio::SimpleActionGroup, _, _| browser.export_open_palette()
+            ))
+            .build();
+
+        let install = gio::ActionEntry::builder("install")
+            .activate(glib::clone!(
+                #[weak(rename_to = browser)]
+                self,
+                move |_: &gio::SimpleActionGroup, _, _| browser.install_open_palette()
+            ))
+            .build();
+
+        let export_code = gio::ActionEntry::builder("export-code")
+            .activate(glib::clone!(
+                #[weak(rename_to = browser)]
+                self,
+                move |_: &gio::SimpleActionGroup, _, _| {
+                    if let Some(palette) = browser.open_palette_data() {
+                        CodeExportDialog::new(palette).present(Some(&browser));
+                    }
+                }
+            ))
+            .build();
+
+        let entry_action = |name: &str, f: fn(&Self, usize)| {
+            gio::ActionEntry::builder(name)
+                .parameter_type(Some(glib::VariantTy::UINT32))
+                .activate(glib::clone!(
+                    #[weak(rename_to = browser)]
+                    self,
+                    move |_: &gio::SimpleActionGroup, _, param| {
+                        if let Some(index) = param.and_then(|p| p.get::<u32>()) {
+                            f(&browser, index as usize);
+                        }
+                    }
+                ))
+                .build()
+        };
+
+        group.add_action_entries([
+            rename,
+            delete,
+            export,
+            install,
+            export_code,
+            entry_action("edit-notes", Self::edit_notes),
+            entry_action("move-up", |browser, index| {
+                if index > 0 {
+                    browser.move_entry(index, index - 1);
+                }
+            }),
+            entry_action("move-down", |browser, index| {
+                browser.move_entry(index, index + 1)
+            }),
+            entry_action("remove-entry", |browser, index| {
+                browser.update_open(|palette| {
+                    palette.remove(index);
+                })
+            }),
+        ]);
+        group
+    }
+
+    fn rebuild_entries(&self) {
+        let imp = self.imp();
+        let (Some(index), Some(list)) = (imp.open.get(), imp.entries.borrow().clone()) else {
+            return;
+        };
+        list.remove_all();
+        let palettes = imp.palettes.borrow();
+        let Some(palette) = palettes.get(index) else {
+            return;
+        };
+        let count = palette.entries.len();
+        for (position, entry) in palette.entries.iter().enumerate() {
+            let color = &entry.color;
+            let mut subtitle = format!("{} · {}", color.library.short_name(), color.hex);
+            if !entry.notes.is_empty() {
+                subtitle = format!("{}\n{}", subtitle, entry.notes);
+            }
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&color.name))
+                .subtitle(glib::markup_escape_text(&subtitle))
+                .activatable(true)
+                .build();
+
+            let handle = gtk::Image::from_icon_name("list-drag-handle-symbolic");
+            handle.add_css_class("dim-label");
+            row.add_prefix(&handle);
+            let swatch = ColorPreview::new(color.rgb);
+            swatch.set_size_request(32, 32);
+            swatch.set_valign(gtk::Align::Center);
+            row.add_prefix(&swatch);
+
+            let target = (position as u32).to_variant();
+            let menu = gio::Menu::new();
+            let item = |label: &str, action: &str| {
+                let item = gio::MenuItem::new(Some(label), None);
+                item.set_action_and_target_value(Some(action), Some(&target));
+                menu.append_item(&item);
+            };
+            item("Edit Notes…", "palette.edit-notes");
+            if position > 0 {
+                item("Move Up", "palette.move-up");
+            }
+            if position + 1 < count {
+                item("Move Down", "palette.move-down");
+            }
+            item("Remove", "palette.remove-entry");
+            let menu_button = gtk::MenuButton::new();
+            menu_button.set_icon_name("view-more-symbolic");
+            menu_button.set_tooltip_text(Some("Colour Menu"));
+            menu_button.set_valign(gtk::Align::Center);
+            menu_button.add_css_class("flat");
+            menu_button.set_menu_model(Some(&menu));
+            row.add_suffix(&menu_button);
+
+            self.setup_row_drag(&row, position);
+            list.append(&row);
+        }
+    }
+
+    /// Drag a row onto another to move it there.
+    fn setup_row_drag(&self, row: &adw::ActionRow, position: usize) {
+        let source = gtk::DragSource::new();
+        source.set_actions(gdk::DragAction::MOVE);
+        source.connect_prepare(move |_, _, _| {
+            Some(gdk::ContentProvider::for_value(
+                &(position as u32).to_value(),
+            ))
+        });
+        source.connect_drag_begin(|source, _| {
+            if let Some(row) = source.widget() {
+                let icon = gtk::WidgetPaintable::new(Some(&row));
+                source.set_icon(Some(&icon), 0, 0);
+            }
+        });
+        row.add_controller(source);
+
+        let target = gtk::DropTarget::new(u32::static_type(), gdk::DragAction::MOVE);
+        target.connect_drop(glib::clone!(
+            #[weak(rename_to = browser)]
+            self,
+            #[upgrade_or]
+            false,
+            move |_, value, _, _| {
+                let Ok(from) = value.get::<u32>() else {
+                    return false;
+                };
+                browser.move_entry(from as usize, position);
+                true
+            }
+        ));
+        row.add_controller(target);
+    }
+
+    fn open_entry_color(&self, position: usize) -> Option<PantoneColor> {
+        let imp = self.imp();
+        let index = imp.open.get()?;
+        let palettes = imp.palettes.borrow();
+        palettes
+            .get(index)?
+            .entries
+            .get(position)
+            .map(|entry| entry.color.clone())
+    }
+
+    fn move_entry(&self, from: usize, to: usize) {
+        if from != to {
+            self.update_open(|palette| palette.move_entry(from, to));
+        }
+    }
+
+    fn add_typed(&self, row: &adw::EntryRow) {
+        let text = row.text();
+        let text = text.trim();
+        if text.is_empty() {
+            return;
+        }
+        let color = match hex_to_rgb(text) {
+            Some(rgb) => Some(PantoneColor::custom(&rgb_to_hex(&rgb), rgb)),
+            None => self.imp().db.get().and_then(|db| db.find_by_name(text)),
+        };
+        match color {
+            Some(color) => {
+                self.update_open(|palette| palette.push(color, ""));
+                row.set_text("");
+                row.remove_css_class("error");
+            }
+            None => row.add_css_class("error"),
+        }
+    }
+
+    fn edit_notes(&self, position: usize) {
+        let imp = self.imp();
+        let Some(index) = imp.open.get() else {
+            return;
+        };
+        let (name, notes) = {
+            let palettes = imp.palettes.borrow();
+            let Some(entry) = palettes.get(index).and_then(|p| p.entries.get(position)) else {
+                return;
+            };
+            (entry.color.name.clone(), entry.notes.clone())
+        };
+        self.prompt(
+            &format!("Notes for {}", name),
+            &notes,
+            "Save",
+            move |browser, notes| {
+                browser.update_open(|palette| palette.set_notes(position, notes));
+            },
+        );
+    }
+
+    fn rename_open_palette(&self) {
+        let imp = self.imp();
+        let Some(index) = imp.open.get() else {
+            return;
+        };
+        let Some(name) = imp.palettes.borrow().get(index).map(|p| p.name.clone()) else {
+            return;
+        };
+        self.prompt("Rename Palette", &name, "Rename", move |browser, name| {
+            let name = name.trim();
+            if name.is_empty() {
+                return;
+            }
+            browser.update(|palettes| palettes.rename(index, name));
+            if let Some(page) = browser.imp().open_page.borrow().as_ref() {
+                page.set_title(name);
+            }
+        });
+    }
+
+    fn delete_open_palette(&self) {
+        let imp = self.imp();
+        let Some(index) = imp.open.get() else {
+            return;
+        };
+        let Some(name) = imp.palettes.borrow().get(index).map(|p| p.name.clone()) else {
+            return;
+        };
+        let dialog = adw::AlertDialog::new(
+            Some(&format!("Delete “{}”?", name)),
+            Some("The palette and its notes are removed. Library colours are not affected."),
+        );
+        dialog.add_responses(&[("cancel", "Cancel"), ("delete", "Delete")]);
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        dialog.connect_response(
+            Some("delete"),
+            glib::clone!(
+                #[weak(rename_to = browser)]
+                self,
+                move |_, _| {
+                    if let Some(navigation) = browser.imp().navigation.get() {
+                        navigation.pop();
+                    }
+                    browser.update(|palettes| {
+                        palettes.remove(index);
+                    });
+                }
+            ),
+        );
+        dialog.present(Some(self));
+    }
+
+    /// A copy of the palette on the pushed page, if any.
+    fn open_palette_data(&self) -> Option<Palette> {
+        let imp = self.imp();
+        let index = imp.open.get()?;
+        imp.palettes.borrow().get(index).cloned()
+    }
+
+    fn export_open_palette(&self) {
+        if let Some(palette) = self.open_palette_data() {
+            export_swatches(self, &palette.name.clone(), vec![palette]);
+        }
+    }
+
+    /// Write the open palette into a GIMP or Inkscape palettes folder as a
+    /// .gpl named after it, replacing an earlier copy.
+    fn install_open_palette(&self) {
+        let Some(palette) = self.open_palette_data() else {
+            return;
+        };
+        let dirs = storage::installed_palette_dirs();
+        if dirs.is_empty() {
+            let dialog = adw::AlertDialog::new(
+                Some("No Palettes Folder Found"),
+                Some(
+                    "Open GIMP or Inkscape once so it creates its palettes folder, then try again.",
+                ),
+            );
+            dialog.add_response("close", "Close");
+            dialog.present(Some(self));
+            return;
+        }
+
+        let dialog = adw::AlertDialog::new(
+            Some(&format!("Add “{}” to…", palette.name)),
+            Some("It is listed among the app’s palettes after a restart. An earlier copy is replaced."),
+        );
+        dialog.add_response("cancel", "Cancel");
+        for (index, (label, _)) in dirs.iter().enumerate() {
+            dialog.add_response(&index.to_string(), label);
+        }
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = browser)]
+                self,
+                move |_, response| {
+                    let Some((label, dir)) =
+                        response.parse::<usize>().ok().and_then(|i| dirs.get(i))
+                    else {
+                        return;
+                    };
+                    let path = dir.join(format!(
+                        "{}.{}",
+                        file_stem(&palette.name),
+                        SwatchFormat::Gpl.extension()
+                    ));
+                    let contents = SwatchFormat::Gpl.write(std::slice::from_ref(&palette));
+                    match fs::write(path, contents) {
+                        Ok(()) => show_toast(&browser, &format!("Added to {}", label)),
+                        Err(err) => {
+                            show_toast(&browser, &format!("Could not add to {}: {}", label, err))
+                        }
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(self));
+    }
+
+    /// Ask for a line of text, prefilled with `text`, and pass it to `apply`.
+    fn prompt(
+        &self,
+        heading: &str,
+        text: &str,
+        confirm: &str,
+        apply: impl Fn(&Self, &str) + 'static,
+    ) {
+        let entry = gtk::Entry::new();
+        entry.set_text(text);
+        entry.set_activates_default(true);
+
+        let dialog = adw::AlertDialog::new(Some(heading), None);
+        dialog.set_extra_child(Some(&entry));
+        dialog.add_responses(&[("cancel", "Cancel"), ("apply", confirm)]);
+        dialog.set_response_appearance("apply", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("apply"));
+        dialog.connect_response(
+            Some("apply"),
+            glib::clone!(
+                #[weak(rename_to = browser)]
+                self,
+                #[weak]
+                entry,
+                move |_, _| apply(&browser, &entry.text())
+            ),
+        );
+        dialog.present(Some(self));
+    }
+
+    fn new_palette(&self) {
+        let index = self.create_palette();
+        self.open_palette(index);
+    }
+
+    fn create_palette(&self) -> usize {
+        let mut index = 0;
+        self.update(|palettes| index = palettes.create(UNTITLED));
+        index
+    }
+
+    /// Names of every palette, in order, for "Add to Palette" menus.
+    pub fn palette_names(&self) -> Vec<String> {
+        self.imp()
+            .palettes
+            .borrow()
+            .palettes()
+            .iter()
+            .map(|palette| palette.name.clone())
+            .collect()
+    }
+
+    /// Append `color` to the palette at `index`.
+    pub fn add_color(&self, index: usize, color: &PantoneColor) {
+        let mut added = None;
+        self.update(|palettes| {
+            if let Some(palette) = palettes.get_mut(index) {
+                palette.push(color.clone(), "");
+                added = Some(palette.name.clone());
+            }
+        });
+        if let Some(name) = added {
+            show_toast(self, &format!("Added to {}", name));
+        }
+    }
+
+    /// Start a palette with `color` in it.
+    pub fn add_to_new_palette(&self, color: &PantoneColor) {
+        let index = self.create_palette();
+        self.add_color(index, color);
+    }
+
+    /// Add palettes read from a swatch file, and go back to the list to show them.
+    pub fn import_palettes(&self, palettes: Vec<Palette>) {
+        let count = palettes.len();
+        self.update(|collection| {
+            for palette in palettes {
+                collection.push(palette);
+            }
+        });
+        if let Some(navigation) = self.imp().navigation.get() {
+            navigation.pop_to_tag("list");
+        }
+        if count == 1 {
+            show_toast(self, "Imported 1 palette");
+        } else {
+            show_toast(self, &format!("Imported {} palettes", count));
+        }
+    }
+
+    /// Edit the open palette, if there is one.
+    fn update_open(&self, f: impl FnOnce(&mut Palette)) {
+        let Some(index) = self.imp().open.get() else {
+            return;
+        };
+        self.update(|palettes| {
+            if let Some(palette) = palettes.get_mut(index) {
+                f(palette);
+            }
+        });
+    }
+
+    /// Apply `f`, save to disk and refresh every view of the palettes.
+    fn update(&self, f: impl FnOnce(&mut PaletteCollection)) {
+        let imp = self.imp();
+        f(&mut imp.palettes.borrow_mut());
+
+        let saved = imp
+            .palettes
+            .borrow()
+            .to_json()
+            .map_err(|err| err.to_string())
+            .and_then(|json| storage::write(PALETTES_FILE, &json).map_err(|err| err.to_string()));
+        if let Err(err) = saved {
+            show_toast(self, &format!("Could not save palettes: {}", err));
+        }
+
+        self.rebuild_list();
+        self.rebuild_entries();
+        self.emit_by_name::<()>("changed", &[]);
+    }
+
+    pub fn connect_color_activated<F: Fn(&Self, &ColorObject) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "color-activated",
+            false,
+            glib::closure_local!(move |browser: &Self, color: &ColorObject| f(browser, color)),
+        )
+    }
+
+    pub fn connect_changed<F: Fn(&Self) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "changed",
+            false,
+            glib::closure_local!(move |browser: &Self| f(browser)),
+        )
+    }
+}
+
+/// An "Add to Palette" menu for the colour `key`, one item per palette plus
+/// "New Palette", activating `win.add-to-palette` and `win.add-to-new-palette`.
+pub fn add_to_palette_menu(names: &[String], key: &ColorKey) -> gio::Menu {
+    let menu = gio::Menu::new();
+    let palettes = gio::Menu::new();
+    for (index, name) in names.iter().enumerate() {
+        let item = gio::MenuItem::new(Some(name), None);
+        item.set_action_and_target_value(
+            Some("win.add-to-palette"),
+            Some(&(index as u32, key.library.as_str(), key.name.as_str()).to_variant()),
+        );
+        palettes.append_item(&item);
+    }
+    menu.append_section(None, &palettes);
+
+    let item = gio::MenuItem::new(Some("New Palette"), None);
+    item.set_action_and_target_value(
+        Some("win.add-to-new-palette"),
+        Some(&(key.library.as_str(), key.name.as_str()).to_variant()),
+    );
+    menu.append_item(&item);
+    menu
+}
+
+/// A header bar over scrolling `content`, with `action` at the start.
+fn page_toolbar(content: &gtk::Box, action: &impl IsA<gtk::Widget>) -> adw::ToolbarView {
+    let header = adw::HeaderBar::new();
+    header.set_show_end_title_buttons(false);
+    header.pack_start(action);
+    // Leave room for the floating buttons in the window's corner
+    let spacer = gtk::Box::new(gtk::Orientation::Horizontal, 0);
+    spacer.set_width_request(FLOATING_BUTTONS_WIDTH);
+    header.pack_end(&spacer);
+
+    let scrolled = gtk::ScrolledWindow::new();
+    scrolled.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+    scrolled.set_vexpand(true);
+    scrolled.set_child(Some(content));
+
+    let toolbar = adw::ToolbarView::new();
+    toolbar.add_top_bar(&header);
+    toolbar.set_content(Some(&scrolled));
+    toolbar
+}
+
+fn colour_count(count: usize) -> String {
+    if count == 1 {
+        "1 colour".to_string()
+    } else {
+        format!("{} colours", count)
+    }
+}
+
+/// `name` made safe to use as a file name.
+fn file_stem(name: &str) -> String {
+    let stem: String = name
+        .chars()
+        .map(|c| if c == '/' || c.is_control() { '-' } else { c })
+        .collect();
+    match stem.trim().trim_start_matches('.') {
+        "" => UNTITLED.to_string(),
+        stem => stem.to_string(),
+    }
+}
+
+/// Show `message` in the nearest toast overlay.
+fn show_toast(widget: &impl IsA<gtk::Widget>, message: &str) {
+    let mut parent = widget.parent();
+    while let Some(p) = parent {
+        if let Ok(overlay) = p.clone().downcast::<adw::ToastOverlay>() {
+            let toast = adw::Toast::new(message);
+            toast.set_timeout(2);
+            overlay.add_toast(toast);
+            break;
+        }
+        parent = p.parent();
+    }
+}
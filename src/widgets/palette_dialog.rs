@@ -0,0 +1,269 @@
+use crate::color::{
+    extract_dominant_colors, rgb_to_hex, ColorDatabase, ColorMatch, DeltaE, DominantColor, Palette,
+    Rgb,
+};
+use crate::image::ImagePixels;
+use crate::widgets::{match_row, ColorObject, ColorPreview};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib::subclass::Signal;
+use gtk::{gdk, gio, glib};
+use std::cell::{Cell, OnceCell, RefCell};
+use std::rc::Rc;
+use std::sync::OnceLock;
+
+/// Images are subsampled to roughly this many pixels before clustering.
+const SAMPLE_PIXELS: usize = 40_000;
+const DEFAULT_COUNT: f64 = 6.0;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct PaletteDialog {
+        pub db: OnceCell<Rc<ColorDatabase>>,
+        pub formula: Cell<DeltaE>,
+        pub name: RefCell<String>,
+        pub pixels: RefCell<Vec<Rgb>>,
+        pub entries: RefCell<Vec<(DominantColor, ColorMatch)>>,
+        pub list: OnceCell<gtk::ListBox>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PaletteDialog {
+        const NAME: &'static str = "LonPaletteDialog";
+        type Type = super::PaletteDialog;
+        type ParentType = adw::Dialog;
+    }
+
+    impl ObjectImpl for PaletteDialog {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("color-activated")
+                    .param_types([ColorObject::static_type()])
+                    .build()]
+            })
+        }
+    }
+
+    impl WidgetImpl for PaletteDialog {}
+    impl AdwDialogImpl for PaletteDialog {}
+}
+
+glib::wrapper! {
+    pub struct PaletteDialog(ObjectSubclass<imp::PaletteDialog>)
+        @extends adw::Dialog, gtk::Widget;
+}
+
+impl PaletteDialog {
+    /// Extract the dominant colours of `texture` and match each to the nearest library colour.
+    pub fn new(name: &str, texture: &gdk::Texture, db: Rc<ColorDatabase>, formula: DeltaE) -> Self {
+        let dialog: Self = glib::Object::builder()
+            .property("title", name)
+            .property("content-width", 400)
+            .property("content-height", 640)
+            .build();
+        let imp = dialog.imp();
+        let _ = imp.db.set(db);
+        imp.formula.set(formula);
+        imp.name.replace(name.to_string());
+        imp.pixels
+            .replace(ImagePixels::from_texture(texture).sample(SAMPLE_PIXELS));
+        dialog.setup(texture);
+        dialog.extract(DEFAULT_COUNT as usize);
+        dialog
+    }
+
+    fn setup(&self, texture: &gdk::Texture) {
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 12);
+        content.set_margin_start(16);
+        content.set_margin_end(16);
+        content.set_margin_top(8);
+        content.set_margin_bottom(16);
+
+        let picture = gtk::Picture::for_paintable(texture);
+        picture.set_content_fit(gtk::ContentFit::Contain);
+        picture.set_height_request(160);
+        picture.add_css_class("card");
+        content.append(&picture);
+
+        let count_row = adw::SpinRow::with_range(2.0, 16.0, 1.0);
+        count_row.set_title("Colours");
+        count_row.set_value(DEFAULT_COUNT);
+        count_row.connect_value_notify(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |row| dialog.extract(row.value() as usize)
+        ));
+        let settings = gtk::ListBox::new();
+        settings.add_css_class("boxed-list");
+        settings.set_selection_mode(gtk::SelectionMode::None);
+        settings.append(&count_row);
+        content.append(&settings);
+
+        let list = gtk::ListBox::new();
+        list.add_css_class("boxed-list");
+        list.set_selection_mode(gtk::SelectionMode::None);
+        list.connect_row_activated(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_, row| {
+                let color = dialog
+                    .imp()
+                    .entries
+                    .borrow()
+                    .get(row.index() as usize)
+                    .map(|(_, m)| m.color.clone());
+                if let Some(color) = color {
+                    dialog.emit_by_name::<()>("color-activated", &[&ColorObject::new(color)]);
+                    dialog.close();
+                }
+            }
+        ));
+        content.append(&list);
+        let _ = self.imp().list.set(list);
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+        scrolled.set_propagate_natural_height(true);
+        scrolled.set_child(Some(&content));
+
+        let save_button = gtk::Button::with_label("Save…");
+        save_button.set_tooltip_text(Some("Save as Palette"));
+        save_button.connect_clicked(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            move |_| dialog.save()
+        ));
+        let header = adw::HeaderBar::new();
+        header.pack_start(&save_button);
+
+        let toolbar = adw::ToolbarView::new();
+        toolbar.add_top_bar(&header);
+        toolbar.set_content(Some(&scrolled));
+        self.set_child(Some(&toolbar));
+    }
+
+    fn extract(&self, count: usize) {
+        let imp = self.imp();
+        let Some(db) = imp.db.get() else {
+            return;
+        };
+        let formula = imp.formula.get();
+        let entries: Vec<(DominantColor, ColorMatch)> =
+            extract_dominant_colors(&imp.pixels.borrow(), count)
+                .into_iter()
+                .filter_map(|dominant| {
+                    let nearest = db.nearest(&dominant.rgb, formula, 1).into_iter().next()?;
+                    Some((dominant, nearest))
+                })
+                .collect();
+
+        let Some(list) = imp.list.get() else {
+            return;
+        };
+        list.remove_all();
+        for (dominant, color_match) in &entries {
+            list.append(&Self::row(dominant, color_match, formula));
+        }
+        imp.entries.replace(entries);
+    }
+
+    fn row(dominant: &DominantColor, color_match: &ColorMatch, formula: DeltaE) -> adw::ActionRow {
+        let color = &color_match.color;
+        let row = match_row(color_match, formula);
+        row.set_subtitle(&format!(
+            "{} · {} · {:.0}% of image",
+            color.library.short_name(),
+            color.hex,
+            dominant.share * 100.0
+        ));
+        row.set_activatable(true);
+
+        // The sampled colour goes in front of its match, so the gap is visible
+        let sampled = ColorPreview::new(dominant.rgb);
+        sampled.set_size_request(32, 32);
+        sampled.set_valign(gtk::Align::Center);
+        sampled.set_tooltip_text(Some(&format!(
+            "Image {} → {}",
+            rgb_to_hex(&dominant.rgb),
+            color.hex
+        )));
+        row.add_prefix(&sampled);
+        row
+    }
+
+    /// The extracted colours as a palette, largest share first.
+    pub fn palette(&self) -> Palette {
+        let imp = self.imp();
+        let mut palette = Palette::new(&imp.name.borrow());
+        for (dominant, color_match) in imp.entries.borrow().iter() {
+            palette.push(
+                color_match.color.clone(),
+                &format!(
+                    "{:.0}% of image, sampled {}",
+                    dominant.share * 100.0,
+                    rgb_to_hex(&dominant.rgb)
+                ),
+            );
+        }
+        palette
+    }
+
+    fn save(&self) {
+        let palette = self.palette();
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Palette"));
+        filter.add_suffix("json");
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+        let file_dialog = gtk::FileDialog::builder()
+            .title("Save Palette")
+            .initial_name(format!("{}.json", palette.name))
+            .filters(&filters)
+            .build();
+
+        let root = self.root().and_downcast::<gtk::Window>();
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = dialog)]
+            self,
+            async move {
+                let Ok(file) = file_dialog.save_future(root.as_ref()).await else {
+                    return;
+                };
+                let result = match palette.to_json() {
+                    Ok(json) => file
+                        .replace_contents_future(
+                            json.into_bytes(),
+                            None,
+                            false,
+                            gio::FileCreateFlags::REPLACE_DESTINATION,
+                        )
+                        .await
+                        .map(|_| ())
+                        .map_err(|(_, err)| err.to_string()),
+                    Err(err) => Err(err.to_string()),
+                };
+                if let Err(err) = result {
+                    let alert = adw::AlertDialog::new(Some("Could Not Save Palette"), Some(&err));
+                    alert.add_response("close", "Close");
+                    alert.present(Some(&dialog));
+                } else {
+                    dialog.close();
+                }
+            }
+        ));
+    }
+
+    pub fn connect_color_activated<F: Fn(&Self, &ColorObject) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "color-activated",
+            false,
+            glib::closure_local!(move |dialog: &Self, color: &ColorObject| f(dialog, color)),
+        )
+    }
+}
@@ -0,0 +1,243 @@
+use crate::color::{ColorLibrary, CopyFormat, DeltaE, Grouping, Notation, Rgb, SortOrder};
+use crate::widgets::{Surround, SwatchLabel, MAX_SWATCH_SIZE, MIN_SWATCH_SIZE, SWATCH_SIZE_STEP};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::{gio, glib};
+
+/// Shown as the example value for each notation.
+const SAMPLE: Rgb = Rgb {
+    r: 0xbb,
+    g: 0x26,
+    b: 0x49,
+};
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct PreferencesDialog {}
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for PreferencesDialog {
+        const NAME: &'static str = "LonPreferencesDialog";
+        type Type = super::PreferencesDialog;
+        type ParentType = adw::PreferencesDialog;
+    }
+
+    impl ObjectImpl for PreferencesDialog {}
+    impl WidgetImpl for PreferencesDialog {}
+    impl AdwDialogImpl for PreferencesDialog {}
+    impl PreferencesDialogImpl for PreferencesDialog {}
+}
+
+glib::wrapper! {
+    pub struct PreferencesDialog(ObjectSubclass<imp::PreferencesDialog>)
+        @extends adw::PreferencesDialog, adw::Dialog, gtk::Widget;
+}
+
+impl PreferencesDialog {
+    /// Every row writes straight to `settings`; the window applies changes as they happen.
+    pub fn new(settings: &gio::Settings) -> Self {
+        let dialog: Self = glib::Object::new();
+        dialog.add(&Self::general_page(settings));
+        dialog.add(&Self::values_page(settings));
+        dialog
+    }
+
+    fn general_page(settings: &gio::Settings) -> adw::PreferencesPage {
+        let page = adw::PreferencesPage::builder()
+            .title("General")
+            .icon_name("preferences-system-symbolic")
+            .build();
+
+        let appearance = adw::PreferencesGroup::builder().title("Appearance").build();
+        appearance.add(&choice_row(
+            settings,
+            "color-scheme",
+            "Style",
+            vec![
+                ("system", "Follow System"),
+                ("light", "Light"),
+                ("dark", "Dark"),
+            ],
+        ));
+        let surround = choice_row(
+            settings,
+            "surround",
+            "Swatch Background",
+            Surround::all()
+                .iter()
+                .map(|s| (s.id(), s.display_name()))
+                .collect(),
+        );
+        surround.set_subtitle("The surround changes how a colour is perceived");
+        appearance.add(&surround);
+        page.add(&appearance);
+
+        let browsing = adw::PreferencesGroup::builder().title("Browsing").build();
+        browsing.add(&choice_row(
+            settings,
+            "default-library",
+            "Default Library",
+            ColorLibrary::all()
+                .iter()
+                .map(|l| (l.id(), l.short_name()))
+                .collect(),
+        ));
+        browsing.add(&choice_row(
+            settings,
+            "group-by",
+            "Group By",
+            Grouping::all()
+                .iter()
+                .map(|g| (g.id(), g.display_name()))
+                .collect(),
+        ));
+        browsing.add(&choice_row(
+            settings,
+            "sort-by",
+            "Sort By",
+            SortOrder::all()
+                .iter()
+                .map(|s| (s.id(), s.display_name()))
+                .collect(),
+        ));
+
+        let swatch_size = adw::SpinRow::with_range(
+            MIN_SWATCH_SIZE as f64,
+            MAX_SWATCH_SIZE as f64,
+            SWATCH_SIZE_STEP as f64,
+        );
+        swatch_size.set_title("Swatch Size");
+        settings
+            .bind("swatch-size", &swatch_size, "value")
+            .mapping(|variant, _| variant.get::<i32>().map(|size| (size as f64).to_value()))
+            .set_mapping(|value, _| {
+                value
+                    .get::<f64>()
+                    .ok()
+                    .map(|size| (size as i32).to_variant())
+            })
+            .build();
+        browsing.add(&swatch_size);
+
+        browsing.add(&choice_row(
+            settings,
+            "swatch-label",
+            "Swatch Labels",
+            SwatchLabel::all()
+                .iter()
+                .map(|l| (l.id(), l.display_name()))
+                .collect(),
+        ));
+
+        let wrap_around = adw::SwitchRow::builder()
+            .title("Wrap Around")
+            .subtitle("Scrolling past either end of a library continues from the other")
+            .build();
+        settings.bind("wrap-around", &wrap_around, "active").build();
+        browsing.add(&wrap_around);
+        page.add(&browsing);
+
+        let copying = adw::PreferencesGroup::builder().title("Copying").build();
+        copying.add(&choice_row(
+            settings,
+            "copy-format",
+            "Copy Format",
+            CopyFormat::all()
+                .iter()
+                .map(|f| (f.id(), f.display_name()))
+                .collect(),
+        ));
+        let template = adw::EntryRow::builder().title("Custom Template").build();
+        settings.bind("copy-template", &template, "text").build();
+        copying.add(&template);
+        page.add(&copying);
+
+        let matching = adw::PreferencesGroup::builder().title("Matching").build();
+        matching.add(&choice_row(
+            settings,
+            "delta-e",
+            "Colour Difference",
+            DeltaE::all()
+                .iter()
+                .map(|f| (f.id(), f.display_name()))
+                .collect(),
+        ));
+        page.add(&matching);
+
+        page
+    }
+
+    fn values_page(settings: &gio::Settings) -> adw::PreferencesPage {
+        let page = adw::PreferencesPage::builder()
+            .title("Colour Values")
+            .icon_name("color-select-symbolic")
+            .build();
+        let group = adw::PreferencesGroup::builder()
+            .title("Detail Panel")
+            .description("Values listed for the selected colour")
+            .build();
+
+        let enabled = settings.strv("notations");
+        let rows: Vec<(Notation, adw::SwitchRow)> = Notation::all()
+            .iter()
+            .map(|notation| {
+                let row = adw::SwitchRow::builder()
+                    .title(notation.display_name())
+                    .subtitle(notation.format(&SAMPLE))
+                    .active(enabled.iter().any(|id| id.as_str() == notation.id()))
+                    .build();
+                group.add(&row);
+                (*notation, row)
+            })
+            .collect();
+
+        for (_, row) in &rows {
+            let rows = rows.clone();
+            let settings = settings.clone();
+            row.connect_active_notify(move |_| {
+                let ids: Vec<&str> = rows
+                    .iter()
+                    .filter(|(_, row)| row.is_active())
+                    .map(|(notation, _)| notation.id())
+                    .collect();
+                let _ = settings.set_strv("notations", ids.as_slice());
+            });
+        }
+
+        page.add(&group);
+        page
+    }
+}
+
+/// A combo row bound to a string setting whose values are `choices` ids.
+fn choice_row(
+    settings: &gio::Settings,
+    key: &str,
+    title: &str,
+    choices: Vec<(&'static str, &'static str)>,
+) -> adw::ComboRow {
+    let names: Vec<&str> = choices.iter().map(|(_, name)| *name).collect();
+    let row = adw::ComboRow::builder()
+        .title(title)
+        .model(&gtk::StringList::new(&names))
+        .build();
+
+    let ids: Vec<&'static str> = choices.iter().map(|(id, _)| *id).collect();
+    let set_ids = ids.clone();
+    settings
+        .bind(key, &row, "selected")
+        .mapping(move |variant, _| {
+            let id = variant.str()?;
+            ids.iter()
+                .position(|choice| *choice == id)
+                .map(|index| (index as u32).to_value())
+        })
+        .set_mapping(move |value, _| {
+            let index = value.get::<u32>().ok()?;
+            set_ids.get(index as usize).map(|id| id.to_variant())
+        })
+        .build();
+    row
+}
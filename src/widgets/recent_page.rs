@@ -0,0 +1,187 @@
+use crate::color::HistoryEntry;
+use crate::widgets::{ColorObject, ColorPreview};
+use adw::prelude::*;
+use adw::subclass::prelude::*;
+use gtk::glib;
+use gtk::glib::subclass::Signal;
+use std::cell::{OnceCell, RefCell};
+use std::sync::OnceLock;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct RecentPage {
+        pub entries: RefCell<Vec<HistoryEntry>>,
+        pub list: OnceCell<gtk::ListBox>,
+        pub empty_status: OnceCell<adw::StatusPage>,
+        pub clear_button: OnceCell<gtk::Button>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for RecentPage {
+        const NAME: &'static str = "LonRecentPage";
+        type Type = super::RecentPage;
+        type ParentType = adw::Bin;
+    }
+
+    impl ObjectImpl for RecentPage {
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("color-activated")
+                    .param_types([ColorObject::static_type()])
+                    .build()]
+            })
+        }
+
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup();
+        }
+    }
+
+    impl WidgetImpl for RecentPage {}
+    impl BinImpl for RecentPage {}
+}
+
+glib::wrapper! {
+    pub struct RecentPage(ObjectSubclass<imp::RecentPage>)
+        @extends adw::Bin, gtk::Widget;
+}
+
+impl Default for RecentPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RecentPage {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn setup(&self) {
+        let imp = self.imp();
+
+        let list = gtk::ListBox::new();
+        list.add_css_class("boxed-list");
+        list.set_selection_mode(gtk::SelectionMode::None);
+        list.set_valign(gtk::Align::Start);
+        list.connect_row_activated(glib::clone!(
+            #[weak(rename_to = page)]
+            self,
+            move |_, row| {
+                let color = page
+                    .imp()
+                    .entries
+                    .borrow()
+                    .get(row.index() as usize)
+                    .map(|entry| entry.color.clone());
+                if let Some(color) = color {
+                    page.emit_by_name::<()>("color-activated", &[&ColorObject::new(color)]);
+                }
+            }
+        ));
+
+        let clear_button = gtk::Button::with_label("Clear History");
+        clear_button.add_css_class("pill");
+        clear_button.set_halign(gtk::Align::Center);
+        clear_button.set_action_name(Some("win.clear-history"));
+
+        let empty_status = adw::StatusPage::builder()
+            .icon_name("document-open-recent-symbolic")
+            .title("No Recent Colours")
+            .description("Colours you open are listed here")
+            .vexpand(true)
+            .build();
+
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 18);
+        content.set_margin_start(16);
+        content.set_margin_end(16);
+        content.set_margin_top(8);
+        // Clear of the carousel indicator dots
+        content.set_margin_bottom(36);
+        content.append(&list);
+        content.append(&clear_button);
+        content.append(&empty_status);
+
+        let scrolled = gtk::ScrolledWindow::new();
+        scrolled.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+        scrolled.set_hexpand(true);
+        scrolled.set_vexpand(true);
+        scrolled.set_child(Some(&content));
+        self.set_child(Some(&scrolled));
+
+        let _ = imp.list.set(list);
+        let _ = imp.empty_status.set(empty_status);
+        let _ = imp.clear_button.set(clear_button);
+        self.set_entries(&[]);
+    }
+
+    pub fn title(&self) -> String {
+        "Recent".to_string()
+    }
+
+    /// List `entries`, most recent first.
+    pub fn set_entries(&self, entries: &[HistoryEntry]) {
+        let imp = self.imp();
+        imp.entries.replace(entries.to_vec());
+        let (Some(list), Some(empty_status), Some(clear_button)) = (
+            imp.list.get(),
+            imp.empty_status.get(),
+            imp.clear_button.get(),
+        ) else {
+            return;
+        };
+
+        list.remove_all();
+        let now = glib::DateTime::now_local().ok();
+        for entry in entries {
+            let color = &entry.color;
+            let row = adw::ActionRow::builder()
+                .title(glib::markup_escape_text(&color.name))
+                .subtitle(format!("{} · {}", color.library.short_name(), color.hex))
+                .activatable(true)
+                .build();
+
+            let swatch = ColorPreview::new(color.rgb);
+            swatch.set_size_request(32, 32);
+            swatch.set_valign(gtk::Align::Center);
+            row.add_prefix(&swatch);
+
+            let time = gtk::Label::new(Some(&viewed_label(entry.viewed_at, now.as_ref())));
+            time.add_css_class("dim-label");
+            time.add_css_class("numeric");
+            row.add_suffix(&time);
+            list.append(&row);
+        }
+
+        list.set_visible(!entries.is_empty());
+        clear_button.set_visible(!entries.is_empty());
+        empty_status.set_visible(entries.is_empty());
+    }
+
+    pub fn connect_color_activated<F: Fn(&Self, &ColorObject) + 'static>(
+        &self,
+        f: F,
+    ) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "color-activated",
+            false,
+            glib::closure_local!(move |page: &Self, color: &ColorObject| f(page, color)),
+        )
+    }
+}
+
+/// The time of day for views today, otherwise the date.
+fn viewed_label(viewed_at: i64, now: Option<&glib::DateTime>) -> String {
+    let Ok(time) = glib::DateTime::from_unix_local(viewed_at) else {
+        return String::new();
+    };
+    let today = now.is_some_and(|now| now.ymd() == time.ymd());
+    let format = if today { "%H:%M" } else { "%e %b" };
+    time.format(format)
+        .map(|label| label.trim().to_string())
+        .unwrap_or_default()
+}
@@ -0,0 +1,136 @@
+use adw::subclass::prelude::*;
+use gtk::glib;
+use gtk::glib::subclass::Signal;
+use gtk::prelude::*;
+use std::cell::{Cell, RefCell};
+use std::sync::OnceLock;
+
+mod imp {
+    use super::*;
+
+    #[derive(Default)]
+    pub struct SectionIndex {
+        /// Start position of each section, in the same order as the labels
+        pub starts: RefCell<Vec<u32>>,
+        pub last_jump: Cell<Option<usize>>,
+    }
+
+    #[glib::object_subclass]
+    impl ObjectSubclass for SectionIndex {
+        const NAME: &'static str = "LonSectionIndex";
+        type Type = super::SectionIndex;
+        type ParentType = gtk::Box;
+    }
+
+    impl ObjectImpl for SectionIndex {
+        fn constructed(&self) {
+            self.parent_constructed();
+            self.obj().setup();
+        }
+
+        fn signals() -> &'static [Signal] {
+            static SIGNALS: OnceLock<Vec<Signal>> = OnceLock::new();
+            SIGNALS.get_or_init(|| {
+                vec![Signal::builder("jump")
+                    .param_types([u32::static_type()])
+                    .build()]
+            })
+        }
+    }
+
+    impl WidgetImpl for SectionIndex {}
+    impl BoxImpl for SectionIndex {}
+}
+
+glib::wrapper! {
+    pub struct SectionIndex(ObjectSubclass<imp::SectionIndex>)
+        @extends gtk::Box, gtk::Widget,
+        @implements gtk::Orientable;
+}
+
+impl SectionIndex {
+    pub fn new() -> Self {
+        glib::Object::new()
+    }
+
+    fn setup(&self) {
+        self.set_orientation(gtk::Orientation::Vertical);
+        self.set_homogeneous(true);
+        self.set_valign(gtk::Align::Center);
+        self.add_css_class("section-index");
+        self.set_cursor_from_name(Some("pointer"));
+
+        // Tap or drag along the strip to jump between sections
+        let click = gtk::GestureClick::new();
+        click.connect_pressed(glib::clone!(
+            #[weak(rename_to = index)]
+            self,
+            move |_, _, _, y| {
+                index.imp().last_jump.set(None);
+                index.jump_to_y(y);
+            }
+        ));
+        self.add_controller(click);
+
+        let drag = gtk::GestureDrag::new();
+        drag.connect_drag_update(glib::clone!(
+            #[weak(rename_to = index)]
+            self,
+            move |gesture, _, offset_y| {
+                if let Some((_, start_y)) = gesture.start_point() {
+                    index.jump_to_y(start_y + offset_y);
+                }
+            }
+        ));
+        self.add_controller(drag);
+    }
+
+    /// Replace the index entries with `(label, start position)` pairs.
+    pub fn set_sections(&self, sections: Vec<(String, u32)>) {
+        while let Some(child) = self.first_child() {
+            self.remove(&child);
+        }
+
+        let mut starts = Vec::with_capacity(sections.len());
+        for (label, start) in sections {
+            let label = gtk::Label::new(Some(&label));
+            label.add_css_class("caption");
+            self.append(&label);
+            starts.push(start);
+        }
+        // A single section has nothing to jump between
+        self.set_visible(starts.len() > 1);
+        self.imp().starts.replace(starts);
+    }
+
+    fn jump_to_y(&self, y: f64) {
+        let starts = self.imp().starts.borrow();
+        let height = self.height() as f64;
+        if starts.is_empty() || height <= 0.0 {
+            return;
+        }
+
+        let index =
+            ((y / height) * starts.len() as f64).clamp(0.0, starts.len() as f64 - 1.0) as usize;
+        if self.imp().last_jump.replace(Some(index)) == Some(index) {
+            return;
+        }
+        let start = starts[index];
+        drop(starts);
+        self.emit_by_name::<()>("jump", &[&start]);
+    }
+
+    pub fn connect_jump<F: Fn(&Self, u32) + 'static>(&self, f: F) -> glib::SignalHandlerId {
+        self.connect_closure(
+            "jump",
+            false,
+            glib::closure_local!(move |index: &Self, start: u32| f(index, start)),
+        )
+    }
+}
+
+impl Default for SectionIndex {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,64 @@
+use crate::color::{Palette, SwatchFormat};
+use adw::prelude::*;
+use gtk::{gio, glib};
+
+/// File filters for every swatch format, the first accepting any of them.
+pub fn swatch_filters() -> gio::ListStore {
+    let filters = gio::ListStore::new::<gtk::FileFilter>();
+    let any = gtk::FileFilter::new();
+    any.set_name(Some("Swatch Files"));
+    filters.append(&any);
+    for format in SwatchFormat::all() {
+        any.add_suffix(format.extension());
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some(format.display_name()));
+        filter.add_suffix(format.extension());
+        filters.append(&filter);
+    }
+    filters
+}
+
+/// Ask where to save `palettes` and write them in the format the chosen file
+/// name's extension asks for. `name` suggests the file name.
+pub fn export_swatches(parent: &impl IsA<gtk::Widget>, name: &str, palettes: Vec<Palette>) {
+    let default_format = SwatchFormat::all()[0];
+    let file_dialog = gtk::FileDialog::builder()
+        .title("Export Swatches")
+        .initial_name(format!("{}.{}", name, default_format.extension()))
+        .filters(&swatch_filters())
+        .build();
+
+    let parent = parent.clone().upcast::<gtk::Widget>();
+    let root = parent.root().and_downcast::<gtk::Window>();
+    glib::spawn_future_local(async move {
+        let Ok(file) = file_dialog.save_future(root.as_ref()).await else {
+            return;
+        };
+        let file_name = file
+            .basename()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let result = match SwatchFormat::from_filename(&file_name) {
+            Some(format) => file
+                .replace_contents_future(
+                    format.write(&palettes),
+                    None,
+                    false,
+                    gio::FileCreateFlags::REPLACE_DESTINATION,
+                )
+                .await
+                .map(|_| ())
+                .map_err(|(_, err)| err.to_string()),
+            None => Err(format!(
+                "“{}” does not end in a swatch file extension, such as .{}",
+                file_name,
+                default_format.extension()
+            )),
+        };
+        if let Err(err) = result {
+            let alert = adw::AlertDialog::new(Some("Could Not Export Swatches"), Some(&err));
+            alert.add_response("close", "Close");
+            alert.present(Some(&parent));
+        }
+    });
+}
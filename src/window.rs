@@ -1,9 +1,36 @@
-use crate::color::{ColorDatabase, ColorLibrary, PantoneColor};
-use crate::widgets::{ColorDetailPanel, ColorObject, ColorSwatch, InfiniteListModel};
+use crate::color::{
+    hex_to_rgb, Annotation, AnnotationKey, Annotations, ColorDatabase, ColorKey, ColorLibrary,
+    CopyFormat, CustomColor, CustomLibrary, DeltaE, Favourites, Grouping, History, Notation,
+    Palette, PantoneColor, Rgb, SortOrder, SwatchFormat, Versioned, ViewTrail,
+    TEMPLATE_PLACEHOLDERS,
+};
+use crate::portal::{self, PortalError};
+use crate::settings;
+use crate::storage;
+use crate::widgets::{
+    export_swatches, swatch_filters, CodeExportDialog, ColorDetailPanel, ColorSwatch,
+    CompareDialog, CustomColorDialog, FamilyChips, ImageInspector, InstalledPalettesDialog,
+    LibraryPage, MatchesDialog, PaletteBrowser, PaletteDialog, PreferencesDialog, RecentPage,
+    Surround, SwatchLabel, FLOATING_BUTTONS_WIDTH, MAX_SWATCH_SIZE, MIN_SWATCH_SIZE,
+    SWATCH_SIZE_STEP,
+};
 use adw::prelude::*;
 use adw::subclass::prelude::*;
-use gtk::{gio, glib};
-use std::cell::RefCell;
+use gtk::{gdk, gio, glib};
+use std::cell::{OnceCell, RefCell};
+use std::collections::HashSet;
+use std::rc::Rc;
+
+/// Starred colours, under `storage::data_dir()`.
+const FAVOURITES_FILE: &str = "favourites.json";
+/// Recently viewed colours, under `storage::data_dir()`.
+const HISTORY_FILE: &str = "history.json";
+/// The Custom library, under `storage::data_dir()`.
+const CUSTOM_COLORS_FILE: &str = "custom-colors.json";
+/// Notes and tags on colours, under `storage::data_dir()`.
+const ANNOTATIONS_FILE: &str = "annotations.json";
+/// Pantone colours listed for a custom colour in the sheet.
+const CUSTOM_MATCH_COUNT: usize = 3;
 
 mod imp {
     use super::*;
@@ -13,6 +40,25 @@ mod imp {
         pub bottom_sheet: RefCell<Option<adw::BottomSheet>>,
         pub detail_panel: RefCell<Option<ColorDetailPanel>>,
         pub section_toast: RefCell<Option<adw::Toast>>,
+        pub db: OnceCell<Rc<ColorDatabase>>,
+        pub pages: RefCell<Vec<LibraryPage>>,
+        pub family_chips: RefCell<Option<FamilyChips>>,
+        pub carousel: RefCell<Option<adw::Carousel>>,
+        pub search_bar: RefCell<Option<gtk::SearchBar>>,
+        pub toast_overlay: RefCell<Option<adw::ToastOverlay>>,
+        pub compare_dialog: OnceCell<CompareDialog>,
+        pub settings: OnceCell<gio::Settings>,
+        pub favourites: RefCell<Favourites>,
+        pub favourites_page: OnceCell<LibraryPage>,
+        pub palette_browser: OnceCell<PaletteBrowser>,
+        pub view_stack: OnceCell<gtk::Stack>,
+        pub history: RefCell<History>,
+        pub trail: RefCell<ViewTrail>,
+        pub recent_page: OnceCell<RecentPage>,
+        pub custom_colors: RefCell<CustomLibrary>,
+        pub custom_page: OnceCell<LibraryPage>,
+        pub annotations: RefCell<Annotations>,
+        pub search_entry: OnceCell<gtk::SearchEntry>,
     }
 
     #[glib::object_subclass]
@@ -35,6 +81,13 @@ mod imp {
     impl AdwApplicationWindowImpl for LonWindow {}
 }
 
+/// What to do with an image once it is opened.
+#[derive(Debug, Clone, Copy)]
+enum ImageTool {
+    Palette,
+    Inspector,
+}
+
 glib::wrapper! {
     pub struct LonWindow(ObjectSubclass<imp::LonWindow>)
         @extends adw::ApplicationWindow, gtk::ApplicationWindow, gtk::Window, gtk::Widget,
@@ -54,20 +107,612 @@ impl LonWindow {
         window
     }
 
+    fn setup_actions(&self) {
+        let search = gio::ActionEntry::builder("search")
+            .activate(|window: &Self, _, _| {
+                window.set_palettes_visible(false);
+                if let Some(search_bar) = window.imp().search_bar.borrow().as_ref() {
+                    search_bar.set_search_mode(true);
+                }
+            })
+            .build();
+
+        let close_sheet = gio::ActionEntry::builder("close-sheet")
+            .activate(|window: &Self, _, _| {
+                if let Some(sheet) = window.imp().bottom_sheet.borrow().as_ref() {
+                    sheet.set_open(false);
+                }
+            })
+            .build();
+
+        let copy_color = gio::ActionEntry::builder("copy-color")
+            .activate(|window: &Self, _, _| {
+                if let Some(color) = window.focused_color() {
+                    window.copy_color(&color, window.copy_format());
+                }
+            })
+            .build();
+
+        let copy_detail = gio::ActionEntry::builder("copy-detail")
+            .activate(|window: &Self, _, _| {
+                if let Some(color) = window.detail_color() {
+                    window.copy_color(&color, window.copy_format());
+                }
+            })
+            .build();
+
+        let copy_detail_as = gio::ActionEntry::builder("copy-detail-as")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(|window: &Self, _, param| {
+                let format = param
+                    .and_then(|p| p.get::<String>())
+                    .and_then(|id| CopyFormat::from_id(&id));
+                if let (Some(color), Some(format)) = (window.detail_color(), format) {
+                    window.copy_color(&color, format);
+                }
+            })
+            .build();
+
+        let edit_copy_template = gio::ActionEntry::builder("edit-copy-template")
+            .activate(|window: &Self, _, _| window.edit_copy_template())
+            .build();
+
+        let show_preferences = gio::ActionEntry::builder("show-preferences")
+            .activate(|window: &Self, _, _| {
+                PreferencesDialog::new(window.settings()).present(Some(window));
+            })
+            .build();
+
+        let compare_color = gio::ActionEntry::builder("compare-color")
+            .activate(|window: &Self, _, _| {
+                if let Some(color) = window.focused_color() {
+                    window.compare_color(&color);
+                }
+            })
+            .build();
+
+        let show_compare = gio::ActionEntry::builder("show-compare")
+            .activate(|window: &Self, _, _| window.show_compare())
+            .build();
+
+        let previous_library = gio::ActionEntry::builder("previous-library")
+            .activate(|window: &Self, _, _| window.step_library(-1))
+            .build();
+
+        let next_library = gio::ActionEntry::builder("next-library")
+            .activate(|window: &Self, _, _| window.step_library(1))
+            .build();
+
+        let pick_color = gio::ActionEntry::builder("pick-color")
+            .activate(|window: &Self, _, _| window.pick_color())
+            .build();
+
+        let open_image = gio::ActionEntry::builder("open-image")
+            .activate(|window: &Self, _, _| window.open_image(ImageTool::Palette))
+            .build();
+
+        let inspect_image = gio::ActionEntry::builder("inspect-image")
+            .activate(|window: &Self, _, _| window.open_image(ImageTool::Inspector))
+            .build();
+
+        let zoom_in = gio::ActionEntry::builder("zoom-in")
+            .activate(|window: &Self, _, _| window.zoom_swatches(SWATCH_SIZE_STEP))
+            .build();
+
+        let zoom_out = gio::ActionEntry::builder("zoom-out")
+            .activate(|window: &Self, _, _| window.zoom_swatches(-SWATCH_SIZE_STEP))
+            .build();
+
+        let zoom_reset = gio::ActionEntry::builder("zoom-reset")
+            .activate(|window: &Self, _, _| window.settings().reset("swatch-size"))
+            .build();
+
+        let toggle_favourite = gio::ActionEntry::builder("toggle-favourite")
+            .parameter_type(Some(glib::VariantTy::new("(ss)").unwrap()))
+            .activate(|window: &Self, _, param| {
+                if let Some((library, name)) = param.and_then(|p| p.get::<(String, String)>()) {
+                    window.toggle_favourite(&ColorKey::new(&library, &name));
+                }
+            })
+            .build();
+
+        let show_palettes = gio::ActionEntry::builder("show-palettes")
+            .state(false.to_variant())
+            .activate(|window: &Self, action, _| {
+                let visible = action
+                    .state()
+                    .and_then(|s| s.get::<bool>())
+                    .unwrap_or(false);
+                window.set_palettes_visible(!visible);
+            })
+            .build();
+
+        let add_to_palette = gio::ActionEntry::builder("add-to-palette")
+            .parameter_type(Some(glib::VariantTy::new("(uss)").unwrap()))
+            .activate(|window: &Self, _, param| {
+                let Some((index, library, name)) =
+                    param.and_then(|p| p.get::<(u32, String, String)>())
+                else {
+                    return;
+                };
+                let color = window.find_color(&ColorKey::new(&library, &name));
+                if let (Some(color), Some(browser)) = (color, window.imp().palette_browser.get()) {
+                    browser.add_color(index as usize, &color);
+                }
+            })
+            .build();
+
+        let add_to_new_palette = gio::ActionEntry::builder("add-to-new-palette")
+            .parameter_type(Some(glib::VariantTy::new("(ss)").unwrap()))
+            .activate(|window: &Self, _, param| {
+                let Some((library, name)) = param.and_then(|p| p.get::<(String, String)>()) else {
+                    return;
+                };
+                let color = window.find_color(&ColorKey::new(&library, &name));
+                if let (Some(color), Some(browser)) = (color, window.imp().palette_browser.get()) {
+                    browser.add_to_new_palette(&color);
+                }
+            })
+            .build();
+
+        let history_back = gio::ActionEntry::builder("history-back")
+            .activate(|window: &Self, _, _| {
+                let color = window.imp().trail.borrow_mut().go_back().cloned();
+                if let Some(color) = color {
+                    window.display_color_detail(&color);
+                }
+            })
+            .build();
+
+        let history_forward = gio::ActionEntry::builder("history-forward")
+            .activate(|window: &Self, _, _| {
+                let color = window.imp().trail.borrow_mut().go_forward().cloned();
+                if let Some(color) = color {
+                    window.display_color_detail(&color);
+                }
+            })
+            .build();
+
+        let show_color = gio::ActionEntry::builder("show-color")
+            .parameter_type(Some(glib::VariantTy::new("(ss)").unwrap()))
+            .activate(|window: &Self, _, param| {
+                let Some((library, name)) = param.and_then(|p| p.get::<(String, String)>()) else {
+                    return;
+                };
+                if let Some(color) = window.find_color(&ColorKey::new(&library, &name)) {
+                    window.show_color_detail(&color);
+                }
+            })
+            .build();
+
+        let add_custom_color = gio::ActionEntry::builder("add-custom-color")
+            .activate(|window: &Self, _, _| window.edit_custom_color(None))
+            .build();
+
+        let edit_custom_color = gio::ActionEntry::builder("edit-custom-color")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(|window: &Self, _, param| {
+                if let Some(name) = param.and_then(|p| p.get::<String>()) {
+                    window.edit_custom_color(Some(&name));
+                }
+            })
+            .build();
+
+        let delete_custom_color = gio::ActionEntry::builder("delete-custom-color")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(|window: &Self, _, param| {
+                if let Some(name) = param.and_then(|p| p.get::<String>()) {
+                    window.delete_custom_color(&name);
+                }
+            })
+            .build();
+
+        let set_notes = gio::ActionEntry::builder("set-notes")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(|window: &Self, _, param| {
+                if let Some(notes) = param.and_then(|p| p.get::<String>()) {
+                    window.set_notes(notes.trim());
+                }
+            })
+            .build();
+
+        let add_tag = gio::ActionEntry::builder("add-tag")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(|window: &Self, _, param| {
+                if let Some(tag) = param.and_then(|p| p.get::<String>()) {
+                    window.annotate(|annotation| {
+                        annotation.add_tag(&tag);
+                    });
+                }
+            })
+            .build();
+
+        let remove_tag = gio::ActionEntry::builder("remove-tag")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(|window: &Self, _, param| {
+                if let Some(tag) = param.and_then(|p| p.get::<String>()) {
+                    window.annotate(|annotation| annotation.remove_tag(&tag));
+                }
+            })
+            .build();
+
+        let search_tag = gio::ActionEntry::builder("search-tag")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(|window: &Self, _, param| {
+                if let Some(tag) = param.and_then(|p| p.get::<String>()) {
+                    window.search_tag(&tag);
+                }
+            })
+            .build();
+
+        let import_swatches = gio::ActionEntry::builder("import-swatches")
+            .activate(|window: &Self, _, _| window.import_swatches())
+            .build();
+
+        let show_installed_palettes = gio::ActionEntry::builder("show-installed-palettes")
+            .activate(|window: &Self, _, _| InstalledPalettesDialog::new().present(Some(window)))
+            .build();
+
+        let import_installed = gio::ActionEntry::builder("import-installed")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(|window: &Self, _, param| {
+                let Some(path) = param.and_then(|p| p.get::<String>()) else {
+                    return;
+                };
+                glib::spawn_future_local(glib::clone!(
+                    #[weak]
+                    window,
+                    async move { window.import_file(&gio::File::for_path(path)).await }
+                ));
+            })
+            .build();
+
+        let export_visible = gio::ActionEntry::builder("export-visible")
+            .activate(|window: &Self, _, _| {
+                if let Some(palette) = window.page_palette(true) {
+                    export_swatches(window, &palette.name.clone(), vec![palette]);
+                }
+            })
+            .build();
+
+        let export_code = gio::ActionEntry::builder("export-code")
+            .parameter_type(Some(glib::VariantTy::STRING))
+            .activate(|window: &Self, _, param| {
+                let visible_only =
+                    param.and_then(|p| p.get::<String>()).as_deref() != Some("library");
+                if let Some(palette) = window.page_palette(visible_only) {
+                    CodeExportDialog::new(palette).present(Some(window));
+                }
+            })
+            .build();
+
+        let show_recent = gio::ActionEntry::builder("show-recent")
+            .parameter_type(Some(glib::VariantTy::UINT32))
+            .activate(|window: &Self, _, param| {
+                let Some(index) = param.and_then(|p| p.get::<u32>()) else {
+                    return;
+                };
+                let color = window
+                    .imp()
+                    .history
+                    .borrow()
+                    .entries()
+                    .get(index as usize)
+                    .map(|entry| entry.color.clone());
+                if let Some(color) = color {
+                    window.show_color_detail(&color);
+                }
+            })
+            .build();
+
+        let clear_history = gio::ActionEntry::builder("clear-history")
+            .activate(|window: &Self, _, _| {
+                window.imp().history.borrow_mut().clear();
+                window.save_history();
+                window.update_history();
+            })
+            .build();
+
+        self.add_action_entries([
+            pick_color,
+            open_image,
+            inspect_image,
+            search,
+            close_sheet,
+            copy_color,
+            copy_detail,
+            copy_detail_as,
+            edit_copy_template,
+            show_preferences,
+            compare_color,
+            show_compare,
+            previous_library,
+            next_library,
+            zoom_in,
+            zoom_out,
+            zoom_reset,
+            toggle_favourite,
+            show_palettes,
+            add_to_palette,
+            add_to_new_palette,
+            history_back,
+            history_forward,
+            show_color,
+            show_recent,
+            clear_history,
+            add_custom_color,
+            edit_custom_color,
+            delete_custom_color,
+            set_notes,
+            add_tag,
+            remove_tag,
+            search_tag,
+            import_swatches,
+            show_installed_palettes,
+            import_installed,
+            export_visible,
+            export_code,
+        ]);
+
+        // Menu choices that are also preferences act on the settings directly
+        let settings = self.settings();
+        for key in [
+            "color-scheme",
+            "surround",
+            "swatch-label",
+            "group-by",
+            "sort-by",
+            "wrap-around",
+            "copy-format",
+        ] {
+            self.add_action(&settings.create_action(key));
+        }
+    }
+
+    fn zoom_swatches(&self, delta: i32) {
+        let settings = self.settings();
+        let size = (settings.int("swatch-size") + delta).clamp(MIN_SWATCH_SIZE, MAX_SWATCH_SIZE);
+        let _ = settings.set_int("swatch-size", size);
+    }
+
+    fn settings(&self) -> &gio::Settings {
+        self.imp().settings.get_or_init(settings::settings)
+    }
+
+    fn copy_format(&self) -> CopyFormat {
+        CopyFormat::from_id(&self.settings().string("copy-format")).unwrap_or_default()
+    }
+
+    fn delta_e(&self) -> DeltaE {
+        DeltaE::from_id(&self.settings().string("delta-e")).unwrap_or_default()
+    }
+
+    /// Apply the settings that shape the library pages and detail panel now,
+    /// and again whenever they change.
+    fn bind_settings(&self) {
+        let settings = self.settings();
+        for key in [
+            "color-scheme",
+            "surround",
+            "swatch-label",
+            "group-by",
+            "sort-by",
+            "wrap-around",
+            "swatch-size",
+            "notations",
+        ] {
+            settings.connect_changed(
+                Some(key),
+                glib::clone!(
+                    #[weak(rename_to = window)]
+                    self,
+                    move |_, key| window.apply_setting(key)
+                ),
+            );
+            self.apply_setting(key);
+        }
+    }
+
+    fn apply_setting(&self, key: &str) {
+        let imp = self.imp();
+        let settings = self.settings();
+        let pages = imp.pages.borrow();
+        match key {
+            "color-scheme" => {
+                let scheme = match settings.string(key).as_str() {
+                    "light" => adw::ColorScheme::ForceLight,
+                    "dark" => adw::ColorScheme::ForceDark,
+                    _ => adw::ColorScheme::Default,
+                };
+                adw::StyleManager::default().set_color_scheme(scheme);
+            }
+            "surround" => {
+                let surround = Surround::from_id(&settings.string(key)).unwrap_or_default();
+                pages.iter().for_each(|page| page.set_surround(surround));
+            }
+            "group-by" => {
+                let grouping = Grouping::from_id(&settings.string(key)).unwrap_or_default();
+                pages.iter().for_each(|page| page.set_grouping(grouping));
+            }
+            "sort-by" => {
+                let sort_order = SortOrder::from_id(&settings.string(key)).unwrap_or_default();
+                pages
+                    .iter()
+                    .for_each(|page| page.set_sort_order(sort_order));
+            }
+            "wrap-around" => {
+                let wrap_around = settings.boolean(key);
+                pages
+                    .iter()
+                    .for_each(|page| page.set_wrap_around(wrap_around));
+            }
+            "swatch-size" => {
+                let size = settings.int(key);
+                pages.iter().for_each(|page| page.set_swatch_size(size));
+            }
+            "swatch-label" => {
+                let label = SwatchLabel::from_id(&settings.string(key)).unwrap_or_default();
+                pages.iter().for_each(|page| page.set_swatch_label(label));
+            }
+            "notations" => {
+                let notations = settings
+                    .strv(key)
+                    .iter()
+                    .filter_map(|id| Notation::from_id(id.as_str()))
+                    .collect();
+                if let Some(panel) = imp.detail_panel.borrow().as_ref() {
+                    panel.set_notations(notations);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn setup_shortcuts(&self) {
+        // Bubble phase, so text entries keep their own Ctrl+C, / and Escape
+        let shortcuts = gtk::ShortcutController::new();
+        for (trigger, action) in [
+            ("slash|<Control>f", "win.search"),
+            ("Escape", "win.close-sheet"),
+            ("<Control>c", "win.copy-color"),
+            ("<Alt>Left|Back", "win.history-back"),
+            ("<Alt>Right|Forward", "win.history-forward"),
+            (
+                "<Control>plus|<Control>equal|<Control>KP_Add",
+                "win.zoom-in",
+            ),
+            ("<Control>minus|<Control>KP_Subtract", "win.zoom-out"),
+            ("<Control>0|<Control>KP_0", "win.zoom-reset"),
+        ] {
+            shortcuts.add_shortcut(gtk::Shortcut::new(
+                gtk::ShortcutTrigger::parse_string(trigger),
+                Some(gtk::NamedAction::new(action)),
+            ));
+        }
+        self.add_controller(shortcuts);
+
+        // Capture phase, as scrolled windows bind Ctrl+PageUp/Down themselves
+        let library_shortcuts = gtk::ShortcutController::new();
+        library_shortcuts.set_propagation_phase(gtk::PropagationPhase::Capture);
+        for (trigger, action) in [
+            ("<Control>Page_Up", "win.previous-library"),
+            ("<Control>Page_Down", "win.next-library"),
+        ] {
+            library_shortcuts.add_shortcut(gtk::Shortcut::new(
+                gtk::ShortcutTrigger::parse_string(trigger),
+                Some(gtk::NamedAction::new(action)),
+            ));
+        }
+        self.add_controller(library_shortcuts);
+    }
+
+    fn main_menu() -> gio::Menu {
+        let group_menu = gio::Menu::new();
+        for grouping in Grouping::all() {
+            group_menu.append(
+                Some(grouping.display_name()),
+                Some(&format!("win.group-by::{}", grouping.id())),
+            );
+        }
+
+        let sort_menu = gio::Menu::new();
+        for sort_order in SortOrder::all() {
+            sort_menu.append(
+                Some(sort_order.display_name()),
+                Some(&format!("win.sort-by::{}", sort_order.id())),
+            );
+        }
+
+        let style_menu = gio::Menu::new();
+        for (id, name) in [
+            ("system", "Follow System"),
+            ("light", "Light"),
+            ("dark", "Dark"),
+        ] {
+            style_menu.append(Some(name), Some(&format!("win.color-scheme::{}", id)));
+        }
+
+        let surround_menu = gio::Menu::new();
+        for surround in Surround::all() {
+            surround_menu.append(
+                Some(surround.display_name()),
+                Some(&format!("win.surround::{}", surround.id())),
+            );
+        }
+
+        let label_menu = gio::Menu::new();
+        for label in SwatchLabel::all() {
+            label_menu.append(
+                Some(label.display_name()),
+                Some(&format!("win.swatch-label::{}", label.id())),
+            );
+        }
+
+        let tools_section = gio::Menu::new();
+        tools_section.append(Some("Pick Colour from Screen"), Some("win.pick-color"));
+        tools_section.append(Some("Extract Palette from Image…"), Some("win.open-image"));
+        tools_section.append(Some("Inspect Image…"), Some("win.inspect-image"));
+        tools_section.append(Some("Compare Colours"), Some("win.show-compare"));
+        tools_section.append(Some("Add Custom Colour…"), Some("win.add-custom-color"));
+
+        let swatches_section = gio::Menu::new();
+        swatches_section.append(Some("Import Swatches…"), Some("win.import-swatches"));
+        swatches_section.append(
+            Some("GIMP and Inkscape Palettes…"),
+            Some("win.show-installed-palettes"),
+        );
+        swatches_section.append(Some("Export Visible Colours…"), Some("win.export-visible"));
+        let code_menu = gio::Menu::new();
+        code_menu.append(Some("Visible Colours…"), Some("win.export-code::visible"));
+        code_menu.append(Some("Whole Library…"), Some("win.export-code::library"));
+        swatches_section.append_submenu(Some("Export as Code"), &code_menu);
+
+        let format_menu = gio::Menu::new();
+        let formats_section = gio::Menu::new();
+        for format in CopyFormat::all() {
+            formats_section.append(
+                Some(format.display_name()),
+                Some(&format!("win.copy-format::{}", format.id())),
+            );
+        }
+        format_menu.append_section(None, &formats_section);
+        format_menu.append(
+            Some("Edit Custom Template…"),
+            Some("win.edit-copy-template"),
+        );
+
+        let view_section = gio::Menu::new();
+        view_section.append_submenu(Some("Copy Format"), &format_menu);
+        view_section.append_submenu(Some("Group By"), &group_menu);
+        view_section.append_submenu(Some("Sort By"), &sort_menu);
+        view_section.append(Some("Wrap Around"), Some("win.wrap-around"));
+        view_section.append(Some("Palettes"), Some("win.show-palettes"));
+        view_section.append_submenu(Some("Style"), &style_menu);
+        view_section.append_submenu(Some("Swatch Labels"), &label_menu);
+        view_section.append_submenu(Some("Swatch Background"), &surround_menu);
+
+        let app_section = gio::Menu::new();
+        app_section.append(Some("Preferences"), Some("win.show-preferences"));
+        app_section.append(Some("Keyboard Shortcuts"), Some("win.show-help-overlay"));
+        app_section.append(Some("About lon"), Some("app.about"));
+
+        let menu = gio::Menu::new();
+        menu.append_section(None, &tools_section);
+        menu.append_section(None, &swatches_section);
+        menu.append_section(None, &view_section);
+        menu.append_section(None, &app_section);
+        menu
+    }
+
     fn setup_ui(&self) {
         let imp = self.imp();
+        self.setup_actions();
+        self.setup_shortcuts();
 
         // Custom CSS
         let css = gtk::CssProvider::new();
         css.load_from_string(
-            "@define-color window_bg_color #0a0a0a;
-             @define-color view_bg_color #0a0a0a;
-             @define-color headerbar_bg_color #0f0f0f;
-             @define-color popover_bg_color #0f0f0f;
-             @define-color card_bg_color #0f0f0f;
-             @define-color dialog_bg_color #0a0a0a;
-             @define-color bottom_sheet_bg #0f0f0f;
-             .no-scrollbar scrollbar { opacity: 0; }
+            ".no-scrollbar scrollbar { opacity: 0; }
              gridview { padding: 6px; }
              gridview > child:hover {
                  background: @accent_bg_color;
@@ -75,7 +720,24 @@ impl LonWindow {
              }
              .close-btn { background: alpha(@window_bg_color, 0.8); border-radius: 50%; }
              carouselindicatordots { background: transparent; }
-"
+             .section-header {
+                 background: alpha(@window_bg_color, 0.85);
+                 border-radius: 999px;
+                 padding: 4px 12px;
+             }
+             .section-index {
+                 background: alpha(@window_bg_color, 0.6);
+                 border-radius: 999px;
+                 padding: 6px 2px;
+             }
+             .section-index label { min-width: 22px; }
+             .preview-label.dark { color: rgba(0, 0, 0, 0.87); }
+             .preview-label.light { color: white; }
+             .surround-black gridview { background: #000000; }
+             .surround-grey gridview { background: #777777; }
+             .surround-white gridview { background: #ffffff; }
+             .surround-paper gridview { background: #eff1f4; }
+",
         );
         gtk::style_context_add_provider_for_display(
             &gtk::gdk::Display::default().unwrap(),
@@ -84,18 +746,73 @@ impl LonWindow {
         );
 
         // Pre-load color data
-        let db = ColorDatabase::new();
+        let _ = imp.db.set(Rc::new(ColorDatabase::new()));
 
         // Carousel for swiping between libraries
         let carousel = adw::Carousel::new();
         carousel.set_allow_long_swipes(true);
         carousel.set_vexpand(true);
 
-        let tcx_grid = self.create_grid_view(&db, ColorLibrary::FashionHomeTcx);
-        let solid_grid = self.create_grid_view(&db, ColorLibrary::SolidCoated);
+        let db = imp.db.get().expect("Color database must be loaded");
+        let custom_page = LibraryPage::custom();
+        let _ = imp.custom_page.set(custom_page.clone());
+        let favourites_page = LibraryPage::favourites();
+        let _ = imp.favourites_page.set(favourites_page.clone());
+        let pages = ColorLibrary::pantone()
+            .iter()
+            .map(|library| LibraryPage::new(*library, &db.get_library(*library)))
+            .chain([custom_page, favourites_page]);
+        for page in pages {
+            page.connect_color_activated(glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |_, color_obj| window.show_color_detail(&color_obj.color())
+            ));
+            page.connect_swatch_size_changed(glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |_, size| {
+                    let _ = window.settings().set_int("swatch-size", size);
+                }
+            ));
+            carousel.append(&page);
+            imp.pages.borrow_mut().push(page);
+        }
 
-        carousel.append(&tcx_grid);
-        carousel.append(&solid_grid);
+        let recent_page = RecentPage::new();
+        recent_page.connect_color_activated(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_, color_obj| window.show_color_detail(&color_obj.color())
+        ));
+        carousel.append(&recent_page);
+        let _ = imp.recent_page.set(recent_page);
+        imp.carousel.replace(Some(carousel.clone()));
+
+        // Family filter chips, shared by every library page
+        let family_chips = FamilyChips::new();
+        family_chips.set_margin_start(8);
+        family_chips.set_margin_end(8);
+        family_chips.connect_changed(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |chips| {
+                for page in window.imp().pages.borrow().iter() {
+                    page.set_families(chips.selected());
+                }
+            }
+        ));
+        imp.family_chips.replace(Some(family_chips.clone()));
+        self.update_family_counts(0);
+
+        let chips_scroller = gtk::ScrolledWindow::new();
+        chips_scroller.add_css_class("no-scrollbar");
+        chips_scroller.set_policy(gtk::PolicyType::Automatic, gtk::PolicyType::Never);
+        chips_scroller.set_margin_top(8);
+        chips_scroller.set_margin_bottom(4);
+        // Leave room for the floating menu and close buttons
+        chips_scroller.set_margin_end(FLOATING_BUTTONS_WIDTH);
+        chips_scroller.set_child(Some(&family_chips));
 
         // Connect carousel page change to show toast
         carousel.connect_page_changed(glib::clone!(
@@ -103,17 +820,20 @@ impl LonWindow {
             self,
             move |carousel, index| {
                 let imp = window.imp();
+                window.update_family_counts(index);
 
                 // Dismiss previous toast
                 if let Some(old_toast) = imp.section_toast.borrow().as_ref() {
                     old_toast.dismiss();
                 }
 
-                let name = if index == 0 { "TCX" } else { "Solid Coated" };
+                let Some(name) = imp.pages.borrow().get(index as usize).map(|p| p.title()) else {
+                    return;
+                };
                 let mut parent = carousel.parent();
                 while let Some(p) = parent {
                     if let Ok(overlay) = p.clone().downcast::<adw::ToastOverlay>() {
-                        let toast = adw::Toast::new(name);
+                        let toast = adw::Toast::new(&name);
                         toast.set_timeout(1);
                         overlay.add_toast(toast.clone());
                         imp.section_toast.replace(Some(toast));
@@ -136,14 +856,72 @@ impl LonWindow {
         indicators.set_margin_bottom(12);
         carousel_overlay.add_overlay(&indicators);
 
+        // Search by name or hex, shared by every library page
+        let search_entry = gtk::SearchEntry::new();
+        search_entry.set_placeholder_text(Some("Search colours or tag:…"));
+        search_entry.set_hexpand(true);
+        search_entry.connect_search_changed(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |entry| {
+                for page in window.imp().pages.borrow().iter() {
+                    page.set_search(&entry.text());
+                }
+            }
+        ));
+
+        let search_bar = gtk::SearchBar::new();
+        search_bar.set_child(Some(&search_entry));
+        search_bar.connect_entry(&search_entry);
+        search_bar.connect_search_mode_enabled_notify(glib::clone!(
+            #[weak]
+            search_entry,
+            move |bar| {
+                if !bar.is_search_mode() {
+                    search_entry.set_text("");
+                }
+            }
+        ));
+        imp.search_bar.replace(Some(search_bar.clone()));
+        let _ = imp.search_entry.set(search_entry.clone());
+
+        let content = gtk::Box::new(gtk::Orientation::Vertical, 0);
+        content.append(&search_bar);
+        content.append(&chips_scroller);
+        content.append(&carousel_overlay);
+
+        // User palettes, shown in place of the libraries
+        let palette_browser = PaletteBrowser::new(db.clone());
+        palette_browser.connect_color_activated(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_, color_obj| window.show_color_detail(&color_obj.color())
+        ));
+        palette_browser.connect_changed(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_| window.update_palette_menus()
+        ));
+        let _ = imp.palette_browser.set(palette_browser.clone());
+
+        let view_stack = gtk::Stack::new();
+        view_stack.set_transition_type(gtk::StackTransitionType::Crossfade);
+        view_stack.add_named(&content, Some("libraries"));
+        view_stack.add_named(&palette_browser, Some("palettes"));
+        let _ = imp.view_stack.set(view_stack.clone());
+
         // Create bottom sheet
         let bottom_sheet = adw::BottomSheet::new();
-        bottom_sheet.set_content(Some(&carousel_overlay));
+        bottom_sheet.set_content(Some(&view_stack));
         bottom_sheet.set_show_drag_handle(false);
 
         // Create detail panel for the sheet
         let detail_panel = ColorDetailPanel::new();
-        bottom_sheet.set_sheet(Some(&detail_panel));
+        let detail_scroller = gtk::ScrolledWindow::new();
+        detail_scroller.set_policy(gtk::PolicyType::Never, gtk::PolicyType::Automatic);
+        detail_scroller.set_propagate_natural_height(true);
+        detail_scroller.set_child(Some(&detail_panel));
+        bottom_sheet.set_sheet(Some(&detail_scroller));
 
         imp.bottom_sheet.replace(Some(bottom_sheet.clone()));
         imp.detail_panel.replace(Some(detail_panel));
@@ -159,6 +937,23 @@ impl LonWindow {
         btn_box.set_margin_top(8);
         btn_box.set_margin_end(8);
 
+        // Switch between the libraries and the palettes
+        let palettes_btn = gtk::ToggleButton::new();
+        palettes_btn.set_icon_name("view-list-symbolic");
+        palettes_btn.set_tooltip_text(Some("Palettes"));
+        palettes_btn.set_action_name(Some("win.show-palettes"));
+        palettes_btn.add_css_class("close-btn");
+        palettes_btn.add_css_class("circular");
+        btn_box.append(&palettes_btn);
+
+        // Main menu
+        let menu_btn = gtk::MenuButton::new();
+        menu_btn.set_icon_name("open-menu-symbolic");
+        menu_btn.set_menu_model(Some(&Self::main_menu()));
+        menu_btn.add_css_class("close-btn");
+        menu_btn.add_css_class("circular");
+        btn_box.append(&menu_btn);
+
         // Close button
         let close_btn = gtk::Button::from_icon_name("window-close-symbolic");
         close_btn.add_css_class("close-btn");
@@ -169,7 +964,9 @@ impl LonWindow {
             move |btn| {
                 if bottom_sheet.is_open() {
                     bottom_sheet.set_open(false);
-                } else if let Some(window) = btn.root().and_then(|r| r.downcast::<gtk::Window>().ok()) {
+                } else if let Some(window) =
+                    btn.root().and_then(|r| r.downcast::<gtk::Window>().ok())
+                {
                     window.close();
                 }
             }
@@ -181,93 +978,938 @@ impl LonWindow {
         let toast_overlay = adw::ToastOverlay::new();
         toast_overlay.set_child(Some(&overlay));
         self.set_content(Some(&toast_overlay));
-    }
+        imp.toast_overlay.replace(Some(toast_overlay));
+
+        self.setup_drop_target();
+        self.bind_settings();
+        self.load_custom_colors();
+        self.load_annotations();
+        self.load_favourites();
+        self.update_palette_menus();
+        self.load_history();
 
-    fn create_grid_view(&self, db: &ColorDatabase, library: ColorLibrary) -> gtk::ScrolledWindow {
-        let scrolled = gtk::ScrolledWindow::new();
-        scrolled.add_css_class("no-scrollbar");
-        scrolled.set_margin_bottom(1);
-        scrolled.set_hexpand(true);
-        scrolled.set_vexpand(true);
+        // Start on the preferred library
+        let default_library = ColorLibrary::from_id(&self.settings().string("default-library"));
+        let default_page = imp
+            .pages
+            .borrow()
+            .iter()
+            .position(|page| page.library().is_some() && page.library() == default_library);
+        if let Some(index) = default_page.filter(|index| *index > 0) {
+            carousel.scroll_to(&carousel.nth_page(index as u32), false);
+        }
+    }
 
-        // Create and populate base model
-        let base_model = gio::ListStore::new::<ColorObject>();
-        let mut colors: Vec<_> = db.get_library(library).to_vec();
-        colors.sort_by(|a, b| {
-            a.hsl.h.partial_cmp(&b.hsl.h).unwrap_or(std::cmp::Ordering::Equal)
+    /// Accept colours, hex text and image files dropped from other apps.
+    fn setup_drop_target(&self) {
+        let target = gtk::DropTarget::new(glib::Type::INVALID, gdk::DragAction::COPY);
+        target.set_types(&[
+            gdk::RGBA::static_type(),
+            gdk::FileList::static_type(),
+            String::static_type(),
+        ]);
+        // Our own swatches are dragged out of the window, not back into it
+        target.connect_accept(|target, drop| {
+            drop.drag().is_none()
+                && target
+                    .formats()
+                    .is_some_and(|formats| formats.match_(&drop.formats()))
         });
-        for color in colors {
-            base_model.append(&ColorObject::new(color));
+        target.connect_drop(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            #[upgrade_or]
+            false,
+            move |_, value, _, _| window.handle_drop(value)
+        ));
+        self.add_controller(target);
+    }
+
+    fn handle_drop(&self, value: &glib::Value) -> bool {
+        if let Ok(rgba) = value.get::<gdk::RGBA>() {
+            let channel = |c: f32| (c.clamp(0.0, 1.0) * 255.0).round() as u8;
+            self.show_matches(Rgb {
+                r: channel(rgba.red()),
+                g: channel(rgba.green()),
+                b: channel(rgba.blue()),
+            });
+            return true;
+        }
+
+        if let Ok(files) = value.get::<gdk::FileList>() {
+            let Some(file) = files.files().into_iter().next() else {
+                return false;
+            };
+            glib::spawn_future_local(glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                async move {
+                    if let Some((name, texture)) = window.load_image(&file).await {
+                        window.show_image_palette(&name, &texture);
+                    }
+                }
+            ));
+            return true;
+        }
+
+        if let Ok(text) = value.get::<String>() {
+            return match hex_to_rgb(text.trim()) {
+                Some(rgb) => {
+                    self.show_matches(rgb);
+                    true
+                }
+                None => {
+                    self.show_toast("Dropped text is not a hex colour");
+                    false
+                }
+            };
         }
+        false
+    }
 
-        // Wrap in infinite model
-        let infinite_model = InfiniteListModel::new(base_model, 1000);
-        let middle_pos = infinite_model.middle_position();
+    fn show_toast(&self, title: &str) {
+        if let Some(overlay) = self.imp().toast_overlay.borrow().as_ref() {
+            let toast = adw::Toast::new(title);
+            toast.set_timeout(1);
+            overlay.add_toast(toast);
+        }
+    }
 
-        // Create GridView
-        let factory = gtk::SignalListItemFactory::new();
+    /// The colour shown in the detail panel, whether or not the sheet is open.
+    fn detail_color(&self) -> Option<PantoneColor> {
+        self.imp()
+            .detail_panel
+            .borrow()
+            .as_ref()
+            .and_then(|p| p.color())
+    }
 
-        factory.connect_setup(|_, list_item| {
-            let list_item = list_item.downcast_ref::<gtk::ListItem>().unwrap();
-            let swatch = ColorSwatch::new();
-            list_item.set_child(Some(&swatch));
-        });
+    fn copy_color(&self, color: &PantoneColor, format: CopyFormat) {
+        let text = format.format(color, &self.settings().string("copy-template"));
+        self.clipboard().set_text(&text);
+        self.show_toast(&format!("Copied {}", text));
+    }
 
-        factory.connect_bind(|_, list_item| {
-            let list_item = list_item.downcast_ref::<gtk::ListItem>().unwrap();
-            let color_obj = list_item
-                .item()
-                .and_downcast::<ColorObject>()
-                .expect("Item must be ColorObject");
-            let swatch = list_item
-                .child()
-                .and_downcast::<ColorSwatch>()
-                .expect("Child must be ColorSwatch");
-            swatch.set_color(&color_obj.color());
-            swatch.set_tooltip_text(Some(&color_obj.color().name));
-        });
+    fn edit_copy_template(&self) {
+        let entry = gtk::Entry::new();
+        entry.set_text(&self.settings().string("copy-template"));
+        entry.set_activates_default(true);
+
+        let dialog = adw::AlertDialog::new(
+            Some("Custom Copy Template"),
+            Some(&format!(
+                "Available placeholders: {}",
+                TEMPLATE_PLACEHOLDERS.join(" ")
+            )),
+        );
+        dialog.set_extra_child(Some(&entry));
+        dialog.add_responses(&[("cancel", "Cancel"), ("save", "Save")]);
+        dialog.set_response_appearance("save", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("save"));
+        dialog.connect_response(
+            Some("save"),
+            glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                #[weak]
+                entry,
+                move |_, _| {
+                    let settings = window.settings();
+                    let _ = settings.set_string("copy-template", &entry.text());
+                    // Saving a template implies wanting to use it
+                    let _ = settings.set_string("copy-format", CopyFormat::Template.id());
+                }
+            ),
+        );
+        dialog.present(Some(self));
+    }
 
-        let selection = gtk::SingleSelection::new(Some(infinite_model));
-        let grid_view = gtk::GridView::new(Some(selection.clone()), Some(factory));
-        grid_view.set_min_columns(3);
-        grid_view.set_max_columns(6);
-        grid_view.set_enable_rubberband(false);
-        grid_view.set_single_click_activate(true);
+    /// The colour under keyboard focus in the grid, or the one open in the sheet.
+    fn focused_color(&self) -> Option<PantoneColor> {
+        if let Some(color) = self
+            .focus()
+            .and_downcast::<ColorSwatch>()
+            .and_then(|s| s.color())
+        {
+            return Some(color);
+        }
+        let imp = self.imp();
+        let sheet_open = imp
+            .bottom_sheet
+            .borrow()
+            .as_ref()
+            .is_some_and(|s| s.is_open());
+        if sheet_open {
+            imp.detail_panel.borrow().as_ref().and_then(|p| p.color())
+        } else {
+            None
+        }
+    }
 
-        grid_view.connect_activate(glib::clone!(
+    fn pick_color(&self) {
+        let Some(connection) = self.application().and_then(|app| app.dbus_connection()) else {
+            self.show_toast("Screen picking needs a session bus");
+            return;
+        };
+        portal::pick_color(
+            &connection,
+            "",
+            glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |result| match result {
+                    Ok(rgb) => window.show_matches(rgb),
+                    Err(PortalError::Cancelled) => {}
+                    Err(err) => window.show_toast(&format!("Could not pick a colour: {}", err)),
+                }
+            ),
+        );
+    }
+
+    /// Ask for an image file, then hand it to `tool`.
+    fn open_image(&self, tool: ImageTool) {
+        let filter = gtk::FileFilter::new();
+        filter.set_name(Some("Images"));
+        for mime_type in ["image/png", "image/jpeg", "image/webp"] {
+            filter.add_mime_type(mime_type);
+        }
+        let filters = gio::ListStore::new::<gtk::FileFilter>();
+        filters.append(&filter);
+        let file_dialog = gtk::FileDialog::builder()
+            .title("Open Image")
+            .filters(&filters)
+            .default_filter(&filter)
+            .build();
+
+        glib::spawn_future_local(glib::clone!(
             #[weak(rename_to = window)]
             self,
-            move |grid, position| {
-                let model = grid.model().unwrap();
-                if let Some(item) = model.item(position) {
-                    let color_obj = item.downcast::<ColorObject>().unwrap();
-                    window.show_color_detail(&color_obj.color());
+            async move {
+                let Ok(file) = file_dialog.open_future(Some(&window)).await else {
+                    return;
+                };
+                let Some((name, texture)) = window.load_image(&file).await else {
+                    return;
+                };
+                match tool {
+                    ImageTool::Palette => window.show_image_palette(&name, &texture),
+                    ImageTool::Inspector => window.show_image_inspector(&name, &texture),
                 }
             }
         ));
+    }
+
+    /// Decode an image file, toasting on failure. Returns its display name and pixels.
+    async fn load_image(&self, file: &gio::File) -> Option<(String, gdk::Texture)> {
+        let texture = match file.load_bytes_future().await {
+            Ok((bytes, _)) => gdk::Texture::from_bytes(&bytes).map_err(|err| err.to_string()),
+            Err(err) => Err(err.to_string()),
+        };
+        let texture = match texture {
+            Ok(texture) => texture,
+            Err(err) => {
+                self.show_toast(&format!("Could not open image: {}", err));
+                return None;
+            }
+        };
+
+        let name = file
+            .basename()
+            .and_then(|path| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+            .unwrap_or_else(|| "Image".to_string());
+        Some((name, texture))
+    }
+
+    /// Show the library colours closest to an image's dominant colours.
+    fn show_image_palette(&self, name: &str, texture: &gdk::Texture) {
+        let Some(db) = self.imp().db.get().cloned() else {
+            return;
+        };
+        let dialog = PaletteDialog::new(name, texture, db, self.delta_e());
+        dialog.connect_color_activated(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_, color_obj| window.show_color_detail(&color_obj.color())
+        ));
+        dialog.present(Some(self));
+    }
+
+    fn show_image_inspector(&self, name: &str, texture: &gdk::Texture) {
+        let Some(db) = self.imp().db.get().cloned() else {
+            return;
+        };
+        let dialog = ImageInspector::new(name, texture, db, self.delta_e());
+        dialog.connect_color_activated(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_, color_obj| window.show_color_detail(&color_obj.color())
+        ));
+        dialog.present(Some(self));
+    }
+
+    /// The comparison survives closing the dialog, so pins accumulate.
+    fn compare_dialog(&self) -> Option<&CompareDialog> {
+        let imp = self.imp();
+        if imp.compare_dialog.get().is_none() {
+            let db = imp.db.get()?.clone();
+            let _ = imp
+                .compare_dialog
+                .set(CompareDialog::new(db, self.delta_e()));
+        }
+        imp.compare_dialog.get()
+    }
+
+    fn compare_color(&self, color: &PantoneColor) {
+        let Some(dialog) = self.compare_dialog() else {
+            return;
+        };
+        if !dialog.pin(&color.name, color.rgb) {
+            self.show_toast("Compare holds up to four colours");
+            return;
+        }
+        if let Some(overlay) = self.imp().toast_overlay.borrow().as_ref() {
+            let toast = adw::Toast::builder()
+                .title(format!("Pinned for comparison ({})", dialog.n_colors()))
+                .button_label("Compare")
+                .action_name("win.show-compare")
+                .timeout(3)
+                .build();
+            overlay.add_toast(toast);
+        }
+    }
+
+    fn show_compare(&self) {
+        if let Some(dialog) = self.compare_dialog() {
+            if dialog.parent().is_none() {
+                dialog.present(Some(self));
+            }
+        }
+    }
+
+    /// Show the nearest library colours to an arbitrary colour.
+    fn show_matches(&self, rgb: Rgb) {
+        let Some(db) = self.imp().db.get() else {
+            return;
+        };
+        let formula = self.delta_e();
+        let dialog = MatchesDialog::new(rgb, db.nearest(&rgb, formula, 12), formula);
+        dialog.connect_color_activated(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |_, color_obj| window.show_color_detail(&color_obj.color())
+        ));
+        dialog.present(Some(self));
+    }
+
+    fn step_library(&self, delta: i32) {
+        let Some(carousel) = self.imp().carousel.borrow().clone() else {
+            return;
+        };
+        let n_pages = carousel.n_pages() as i32;
+        if n_pages == 0 {
+            return;
+        }
+        let current = carousel.position().round() as i32;
+        let target = (current + delta).clamp(0, n_pages - 1);
+        carousel.scroll_to(&carousel.nth_page(target as u32), true);
+    }
+
+    fn update_family_counts(&self, page: u32) {
+        let imp = self.imp();
+        let Some(counts) = imp
+            .pages
+            .borrow()
+            .get(page as usize)
+            .map(|p| p.family_counts())
+        else {
+            return;
+        };
+        if let Some(chips) = imp.family_chips.borrow().as_ref() {
+            chips.set_counts(&counts);
+        }
+    }
+
+    fn set_palettes_visible(&self, visible: bool) {
+        if let Some(action) = self.lookup_action("show-palettes") {
+            action.change_state(&visible.to_variant());
+        }
+        if let Some(stack) = self.imp().view_stack.get() {
+            stack.set_visible_child_name(if visible { "palettes" } else { "libraries" });
+        }
+    }
+
+    /// Offer the current palettes in every "Add to Palette" menu.
+    fn update_palette_menus(&self) {
+        let imp = self.imp();
+        let Some(browser) = imp.palette_browser.get() else {
+            return;
+        };
+        let names = browser.palette_names();
+        for page in imp.pages.borrow().iter() {
+            page.set_palette_names(names.clone());
+        }
+        if let Some(panel) = imp.detail_panel.borrow().as_ref() {
+            panel.set_palette_names(names);
+        }
+    }
+
+    /// The library colour `key` refers to, or the one in the sheet if it is a custom colour.
+    fn find_color(&self, key: &ColorKey) -> Option<PantoneColor> {
+        self.imp()
+            .db
+            .get()
+            .and_then(|db| db.find(key))
+            .or_else(|| self.detail_color().filter(|color| color.key() == *key))
+    }
+
+    fn load_favourites(&self) {
+        let favourites = storage::load(FAVOURITES_FILE, Favourites::from_json);
+        self.imp().favourites.replace(favourites);
+        self.update_favourites();
+    }
+
+    fn toggle_favourite(&self, key: &ColorKey) {
+        let starred = self.imp().favourites.borrow_mut().toggle(key);
+        match self.save_favourites() {
+            Err(err) => self.show_toast(&format!("Could not save favourites: {}", err)),
+            Ok(()) if starred => self.show_toast("Added to Favourites"),
+            Ok(()) => self.show_toast("Removed from Favourites"),
+        }
+        self.update_favourites();
+    }
+
+    fn save_favourites(&self) -> Result<(), String> {
+        self.imp()
+            .favourites
+            .borrow()
+            .to_json()
+            .map_err(|err| err.to_string())
+            .and_then(|json| storage::write(FAVOURITES_FILE, &json).map_err(|err| err.to_string()))
+    }
+
+    /// Push the starred colours to every page and the detail panel.
+    fn update_favourites(&self) {
+        let imp = self.imp();
+        let Some(db) = imp.db.get() else {
+            return;
+        };
+        let favourites = imp.favourites.borrow();
+        let starred: HashSet<ColorKey> = favourites.keys().iter().cloned().collect();
+        for page in imp.pages.borrow().iter() {
+            page.set_starred(starred.clone());
+        }
+        if let Some(page) = imp.favourites_page.get() {
+            let colors: Vec<PantoneColor> = favourites
+                .keys()
+                .iter()
+                .filter_map(|key| db.find(key))
+                .collect();
+            page.set_colors(&colors);
+        }
+        if let Some(panel) = imp.detail_panel.borrow().as_ref() {
+            let key = panel.color().map(|color| color.key());
+            panel.set_starred(key.is_some_and(|key| favourites.contains(&key)));
+        }
+        drop(favourites);
+
+        if let Some(carousel) = imp.carousel.borrow().as_ref() {
+            self.update_family_counts(carousel.position().round() as u32);
+        }
+    }
 
-        // Scroll to middle after GridView is mapped
-        grid_view.connect_map(move |grid| {
-            let grid = grid.clone();
-            let selection = selection.clone();
-            glib::timeout_add_local_once(std::time::Duration::from_millis(100), move || {
-                selection.set_selected(middle_pos);
-                grid.scroll_to(middle_pos, gtk::ListScrollFlags::FOCUS, None);
-                grid.queue_draw();
+    fn load_custom_colors(&self) {
+        let custom_colors = storage::load(CUSTOM_COLORS_FILE, CustomLibrary::from_json);
+        self.imp().custom_colors.replace(custom_colors);
+        self.update_custom_colors();
+    }
+
+    fn save_custom_colors(&self) {
+        let saved = self
+            .imp()
+            .custom_colors
+            .borrow()
+            .to_json()
+            .map_err(|err| err.to_string())
+            .and_then(|json| {
+                storage::write(CUSTOM_COLORS_FILE, &json).map_err(|err| err.to_string())
             });
+        if let Err(err) = saved {
+            self.show_toast(&format!("Could not save custom colours: {}", err));
+        }
+    }
+
+    /// Push the Custom library to the database, its page, favourites and the sheet.
+    fn update_custom_colors(&self) {
+        let imp = self.imp();
+        let colors = imp.custom_colors.borrow().to_colors();
+        if let Some(page) = imp.custom_page.get() {
+            page.set_colors(&colors);
+        }
+        if let Some(db) = imp.db.get() {
+            db.set_custom_colors(colors);
+        }
+        self.update_favourites();
+        if let (Some(color), Some(panel)) =
+            (self.detail_color(), imp.detail_panel.borrow().as_ref())
+        {
+            self.update_custom_details(panel, &color);
+        }
+    }
+
+    /// Add a colour to the Custom library, or edit the one called `name`.
+    fn edit_custom_color(&self, name: Option<&str>) {
+        let imp = self.imp();
+        let Some(db) = imp.db.get().cloned() else {
+            return;
+        };
+        let existing = name.and_then(|name| imp.custom_colors.borrow().find(name).cloned());
+        if name.is_some() && existing.is_none() {
+            return;
+        }
+        let dialog = CustomColorDialog::new(db, self.delta_e(), existing.as_ref());
+        dialog.connect_saved(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            move |dialog| {
+                if let Some(color) = dialog.custom_color() {
+                    window.store_custom_color(dialog.original_name().as_deref(), color);
+                }
+            }
+        ));
+        dialog.present(Some(self));
+    }
+
+    /// Add `color` to the Custom library, or replace `original_name` with it.
+    fn store_custom_color(&self, original_name: Option<&str>, color: CustomColor) {
+        let imp = self.imp();
+        let previous = original_name
+            .and_then(|name| imp.custom_colors.borrow().find(name).cloned())
+            .and_then(|previous| previous.to_color());
+        let stored = match original_name {
+            Some(name) => imp.custom_colors.borrow_mut().replace(name, color.clone()),
+            None => imp.custom_colors.borrow_mut().add(color.clone()),
+        };
+        if !stored {
+            self.show_toast("A custom colour already has this name");
+            return;
+        }
+        self.save_custom_colors();
+
+        // Keep a renamed colour starred
+        let custom_key = |name: &str| ColorKey::new(ColorLibrary::Custom.id(), name);
+        if let Some(name) = original_name.filter(|name| *name != color.name) {
+            let from = custom_key(name);
+            if imp.favourites.borrow().contains(&from) {
+                imp.favourites
+                    .borrow_mut()
+                    .rename(&from, &custom_key(&color.name));
+                if let Err(err) = self.save_favourites() {
+                    self.show_toast(&format!("Could not save favourites: {}", err));
+                }
+            }
+        }
+
+        // Keep its notes and tags if it was renamed
+        if let (Some(previous), Some(current)) = (&previous, color.to_color()) {
+            imp.annotations.borrow_mut().rename(
+                &AnnotationKey::for_color(previous),
+                &AnnotationKey::for_color(&current),
+            );
+            self.save_annotations();
+            self.update_annotations();
+        }
+
+        let shown = self
+            .detail_color()
+            .is_some_and(|shown| Some(shown.key()) == original_name.map(custom_key));
+        self.update_custom_colors();
+        let Some(color) = color.to_color() else {
+            return;
+        };
+        if original_name.is_none() {
+            self.show_color_detail(&color);
+        } else if shown {
+            self.display_color_detail(&color);
+        }
+    }
+
+    fn delete_custom_color(&self, name: &str) {
+        let dialog = adw::AlertDialog::new(
+            Some(&format!("Delete “{}”?", name)),
+            Some("Palettes that use the colour keep their copy of it"),
+        );
+        dialog.add_responses(&[("cancel", "Cancel"), ("delete", "Delete")]);
+        dialog.set_response_appearance("delete", adw::ResponseAppearance::Destructive);
+        dialog.set_default_response(Some("cancel"));
+        let name = name.to_string();
+        dialog.connect_response(
+            Some("delete"),
+            glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |_, _| {
+                    if window
+                        .imp()
+                        .custom_colors
+                        .borrow_mut()
+                        .remove(&name)
+                        .is_some()
+                    {
+                        window.save_custom_colors();
+                        window.update_custom_colors();
+                    }
+                }
+            ),
+        );
+        dialog.present(Some(self));
+    }
+
+    /// Offer editing and the nearest Pantone colours when `color` is a custom one.
+    fn update_custom_details(&self, panel: &ColorDetailPanel, color: &PantoneColor) {
+        let imp = self.imp();
+        let formula = self.delta_e();
+        if color.library != ColorLibrary::Custom {
+            panel.set_custom(None);
+            panel.set_matches(Vec::new(), formula);
+            return;
+        }
+        panel.set_custom(imp.custom_colors.borrow().find(&color.name).cloned());
+        let matches = imp
+            .db
+            .get()
+            .map(|db| db.nearest(&color.rgb, formula, CUSTOM_MATCH_COUNT))
+            .unwrap_or_default();
+        panel.set_matches(matches, formula);
+    }
+
+    /// Ask for a swatch file, then offer to add it to the Custom library or
+    /// as palettes.
+    fn import_swatches(&self) {
+        let file_dialog = gtk::FileDialog::builder()
+            .title("Import Swatches")
+            .filters(&swatch_filters())
+            .build();
+
+        glib::spawn_future_local(glib::clone!(
+            #[weak(rename_to = window)]
+            self,
+            async move {
+                if let Ok(file) = file_dialog.open_future(Some(&window)).await {
+                    window.import_file(&file).await;
+                }
+            }
+        ));
+    }
+
+    /// Read a swatch file, then offer to import it; toasts if it cannot be read.
+    async fn import_file(&self, file: &gio::File) {
+        let file_name = file
+            .basename()
+            .map(|path| path.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let name = file_name
+            .rsplit_once('.')
+            .map_or(file_name.as_str(), |(stem, _)| stem)
+            .to_string();
+        let result = match SwatchFormat::from_filename(&file_name) {
+            Some(format) => match file.load_bytes_future().await {
+                Ok((bytes, _)) => format.read(&bytes, &name).map_err(|err| err.to_string()),
+                Err(err) => Err(err.to_string()),
+            },
+            None => Err("Not a supported swatch file".to_string()),
+        };
+        match result {
+            Ok(palettes) if palettes.is_empty() => {
+                self.show_toast(&format!("No colours in {}", file_name))
+            }
+            Ok(palettes) => self.confirm_import(&name, palettes),
+            Err(err) => self.show_toast(&format!("Could not open {}: {}", file_name, err)),
+        }
+    }
+
+    fn confirm_import(&self, name: &str, palettes: Vec<Palette>) {
+        let count: usize = palettes.iter().map(|palette| palette.entries.len()).sum();
+        let colours = if count == 1 {
+            "1 colour".to_string()
+        } else {
+            format!("{} colours", count)
+        };
+        let body = if palettes.len() == 1 {
+            colours
+        } else {
+            format!("{} in {} groups", colours, palettes.len())
+        };
+        let dialog = adw::AlertDialog::new(Some(&format!("Import “{}”?", name)), Some(&body));
+        dialog.add_responses(&[
+            ("cancel", "Cancel"),
+            ("custom", "Add to Custom Colours"),
+            ("palettes", "Import as Palettes"),
+        ]);
+        dialog.set_response_appearance("palettes", adw::ResponseAppearance::Suggested);
+        dialog.set_default_response(Some("palettes"));
+        dialog.set_close_response("cancel");
+        dialog.connect_response(
+            None,
+            glib::clone!(
+                #[weak(rename_to = window)]
+                self,
+                move |_, response| match response {
+                    "custom" => window.import_custom_colors(&palettes),
+                    "palettes" => {
+                        if let Some(browser) = window.imp().palette_browser.get() {
+                            browser.import_palettes(palettes.clone());
+                        }
+                        window.set_palettes_visible(true);
+                    }
+                    _ => {}
+                }
+            ),
+        );
+        dialog.present(Some(self));
+    }
+
+    /// Add every colour in `palettes` to the Custom library, renaming any
+    /// whose name is taken.
+    fn import_custom_colors(&self, palettes: &[Palette]) {
+        let mut count = 0;
+        {
+            let mut custom_colors = self.imp().custom_colors.borrow_mut();
+            for entry in palettes.iter().flat_map(|palette| &palette.entries) {
+                custom_colors.add_renamed(CustomColor {
+                    name: entry.color.name.clone(),
+                    hex: entry.color.hex.clone(),
+                    code: entry.color.code().unwrap_or_default().to_string(),
+                    notes: entry.notes.clone(),
+                });
+                count += 1;
+            }
+        }
+        self.save_custom_colors();
+        self.update_custom_colors();
+        if let Some(carousel) = self.imp().carousel.borrow().as_ref() {
+            if let Some(page) = self.imp().custom_page.get() {
+                carousel.scroll_to(page, true);
+            }
+        }
+        self.set_palettes_visible(false);
+        self.show_toast(&if count == 1 {
+            "Added 1 custom colour".to_string()
+        } else {
+            format!("Added {} custom colours", count)
         });
+    }
 
-        scrolled.set_child(Some(&grid_view));
-        scrolled
+    /// The current library page's colours as a palette named after it: all
+    /// of them, or only those left by the search and filters. Toasts and
+    /// returns `None` if there are none.
+    fn page_palette(&self, visible_only: bool) -> Option<Palette> {
+        let imp = self.imp();
+        let page = imp.carousel.borrow().as_ref().and_then(|carousel| {
+            let index = carousel.position().round() as usize;
+            imp.pages.borrow().get(index).cloned()
+        });
+        let Some(page) = page else {
+            self.show_toast("Nothing to export on this page");
+            return None;
+        };
+        let colors = if visible_only {
+            page.visible_colors()
+        } else {
+            page.colors()
+        };
+        if colors.is_empty() {
+            self.show_toast("No colours to export");
+            return None;
+        }
+        let mut palette = Palette::new(&page.title());
+        for color in colors {
+            palette.push(color, "");
+        }
+        Some(palette)
+    }
+
+    fn load_annotations(&self) {
+        let annotations = storage::load(ANNOTATIONS_FILE, Annotations::from_json);
+        self.imp().annotations.replace(annotations);
+        self.update_annotations();
+    }
+
+    fn save_annotations(&self) {
+        let saved = self
+            .imp()
+            .annotations
+            .borrow()
+            .to_json()
+            .map_err(|err| err.to_string())
+            .and_then(|json| {
+                storage::write(ANNOTATIONS_FILE, &json).map_err(|err| err.to_string())
+            });
+        if let Err(err) = saved {
+            self.show_toast(&format!("Could not save notes and tags: {}", err));
+        }
+    }
+
+    /// The notes and tags on `color`. A custom colour's notes are kept with
+    /// the colour itself, so they travel with the Custom library.
+    fn annotation(&self, color: &PantoneColor) -> Annotation {
+        let imp = self.imp();
+        let mut annotation = imp
+            .annotations
+            .borrow()
+            .get(&AnnotationKey::for_color(color))
+            .cloned()
+            .unwrap_or_default();
+        if color.library == ColorLibrary::Custom {
+            annotation.notes = imp
+                .custom_colors
+                .borrow()
+                .find(&color.name)
+                .map(|custom| custom.notes.clone())
+                .unwrap_or_default();
+        }
+        annotation
+    }
+
+    /// Change the notes on the colour in the sheet.
+    fn set_notes(&self, notes: &str) {
+        let Some(color) = self.detail_color() else {
+            return;
+        };
+        if color.library != ColorLibrary::Custom {
+            self.annotate(|annotation| annotation.notes = notes.to_string());
+            return;
+        }
+        let imp = self.imp();
+        let custom = imp.custom_colors.borrow().find(&color.name).cloned();
+        let Some(mut custom) = custom else {
+            return;
+        };
+        custom.notes = notes.to_string();
+        imp.custom_colors.borrow_mut().replace(&color.name, custom);
+        self.save_custom_colors();
+        self.update_custom_colors();
+        self.update_annotations();
+    }
+
+    /// Change the notes or tags on the colour in the sheet.
+    fn annotate(&self, f: impl FnOnce(&mut Annotation)) {
+        let Some(color) = self.detail_color() else {
+            return;
+        };
+        self.imp()
+            .annotations
+            .borrow_mut()
+            .update(&AnnotationKey::for_color(&color), f);
+        self.save_annotations();
+        self.update_annotations();
+    }
+
+    /// Push the tags to every page for searching, and the shown colour's
+    /// annotation to the sheet.
+    fn update_annotations(&self) {
+        let imp = self.imp();
+        let tags = imp.annotations.borrow().tags_by_key();
+        for page in imp.pages.borrow().iter() {
+            page.set_tags(tags.clone());
+        }
+        if let (Some(color), Some(panel)) =
+            (self.detail_color(), imp.detail_panel.borrow().as_ref())
+        {
+            panel.set_annotation(self.annotation(&color));
+        }
     }
 
+    /// Search the libraries for colours tagged `tag`.
+    fn search_tag(&self, tag: &str) {
+        let imp = self.imp();
+        self.set_palettes_visible(false);
+        if let Some(sheet) = imp.bottom_sheet.borrow().as_ref() {
+            sheet.set_open(false);
+        }
+        if let Some(search_bar) = imp.search_bar.borrow().as_ref() {
+            search_bar.set_search_mode(true);
+        }
+        if let Some(entry) = imp.search_entry.get() {
+            entry.set_text(&format!("tag:\"{}\"", tag));
+            entry.set_position(-1);
+        }
+    }
+
+    /// Open `color` in the sheet, recording it in the history.
     fn show_color_detail(&self, color: &PantoneColor) {
+        let imp = self.imp();
+        imp.trail.borrow_mut().visit(color);
+        let now = glib::DateTime::now_utc()
+            .map(|now| now.to_unix())
+            .unwrap_or(0);
+        imp.history.borrow_mut().record(color, now);
+        self.save_history();
+        self.update_history();
+        self.display_color_detail(color);
+    }
+
+    /// Open `color` in the sheet without touching the history.
+    fn display_color_detail(&self, color: &PantoneColor) {
         let imp = self.imp();
         if let Some(panel) = imp.detail_panel.borrow().as_ref() {
             panel.set_color(color);
+            panel.set_starred(imp.favourites.borrow().contains(&color.key()));
+            self.update_custom_details(panel, color);
+            panel.set_annotation(self.annotation(color));
         }
         if let Some(sheet) = imp.bottom_sheet.borrow().as_ref() {
             sheet.set_open(true);
         }
+        self.update_trail_actions();
+    }
+
+    fn update_trail_actions(&self) {
+        let trail = self.imp().trail.borrow();
+        for (name, enabled) in [
+            ("history-back", trail.can_go_back()),
+            ("history-forward", trail.can_go_forward()),
+        ] {
+            if let Some(action) = self.lookup_action(name).and_downcast::<gio::SimpleAction>() {
+                action.set_enabled(enabled);
+            }
+        }
+    }
+
+    fn load_history(&self) {
+        let history = storage::load(HISTORY_FILE, History::from_json);
+        self.imp().history.replace(history);
+        self.update_history();
+        self.update_trail_actions();
+    }
+
+    fn save_history(&self) {
+        let saved = self
+            .imp()
+            .history
+            .borrow()
+            .to_json()
+            .map_err(|err| err.to_string())
+            .and_then(|json| storage::write(HISTORY_FILE, &json).map_err(|err| err.to_string()));
+        if let Err(err) = saved {
+            self.show_toast(&format!("Could not save history: {}", err));
+        }
+    }
+
+    /// Push the history to the Recent page and the sheet's strip.
+    fn update_history(&self) {
+        let imp = self.imp();
+        let history = imp.history.borrow();
+        if let Some(page) = imp.recent_page.get() {
+            page.set_entries(history.entries());
+        }
+        if let Some(panel) = imp.detail_panel.borrow().as_ref() {
+            panel.set_recent(
+                history
+                    .entries()
+                    .iter()
+                    .map(|entry| entry.color.clone())
+                    .collect(),
+            );
+        }
     }
 }